@@ -0,0 +1,70 @@
+//! Achievement badges minted by an external contract when a vault completes.
+//!
+//! The admin points `Config::badge_contract` at any contract implementing `BadgeMinter`.
+//! Every completion then calls its `mint` for the creator, with a tier derived from the
+//! creator's streak (see `tier`). Minting is best-effort: the call is made with `try_`, so a
+//! badge contract that is missing, traps or rejects the call never blocks the settlement. The
+//! outcome is reported through `badge_minted` or `badge_mint_failed`. With no badge contract
+//! configured nothing is called and nothing is emitted. Like the streak, badges are only
+//! minted for completions that count toward reputation (see `reputation::counts`).
+
+use soroban_sdk::{contractclient, Address, Env};
+
+use crate::{config, events, reputation, streak, ProductivityVault, VaultStatus};
+
+/// Interface a badge contract must expose.
+#[contractclient(name = "BadgeMinterClient")]
+pub trait BadgeMinter {
+    /// Mint the badge earned by completing `vault_id` to `to`.
+    fn mint(env: Env, to: Address, vault_id: u64, tier: u32);
+}
+
+/// Streak lengths at which the badge tier steps up: tier `n` needs a streak of at least
+/// `TIER_STREAKS[n - 1]`.
+pub const TIER_STREAKS: [u32; 4] = [1, 3, 7, 30];
+
+/// Badge tier for a creator whose streak (including this completion) is `streak`.
+pub fn tier(streak: u32) -> u32 {
+    TIER_STREAKS
+        .iter()
+        .filter(|&&needed| streak >= needed)
+        .count() as u32
+}
+
+/// Result of one mint attempt, published by `publish`.
+pub(crate) struct BadgeMint {
+    tier: u32,
+    minted: bool,
+}
+
+/// Ask the configured badge contract to mint for a just-completed `vault`. Returns `None`
+/// when there is nothing to mint (not completed, not counted, or no badge contract).
+pub(crate) fn mint(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Option<BadgeMint> {
+    if vault.status != VaultStatus::Completed || !reputation::counts(env, vault_id, vault) {
+        return None;
+    }
+    let badge_contract = config::load(env).badge_contract?;
+    let tier = tier(streak::load(env, &vault.creator).current);
+    let minted = BadgeMinterClient::new(env, &badge_contract)
+        .try_mint(&vault.creator, &(vault_id as u64), &tier)
+        .is_ok();
+    Some(BadgeMint { tier, minted })
+}
+
+pub(crate) fn publish(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    mint: Option<BadgeMint>,
+) {
+    match mint {
+        Some(BadgeMint { tier, minted: true }) => {
+            events::badge_minted(env, vault_id, &vault.creator, tier)
+        }
+        Some(BadgeMint {
+            tier,
+            minted: false,
+        }) => events::badge_mint_failed(env, vault_id, &vault.creator, tier),
+        None => {}
+    }
+}
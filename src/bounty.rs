@@ -0,0 +1,269 @@
+//! Bounty vaults: the pot goes to whoever reveals the preimage of `milestone_hash`.
+//!
+//! `create_bounty` escrows the stake with no verifier and no success destination of its own.
+//! Until the deadline anyone can `claim_bounty` with a preimage; if `hash(preimage)` matches
+//! under the bounty's `HashAlg` (see `hashing`), the claimer is paid (less the usual
+//! protocol fee) and the vault completes. After the deadline anyone can `expire_bounty` to
+//! refund the creator, cancelling the vault. The creator cannot take the pot back early:
+//! bounties reject `validate_milestone`, `release_funds`, `redirect_funds` and
+//! `cancel_vault` with `InvalidStatus`.
+//!
+//! A plain claim publishes the preimage in a pending transaction, where anyone can copy it
+//! into a claim of their own. A bounty created with `commit_reveal` closes that race: a
+//! solver first calls `commit_bounty_claim` with `claim_commitment(preimage, claimer)`,
+//! `hash(preimage || claimer XDR)`, which reveals nothing, and claims in a later ledger.
+//! A copied preimage is useless without a commitment made before it was public.
+//!
+//! Commitments live in temporary storage, one entry per claimer, for `COMMITMENT_TTL`
+//! ledgers: they never grow the contract instance, and anyone's spam simply expires. A
+//! claim consumes the claimer's own commitment; those of other solvers are worthless once
+//! the bounty settles and lapse on their own.
+
+use soroban_sdk::{
+    contractimpl, contracttype, token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env,
+};
+
+use crate::hashing::{self, HashAlg};
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    callback, category, check_new_vault, events, load_vault, settle, store_new_vault, streak,
+    yield_adapter, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    ProductivityVault, VaultStatus,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bounty {
+    /// Claims must be committed in an earlier ledger with `commit_bounty_claim`.
+    pub commit_reveal: bool,
+}
+
+/// Ledgers a claim commitment is kept for, about a day: ample time to claim in a later
+/// ledger, after which the solver has to commit again.
+pub const COMMITMENT_TTL: u32 = 17_280;
+
+/// A claimer's commitment and the ledger it was made in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimCommitment {
+    pub commitment: BytesN<32>,
+    pub ledger: u32,
+}
+
+fn load_bounty(env: &Env, vault_id: u32) -> Option<Bounty> {
+    env.storage().instance().get(&DataKey::Bounty(vault_id))
+}
+
+pub(crate) fn is_bounty(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Bounty(vault_id))
+}
+
+/// The commitment a solver makes to `preimage`: `hash(preimage || claimer.to_xdr())` with
+/// the bounty's `alg`. Binding the claimer means a copied commitment cannot be claimed by
+/// anyone else.
+pub fn claim_commitment(
+    env: &Env,
+    alg: HashAlg,
+    preimage: &Bytes,
+    claimer: &Address,
+) -> BytesN<32> {
+    let mut bytes = preimage.clone();
+    bytes.append(&claimer.clone().to_xdr(env));
+    hashing::digest(env, alg, &bytes)
+}
+
+/// An Active bounty still open for claims.
+fn load_open(env: &Env, vault_id: u32) -> Result<(ProductivityVault, Bounty), Error> {
+    let vault = load_vault(env, vault_id)?;
+    let bounty = load_bounty(env, vault_id).ok_or(Error::InvalidStatus)?;
+    match vault.status {
+        VaultStatus::Active => {}
+        _ => return Err(Error::AlreadySettled),
+    }
+    if !vault.validation_open(env.ledger().timestamp()) {
+        return Err(Error::MilestoneExpired);
+    }
+    Ok((vault, bounty))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Post a bounty of `amount` for the preimage of `milestone_hash` under `hash_alg`,
+    /// claimable until `end_timestamp`. Validates like `create_vault`.
+    pub fn create_bounty(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        amount: i128,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        commit_reveal: bool,
+        hash_alg: HashAlg,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+
+        // The destinations are never paid: claims pay the claimer, expiry the creator.
+        let vault = ProductivityVault {
+            creator: creator.clone(),
+            amount,
+            token: usdc_token.clone(),
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier: None,
+            success_destination: creator.clone(),
+            failure_destination: creator,
+            status: VaultStatus::Active,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: true,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_new_vault(&env, &usdc_token, &vault)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Bounty(vault_id), &Bounty { commit_reveal });
+        hashing::register(&env, vault_id, hash_alg);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &vault.creator,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::vault_created(&env, vault_id, &vault);
+        Ok(vault_id)
+    }
+
+    /// Commit to a solution of a `commit_reveal` bounty; `commitment` is
+    /// `claim_commitment(preimage, claimer)`. A later commitment replaces the earlier one and
+    /// restarts its one-ledger wait. Kept for `COMMITMENT_TTL` ledgers.
+    pub fn commit_bounty_claim(
+        env: Env,
+        vault_id: u32,
+        claimer: Address,
+        commitment: BytesN<32>,
+    ) -> Result<bool, Error> {
+        claimer.require_auth();
+        let (_, bounty) = load_open(&env, vault_id)?;
+        if !bounty.commit_reveal {
+            return Err(Error::InvalidStatus);
+        }
+        let key = DataKey::BountyCommitment(vault_id, claimer.clone());
+        env.storage().temporary().set(
+            &key,
+            &ClaimCommitment {
+                commitment: commitment.clone(),
+                ledger: env.ledger().sequence(),
+            },
+        );
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, COMMITMENT_TTL, COMMITMENT_TTL);
+
+        events::bounty_committed(&env, vault_id, &claimer, &commitment);
+        Ok(true)
+    }
+
+    /// Claim the pot with the preimage of `milestone_hash`. Returns `true` once paid.
+    ///
+    /// Fails with `InvalidPreimage` when `hash(preimage)` does not match (or, for
+    /// `commit_reveal` bounties, the claimer's commitment does not), `ClaimNotCommitted` for
+    /// a `commit_reveal` bounty without a live commitment from this claimer in an earlier
+    /// ledger,
+    /// `MilestoneExpired` past the deadline and `AlreadySettled` once claimed or expired.
+    pub fn claim_bounty(
+        env: Env,
+        vault_id: u32,
+        claimer: Address,
+        preimage: Bytes,
+        usdc_token: Address,
+    ) -> Result<bool, Error> {
+        claimer.require_auth();
+        let (mut vault, bounty) = load_open(&env, vault_id)?;
+        let alg = hashing::alg(&env, vault_id);
+        let key = DataKey::BountyCommitment(vault_id, claimer.clone());
+        if bounty.commit_reveal {
+            let committed: ClaimCommitment = env
+                .storage()
+                .temporary()
+                .get(&key)
+                .ok_or(Error::ClaimNotCommitted)?;
+            if committed.ledger >= env.ledger().sequence() {
+                return Err(Error::ClaimNotCommitted);
+            }
+            if committed.commitment != claim_commitment(&env, alg, &preimage, &claimer) {
+                return Err(Error::InvalidPreimage);
+            }
+        }
+        if hashing::digest(&env, alg, &preimage) != vault.milestone_hash {
+            return Err(Error::InvalidPreimage);
+        }
+
+        vault.milestone_validated = true;
+        env.storage().temporary().remove(&key);
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &vec![&env, (claimer.clone(), 1)],
+            VaultStatus::Completed,
+            claimer.clone(),
+        )?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::bounty_claimed(&env, vault_id, &claimer, &preimage);
+        events::funds_released(&env, vault_id, &claimer, &parts);
+        yield_adapter::publish(&env, vault_id, &vault);
+        streak::publish_update(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// Refund the creator of a bounty nobody claimed before its deadline, cancelling it.
+    /// Permissionless; fails with `InvalidTimestamp` before the deadline.
+    pub fn expire_bounty(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        if !is_bounty(&env, vault_id) {
+            return Err(Error::InvalidStatus);
+        }
+        if vault.status != VaultStatus::Active {
+            return Err(Error::AlreadySettled);
+        }
+        if !vault.deadline_reached(env.ledger().timestamp()) {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let refund = vec![&env, (vault.creator.clone(), 1)];
+        settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &refund,
+            VaultStatus::Cancelled,
+            env.current_contract_address(),
+        )?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::vault_cancelled(&env, vault_id, &vault.creator, vault.amount);
+        yield_adapter::publish(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// Terms of a bounty vault, or `None` for other vaults.
+    pub fn get_bounty(env: Env, vault_id: u32) -> Option<Bounty> {
+        load_bounty(&env, vault_id)
+    }
+}
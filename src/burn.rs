@@ -0,0 +1,116 @@
+//! Burning the forfeited stake of a failed vault.
+//!
+//! A vault created with `VaultOptions::burn_on_failure` destroys its stake when it fails
+//! instead of paying `failure_destination`: `redirect_funds` and `check_installment` settle
+//! the failure share to this contract and then burn it from the contract's own balance,
+//! through the token's `burn`. Not every token lets a holder burn, so a burn that fails is
+//! caught and the share accrues to the protocol treasury (`get_accrued_fees`) instead.
+//! Either way the escrow leaves the vault's accounting exactly as a redirect would.
+//!
+//! `funds_redirected` names this contract as the recipient, and `vault_forfeited` follows it
+//! with the share and whether it was burned or fell back to the treasury. A `snooze` fee,
+//! which would go to the failure destination, is burned the same way. Completions,
+//! cancellations and the failure share of a graded release pay out as usual.
+
+use soroban_sdk::{token, Address, Env};
+
+use crate::{
+    events, fees, payout::PayoutBreakdown, privacy, savings, DataKeyExt, Error, ProductivityVault,
+};
+
+/// Whether `vault_id` burns its stake on failure.
+pub(crate) fn is_burn(env: &Env, vault_id: u32) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKeyExt::BurnOnFailure(vault_id))
+}
+
+/// Record the failure mode of a new vault. A savings lock's failure pays its creator back,
+/// so it cannot burn (`InvalidSavingsLock`).
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    burn_on_failure: bool,
+) -> Result<(), Error> {
+    if !burn_on_failure {
+        return Ok(());
+    }
+    if savings::is_savings_lock(vault) {
+        return Err(Error::InvalidSavingsLock);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::BurnOnFailure(vault_id), &true);
+    Ok(())
+}
+
+/// Keep the failure mode of a renewed vault for its successor.
+pub(crate) fn carry_over(env: &Env, from: u32, to: u32) {
+    if is_burn(env, from) {
+        env.storage()
+            .instance()
+            .remove(&DataKeyExt::BurnOnFailure(from));
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::BurnOnFailure(to), &true);
+    }
+}
+
+/// Who a failure settlement of `vault_id` pays: this contract, which then burns the share
+/// with `forfeit`, for burn vaults, the failure destination otherwise.
+pub(crate) fn failure_recipient(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Address {
+    if is_burn(env, vault_id) {
+        env.current_contract_address()
+    } else {
+        vault.failure_destination.clone()
+    }
+}
+
+/// Burn the share `parts` left with this contract, or accrue it to the treasury when the
+/// token refuses. Returns the share and whether it was burned, or `None` when nothing was
+/// left to burn.
+pub(crate) fn forfeit(
+    env: &Env,
+    usdc_token: &Address,
+    parts: &PayoutBreakdown,
+) -> Result<Option<(i128, bool)>, Error> {
+    let contract = env.current_contract_address();
+    let Some((_, amount)) = parts.shares.iter().find(|(to, _)| *to == contract) else {
+        return Ok(None);
+    };
+    if amount <= 0 {
+        return Ok(None);
+    }
+    Ok(Some((amount, burn(env, usdc_token, amount)?)))
+}
+
+/// Burn `amount` of this contract's own balance, or accrue it to the treasury when the token
+/// refuses. Returns whether it was burned.
+pub(crate) fn burn(env: &Env, usdc_token: &Address, amount: i128) -> Result<bool, Error> {
+    let burned = token::Client::new(env, usdc_token)
+        .try_burn(&env.current_contract_address(), &amount)
+        .is_ok_and(|result| result.is_ok());
+    if !burned {
+        fees::accrue(env, usdc_token, amount)?;
+    }
+    Ok(burned)
+}
+
+/// Publish `vault_forfeited` for the outcome of `forfeit`.
+pub(crate) fn publish(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    forfeited: Option<(i128, bool)>,
+) {
+    if let Some((amount, burned)) = forfeited {
+        events::vault_forfeited(
+            env,
+            vault_id,
+            &vault.creator,
+            privacy::amount(vault, amount),
+            burned,
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Settlement callbacks to a contract the creator registers.
+//!
+//! A vault created with `VaultOptions::callback` notifies that contract through
+//! `SettlementListener::on_vault_settled` once it settles, after every transfer has been made.
+//! There is one callback per vault, fixed at creation. The call is best-effort, like badge
+//! minting: it is made with `try_`, so a listener that is missing, traps or rejects the call
+//! can never undo or block the payout; it only produces `callback_failed`.
+//!
+//! A listener that exhausts the transaction's resource budget cannot be caught that way and
+//! would fail the settlement. The admin can drop such a callback with `clear_callback` so the
+//! vault can settle without it.
+
+use soroban_sdk::{contractclient, contractimpl, Address, Env};
+
+use crate::{
+    config, events, load_vault, privacy, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Interface a settlement callback contract must expose.
+#[contractclient(name = "SettlementListenerClient")]
+pub trait SettlementListener {
+    /// `vault_id` settled into `status`, paying out `amount`.
+    fn on_vault_settled(env: Env, vault_id: u64, status: VaultStatus, amount: i128);
+}
+
+fn callback(env: &Env, vault_id: u32) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Callback(vault_id))
+}
+
+pub(crate) fn register(env: &Env, vault_id: u32, callback: &Option<Address>) {
+    if let Some(callback) = callback {
+        env.storage()
+            .instance()
+            .set(&DataKey::Callback(vault_id), callback);
+    }
+}
+
+/// Register a renewed vault's callback on its successor too.
+pub(crate) fn carry_over(env: &Env, from: u32, to: u32) {
+    register(env, to, &callback(env, from));
+}
+
+/// Outcome of one callback attempt, published by `publish`.
+pub(crate) struct Notification {
+    callback: Address,
+    delivered: bool,
+}
+
+/// Notify the vault's callback, if any, that `vault` just settled. Call after the transfers.
+pub(crate) fn notify(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Option<Notification> {
+    let callback = callback(env, vault_id)?;
+    let delivered = SettlementListenerClient::new(env, &callback)
+        .try_on_vault_settled(&(vault_id as u64), &vault.status, &vault.amount)
+        .is_ok();
+    Some(Notification {
+        callback,
+        delivered,
+    })
+}
+
+pub(crate) fn publish(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    notification: Option<Notification>,
+) {
+    if let Some(Notification {
+        callback,
+        delivered: false,
+    }) = notification
+    {
+        events::callback_failed(env, vault_id, &callback, vault.status);
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Settlement callback registered for a vault, if any. Always `None` for private vaults.
+    pub fn get_callback(env: Env, vault_id: u32) -> Option<Address> {
+        if privacy::is_private(&env, vault_id) {
+            return None;
+        }
+        callback(&env, vault_id)
+    }
+
+    /// Drop a vault's settlement callback. Admin only; fails with `VaultNotFound` for unknown
+    /// vaults.
+    pub fn clear_callback(env: Env, vault_id: u32) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        load_vault(&env, vault_id)?;
+        env.storage()
+            .instance()
+            .remove(&DataKey::Callback(vault_id));
+
+        events::callback_cleared(&env, vault_id, &admin);
+        Ok(())
+    }
+}
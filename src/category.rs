@@ -0,0 +1,157 @@
+//! Vault categories and per-category statistics.
+//!
+//! Every vault has a category symbol, fixed at creation. Creators pick one from the list the
+//! admin maintains with `add_category` / `remove_category`; vaults created without one, and
+//! every vault type that takes no options (recurring, group, sponsored), are filed under
+//! `DEFAULT_CATEGORY`, which is always allowed and cannot be listed or removed.
+//!
+//! `CategoryStats` are maintained as vaults move: `created` and `total_staked` when a vault
+//! is stored (and, for group vaults, as stakes are pooled), `completed` and `failed` when it
+//! settles. Removing a category only stops new vaults from using it; its statistics and the
+//! vaults already filed under it are kept.
+
+use soroban_sdk::{contractimpl, contracttype, symbol_short, Env, Symbol, Vec};
+
+use crate::{
+    config, events, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    ProductivityVault, VaultStatus,
+};
+
+/// Category of vaults created without one.
+pub const DEFAULT_CATEGORY: Symbol = symbol_short!("general");
+
+/// Upper bound on admin-listed categories, keeping `list_categories` bounded.
+pub const MAX_CATEGORIES: u32 = 32;
+
+/// Aggregates over every vault ever filed under a category.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CategoryStats {
+    pub created: u32,
+    pub completed: u32,
+    pub failed: u32,
+    /// Sum of stakes escrowed at creation, plus pooled group stakes.
+    pub total_staked: i128,
+}
+
+fn categories(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Categories)
+        .unwrap_or(Vec::new(env))
+}
+
+fn stats(env: &Env, category: &Symbol) -> CategoryStats {
+    env.storage()
+        .instance()
+        .get(&DataKey::CategoryStats(category.clone()))
+        .unwrap_or_default()
+}
+
+fn update(
+    env: &Env,
+    category: &Symbol,
+    apply: impl FnOnce(&mut CategoryStats) -> Option<()>,
+) -> Result<(), Error> {
+    let mut stats = stats(env, category);
+    apply(&mut stats).ok_or(Error::ArithmeticOverflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::CategoryStats(category.clone()), &stats);
+    Ok(())
+}
+
+/// Category for a new vault: `requested`, which must be listed, or `DEFAULT_CATEGORY`.
+pub(crate) fn resolve(env: &Env, requested: Option<Symbol>) -> Result<Symbol, Error> {
+    match requested {
+        None => Ok(DEFAULT_CATEGORY),
+        Some(category) if category == DEFAULT_CATEGORY || categories(env).contains(&category) => {
+            Ok(category)
+        }
+        Some(_) => Err(Error::UnknownCategory),
+    }
+}
+
+/// Count a newly stored vault. Called by `store_new_vault`.
+pub(crate) fn record_created(env: &Env, vault: &ProductivityVault) -> Result<(), Error> {
+    update(env, &vault.category, |stats| {
+        stats.created = stats.created.checked_add(1)?;
+        stats.total_staked = stats.total_staked.checked_add(vault.amount)?;
+        Some(())
+    })
+}
+
+/// Count `amount` pooled into an existing vault.
+pub(crate) fn record_stake(
+    env: &Env,
+    vault: &ProductivityVault,
+    amount: i128,
+) -> Result<(), Error> {
+    update(env, &vault.category, |stats| {
+        stats.total_staked = stats.total_staked.checked_add(amount)?;
+        Some(())
+    })
+}
+
+/// Count `vault`'s new terminal status. Called by `transition`.
+pub(crate) fn record_settled(env: &Env, vault: &ProductivityVault) -> Result<(), Error> {
+    match vault.status {
+        VaultStatus::Completed => update(env, &vault.category, |stats| {
+            stats.completed = stats.completed.checked_add(1)?;
+            Some(())
+        }),
+        VaultStatus::Failed => update(env, &vault.category, |stats| {
+            stats.failed = stats.failed.checked_add(1)?;
+            Some(())
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Allow `category` for new vaults. Admin only.
+    ///
+    /// Fails with `CategoryAlreadyListed` if it is already allowed (`DEFAULT_CATEGORY`
+    /// always is) and `TooManyCategories` once `MAX_CATEGORIES` are listed.
+    pub fn add_category(env: Env, category: Symbol) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        let mut list = categories(&env);
+        if category == DEFAULT_CATEGORY || list.contains(&category) {
+            return Err(Error::CategoryAlreadyListed);
+        }
+        if list.len() >= MAX_CATEGORIES {
+            return Err(Error::TooManyCategories);
+        }
+        list.push_back(category.clone());
+        env.storage().instance().set(&DataKey::Categories, &list);
+
+        events::category_added(&env, &admin, &category);
+        Ok(())
+    }
+
+    /// Stop new vaults from using `category`. Admin only; fails with `UnknownCategory` if it
+    /// is not listed.
+    pub fn remove_category(env: Env, category: Symbol) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        let mut list = categories(&env);
+        let index = list
+            .first_index_of(&category)
+            .ok_or(Error::UnknownCategory)?;
+        list.remove(index);
+        env.storage().instance().set(&DataKey::Categories, &list);
+
+        events::category_removed(&env, &admin, &category);
+        Ok(())
+    }
+
+    /// Admin-listed categories in the order they were added, without `DEFAULT_CATEGORY`.
+    pub fn list_categories(env: Env) -> Vec<Symbol> {
+        categories(&env)
+    }
+
+    /// Statistics of `category`; all zero for a category no vault has used.
+    pub fn get_category_stats(env: Env, category: Symbol) -> CategoryStats {
+        stats(&env, &category)
+    }
+}
@@ -0,0 +1,142 @@
+//! Admin-curated registry of charity addresses for failure destinations.
+//!
+//! Each charity gets a stable index when added; removing it empties its slot without shifting
+//! the others, so indexes that wallets have cached keep meaning the same charity. A vault
+//! copies its failure destination at creation, so removing a charity later never affects
+//! vaults already pointing at it.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::{
+    config, events, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    VaultOptions,
+};
+
+/// Most entries `list_charities` returns per call.
+pub const MAX_CHARITY_PAGE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Charity {
+    pub address: Address,
+    /// Hash of the off-chain description (name, website, mission).
+    pub metadata_hash: BytesN<32>,
+}
+
+/// Registry index of `address` if it is a registered charity.
+pub(crate) fn index_of(env: &Env, address: &Address) -> Option<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::CharityIndex(address.clone()))
+}
+
+fn load(env: &Env, index: u32) -> Option<Charity> {
+    env.storage().instance().get(&DataKey::Charity(index))
+}
+
+fn slot_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CharityCount)
+        .unwrap_or(0)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Register `address` as a charity. Admin only; returns its index.
+    ///
+    /// Fails with `CharityAlreadyRegistered` if it is already listed.
+    pub fn add_charity(
+        env: Env,
+        address: Address,
+        metadata_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        let admin = config::require_admin(&env)?;
+        if index_of(&env, &address).is_some() {
+            return Err(Error::CharityAlreadyRegistered);
+        }
+        let index = slot_count(&env);
+        let next = index.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let charity = Charity {
+            address: address.clone(),
+            metadata_hash,
+        };
+        env.storage().instance().set(&DataKey::CharityCount, &next);
+        env.storage()
+            .instance()
+            .set(&DataKey::Charity(index), &charity);
+        env.storage()
+            .instance()
+            .set(&DataKey::CharityIndex(address), &index);
+
+        events::charity_added(&env, &admin, index, &charity);
+        Ok(index)
+    }
+
+    /// Remove `address` from the registry. Admin only; fails with `CharityNotFound` if it is
+    /// not listed. Existing vaults keep paying it on failure.
+    pub fn remove_charity(env: Env, address: Address) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        let index = index_of(&env, &address).ok_or(Error::CharityNotFound)?;
+        env.storage().instance().remove(&DataKey::Charity(index));
+        env.storage()
+            .instance()
+            .remove(&DataKey::CharityIndex(address.clone()));
+
+        events::charity_removed(&env, &admin, index, &address);
+        Ok(())
+    }
+
+    /// Registered charities with an index in `[start, start + limit)`, as `(index, charity)`
+    /// in index order. Removed slots are skipped and `limit` is capped at `MAX_CHARITY_PAGE`.
+    pub fn list_charities(env: Env, start: u32, limit: u32) -> Vec<(u32, Charity)> {
+        let end = start
+            .saturating_add(limit.min(MAX_CHARITY_PAGE))
+            .min(slot_count(&env));
+        let mut page = Vec::new(&env);
+        for index in start..end {
+            if let Some(charity) = load(&env, index) {
+                page.push_back((index, charity));
+            }
+        }
+        page
+    }
+
+    /// Charity registered at `index`, or `None` if the slot is empty.
+    pub fn get_charity(env: Env, index: u32) -> Option<Charity> {
+        load(&env, index)
+    }
+
+    /// `create_vault` with the failure destination resolved from the charity registry.
+    ///
+    /// Fails with `CharityNotFound` if nothing is registered at `charity_index`, and otherwise
+    /// exactly like `create_vault`.
+    pub fn create_vault_with_charity(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        amount: i128,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        verifier: Option<Address>,
+        success_destination: Address,
+        charity_index: u32,
+        options: VaultOptions,
+    ) -> Result<u32, Error> {
+        let charity = load(&env, charity_index).ok_or(Error::CharityNotFound)?;
+        Self::create_vault(
+            env,
+            usdc_token,
+            creator,
+            amount,
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier,
+            success_destination,
+            charity.address,
+            options,
+        )
+    }
+}
@@ -0,0 +1,107 @@
+//! Transferable claims on a vault's success payout.
+//!
+//! A vault created with `VaultOptions::transferable_claim` has no fixed success destination.
+//! Instead it records a claim owner, initially the chosen `success_destination`, who can sell
+//! or give the right to the payout with `transfer_claim`. Only the payout moves: the creator
+//! still does the work, and failure and cancellation go where they always do. Settlement pays
+//! whoever owns the claim at release time, so the vault's `success_destination` follows the
+//! owner while the vault is unsettled and `update_success_destination` is disabled.
+//!
+//! Claims on failed or cancelled vaults are worthless, but they can still be transferred.
+//! `get_claim` reports the vault status next to the owner so buyers can check first.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::{
+    events, is_terminal, load_vault, privacy, savings, validate_destination, DataKey,
+    DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// A vault's success claim as returned by `get_claim`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claim {
+    pub owner: Address,
+    /// Status of the vault; only a claim on an Active vault can still pay out.
+    pub status: VaultStatus,
+}
+
+fn owner(env: &Env, vault_id: u32) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ClaimOwner(vault_id))
+}
+
+/// Whether `vault_id` was created with a transferable claim.
+pub(crate) fn is_transferable(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::ClaimOwner(vault_id))
+}
+
+/// Record the success destination of a new vault as its claim owner when requested. A
+/// savings lock pays its creator back and has no claim to sell (`InvalidSavingsLock`).
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    transferable: bool,
+) -> Result<(), Error> {
+    if !transferable {
+        return Ok(());
+    }
+    if savings::is_savings_lock(vault) {
+        return Err(Error::InvalidSavingsLock);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::ClaimOwner(vault_id), &vault.success_destination);
+    Ok(())
+}
+
+/// Move the claim of a renewed vault to its successor.
+pub(crate) fn carry_over(env: &Env, from: u32, to: u32) {
+    if let Some(owner) = owner(env, from) {
+        env.storage().instance().remove(&DataKey::ClaimOwner(from));
+        env.storage()
+            .instance()
+            .set(&DataKey::ClaimOwner(to), &owner);
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Claim owner hands the right to the success payout to `new_owner`. Allowed whatever
+    /// the vault's status; an unsettled vault will pay `new_owner` on release.
+    ///
+    /// Fails with `InvalidStatus` for vaults without a transferable claim and
+    /// `InvalidDestination` when `new_owner` is this contract.
+    pub fn transfer_claim(env: Env, vault_id: u32, new_owner: Address) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        let previous = owner(&env, vault_id).ok_or(Error::InvalidStatus)?;
+        previous.require_auth();
+        validate_destination(&env, &new_owner)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ClaimOwner(vault_id), &new_owner);
+        if !is_terminal(vault.status) {
+            vault.success_destination = new_owner.clone();
+            env.storage()
+                .instance()
+                .set(&DataKey::Vault(vault_id), &vault);
+        }
+
+        events::claim_transferred(&env, vault_id, &previous, &new_owner);
+        Ok(true)
+    }
+
+    /// Current claim owner and vault status, or `None` for vaults without a transferable
+    /// claim. Private vaults report this contract's address as the owner.
+    pub fn get_claim(env: Env, vault_id: u32) -> Result<Option<Claim>, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        Ok(owner(&env, vault_id).map(|owner| Claim {
+            owner: match vault.visibility {
+                privacy::Visibility::Public => owner,
+                privacy::Visibility::Private => env.current_contract_address(),
+            },
+            status: vault.status,
+        }))
+    }
+}
@@ -0,0 +1,119 @@
+//! Contract admin and tunable configuration.
+//!
+//! The admin is set by the constructor when the contract is deployed, so no caller can claim
+//! the role on a fresh instance; the admin can then replace the `Config` with `set_config`.
+//! Until a config is set every read falls back to `Config::default()`, so a deployment that
+//! never configures anything behaves exactly like one with the defaults.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::payout::BPS_DENOMINATOR;
+use crate::reputation::ReputationWeights;
+use crate::yield_adapter::ShortfallPolicy;
+use crate::{events, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error};
+
+/// Values the admin can tune without an upgrade.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Config {
+    /// A completed vault continues its creator's streak only if it started at most this
+    /// many seconds after the previous completion. `0` disables the window.
+    pub streak_window: u64,
+    /// Vaults staking less than this earn and lose no reputation, streak or leaderboard
+    /// standing.
+    pub min_amount: i128,
+    /// Scoring weights for `reputation`.
+    pub reputation: ReputationWeights,
+    /// `badge::BadgeMinter` contract called on every completion; `None` mints nothing.
+    pub badge_contract: Option<Address>,
+    /// Protocol fee withheld from completing settlements, in bps.
+    pub fee_bps: u32,
+    /// Share of the protocol fee paid to a vault's referrer, in bps of the fee.
+    pub referral_bps: u32,
+    /// Share of a completed vault's adapter yield paid to its creator, in bps of the yield.
+    pub creator_yield_bps: u32,
+    /// How settlements treat an adapter returning less than was deposited.
+    pub yield_shortfall: ShortfallPolicy,
+    /// Longest single `snooze`, in seconds. `0` disables snoozing.
+    pub max_snooze_seconds: u64,
+    /// Snooze fee, in bps of the escrow, paid to the failure destination.
+    pub snooze_fee_bps: u32,
+    /// Penalty for cancelling a savings lock before its deadline, in bps of the escrow,
+    /// accrued to the protocol. `0` forbids early withdrawal.
+    pub early_withdrawal_bps: u32,
+    /// Share of a vault's verifiers, in bps and rounded up, whose approvals unlock it in an
+    /// emergency (see `emergency`). A vault has a single verifier, so any non-zero rate means
+    /// its one approval; `0` disables emergency unlocks.
+    pub emergency_quorum_bps: u32,
+    /// Seconds an emergency approval stays live. `0` keeps approvals until revoked.
+    pub emergency_approval_window: u64,
+    /// Proofs an unvalidated vault with a verifier must have submitted before its deadline
+    /// to be released once the deadline passes, i.e. on a verifier timeout. Vaults without
+    /// a verifier, such as savings locks, are exempt. `0` requires none.
+    pub timeout_min_proofs: u32,
+    /// Oldest oracle price, in seconds, that quote-denominated vaults are created or settled
+    /// at (see `oracle`). `0` accepts any age.
+    pub max_price_age: u64,
+}
+
+/// Current configuration, or the defaults when none was set.
+pub(crate) fn load(env: &Env) -> Config {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config)
+        .unwrap_or_default()
+}
+
+pub(crate) fn require_admin(env: &Env) -> Result<Address, Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Set the contract admin. Runs once, as part of the deployment.
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Replace the configuration. Admin only; fails with `InvalidAmount` for a negative
+    /// `min_amount` or a non-positive reputation `stake_unit`, and `InvalidBps` for a fee,
+    /// referral, yield, snooze, early-withdrawal or emergency quorum rate above 100%.
+    pub fn set_config(env: Env, config: Config) -> Result<(), Error> {
+        let admin = require_admin(&env)?;
+        if config.min_amount < 0 || config.reputation.stake_unit <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if [
+            config.fee_bps,
+            config.referral_bps,
+            config.creator_yield_bps,
+            config.snooze_fee_bps,
+            config.early_withdrawal_bps,
+            config.emergency_quorum_bps,
+        ]
+        .iter()
+        .any(|&bps| bps > BPS_DENOMINATOR)
+        {
+            return Err(Error::InvalidBps);
+        }
+        env.storage().instance().set(&DataKey::Config, &config);
+        events::config_updated(&env, &admin, &config);
+        Ok(())
+    }
+
+    /// Current configuration (the defaults if none was set).
+    pub fn get_config(env: Env) -> Config {
+        load(&env)
+    }
+
+    /// Contract admin set at deployment.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Admin)
+    }
+}
@@ -0,0 +1,119 @@
+//! Handing an active vault over to a new creator address.
+//!
+//! A creator who migrates wallets, or fears theirs is compromised, proposes the new address
+//! with `transfer_creator`, and the new address takes over with `accept_creator`. Until then
+//! nothing changes, and the current creator can re-propose to replace a mistaken address.
+//!
+//! After acceptance the vault belongs to the new address: it submits proofs, validates when
+//! there is no verifier, cancels and receives refunds. The streak follows the vault, so the
+//! settlement counts towards the new creator's streak. Reputation stays with the original
+//! address the vault was opened from, so a vault cannot be handed to a fresh wallet to launder
+//! a failure away from its history.
+//!
+//! Only Active vaults can change hands; pending group vaults and settled vaults cannot.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{
+    check_terms, events, load_vault, privacy, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Address the vault was created by, whoever holds the creator role now.
+pub(crate) fn original_creator(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::OriginalCreator(vault_id))
+        .unwrap_or_else(|| vault.creator.clone())
+}
+
+fn load_active(env: &Env, vault_id: u32) -> Result<ProductivityVault, Error> {
+    let vault = load_vault(env, vault_id)?;
+    if vault.status != VaultStatus::Active {
+        return Err(Error::VaultNotActive);
+    }
+    Ok(vault)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Creator proposes `new_creator` as the vault's creator. Replaces any earlier proposal.
+    ///
+    /// Fails with `VaultNotActive` unless the vault is Active, `InvalidCreator` for the
+    /// current creator or this contract, and with the creation-time errors (`InvalidPartner`,
+    /// `SelfReferral`) when `new_creator` is the vault's partner or referrer.
+    pub fn transfer_creator(env: Env, vault_id: u32, new_creator: Address) -> Result<bool, Error> {
+        let vault = load_active(&env, vault_id)?;
+        vault.creator.require_auth();
+
+        if new_creator == vault.creator || new_creator == env.current_contract_address() {
+            return Err(Error::InvalidCreator);
+        }
+        check_terms(
+            &env,
+            &ProductivityVault {
+                creator: new_creator.clone(),
+                ..vault.clone()
+            },
+        )?;
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingCreator(vault_id), &new_creator);
+
+        events::creator_transfer_proposed(&env, vault_id, &new_creator, &vault.creator);
+        Ok(true)
+    }
+
+    /// The proposed address accepts the creator role. Fails with `NoPendingTransfer` when
+    /// nothing was proposed and `VaultNotActive` if the vault settled in the meantime.
+    pub fn accept_creator(env: Env, vault_id: u32) -> Result<bool, Error> {
+        let mut vault = load_active(&env, vault_id)?;
+        let new_creator: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingCreator(vault_id))
+            .ok_or(Error::NoPendingTransfer)?;
+        new_creator.require_auth();
+
+        let previous = vault.creator.clone();
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::OriginalCreator(vault_id))
+        {
+            env.storage()
+                .instance()
+                .set(&DataKey::OriginalCreator(vault_id), &previous);
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingCreator(vault_id));
+        vault.creator = new_creator.clone();
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &vault);
+
+        events::creator_transferred(&env, vault_id, vault.validator(), &previous, &new_creator);
+        Ok(true)
+    }
+
+    /// Address awaiting `accept_creator` on a vault, if any. Always `None` for private vaults.
+    pub fn get_pending_creator(env: Env, vault_id: u32) -> Option<Address> {
+        if privacy::is_private(&env, vault_id) {
+            return None;
+        }
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingCreator(vault_id))
+    }
+
+    /// Address a vault was created by; differs from `creator` once the role was transferred.
+    /// Private vaults report this contract's address.
+    pub fn get_original_creator(env: Env, vault_id: u32) -> Result<Address, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        if vault.visibility == privacy::Visibility::Private {
+            return Ok(env.current_contract_address());
+        }
+        Ok(original_creator(&env, vault_id, &vault))
+    }
+}
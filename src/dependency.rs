@@ -0,0 +1,138 @@
+//! Vault dependencies: a vault that unlocks only after earlier vaults complete.
+//!
+//! Each vault carries a single milestone, so a staged plan ("milestone 3 only after 1 and
+//! 2") is a chain of vaults. `VaultOptions::depends_on` lists the earlier vaults a new one
+//! waits on, and `create_vault` rejects the list with:
+//!
+//! - `InvalidParticipants` when it is empty, longer than `MAX_DEPENDENCIES` or has duplicates;
+//! - `VaultNotFound` for a vault that does not exist yet;
+//! - `NotAuthorized` for a vault of another creator;
+//! - `DependencyNotMet` for a vault that already failed or was cancelled.
+//!
+//! Since a vault can only depend on vaults created before it, the graph is acyclic.
+//!
+//! Until every dependency is `Completed` the vault is locked: `validate_milestone`,
+//! `validate_graded` and `release_funds` fail with `DependencyNotMet`. Once a dependency
+//! fails or is cancelled the vault can never unlock. With `VaultOptions::cascade_failure`
+//! anyone may then `redirect_funds` it straight away; without it, the vault expires
+//! normally at its deadline. `get_unlock_status` exposes where a vault stands.
+
+use soroban_sdk::{contractimpl, contracttype, Env, Vec};
+
+use crate::{
+    load_vault, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    ProductivityVault, VaultStatus,
+};
+
+/// Upper bound on `depends_on`, keeping the unlock check to a bounded number of reads.
+pub const MAX_DEPENDENCIES: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dependencies {
+    /// Vaults that must complete before this one unlocks.
+    pub depends_on: Vec<u32>,
+    /// Let the vault be redirected as soon as a dependency fails.
+    pub cascade_failure: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnlockStatus {
+    /// No dependencies, or all of them completed.
+    Unlocked = 0,
+    /// Waiting on a dependency that has not settled yet.
+    Locked = 1,
+    /// A dependency failed or was cancelled; the vault can never unlock.
+    Blocked = 2,
+}
+
+fn load(env: &Env, vault_id: u32) -> Option<Dependencies> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Dependencies(vault_id))
+}
+
+/// Record `depends_on` for the new vault `vault_id`. Called by `create_vault` after
+/// `store_new_vault`.
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    depends_on: Option<Vec<u32>>,
+    cascade_failure: bool,
+) -> Result<(), Error> {
+    let Some(depends_on) = depends_on else {
+        return Ok(());
+    };
+    if depends_on.is_empty() || depends_on.len() > MAX_DEPENDENCIES {
+        return Err(Error::InvalidParticipants);
+    }
+    for (i, dependency) in depends_on.iter().enumerate() {
+        if depends_on.first_index_of(dependency) != Some(i as u32) {
+            return Err(Error::InvalidParticipants);
+        }
+        // The new vault is already stored, so it must not count as its own dependency.
+        if dependency >= vault_id {
+            return Err(Error::VaultNotFound);
+        }
+        let earlier = load_vault(env, dependency)?;
+        if earlier.creator != vault.creator {
+            return Err(Error::NotAuthorized);
+        }
+        if matches!(earlier.status, VaultStatus::Failed | VaultStatus::Cancelled) {
+            return Err(Error::DependencyNotMet);
+        }
+    }
+    env.storage().instance().set(
+        &DataKey::Dependencies(vault_id),
+        &Dependencies {
+            depends_on,
+            cascade_failure,
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn unlock_status(env: &Env, vault_id: u32) -> UnlockStatus {
+    let Some(dependencies) = load(env, vault_id) else {
+        return UnlockStatus::Unlocked;
+    };
+    let mut status = UnlockStatus::Unlocked;
+    for dependency in dependencies.depends_on.iter() {
+        match load_vault(env, dependency).map(|vault| vault.status) {
+            Ok(VaultStatus::Completed) => {}
+            Ok(VaultStatus::Active | VaultStatus::Pending) => status = UnlockStatus::Locked,
+            _ => return UnlockStatus::Blocked,
+        }
+    }
+    status
+}
+
+/// `DependencyNotMet` unless every dependency of `vault_id` has completed.
+pub(crate) fn require_unlocked(env: &Env, vault_id: u32) -> Result<(), Error> {
+    match unlock_status(env, vault_id) {
+        UnlockStatus::Unlocked => Ok(()),
+        _ => Err(Error::DependencyNotMet),
+    }
+}
+
+/// Whether `vault_id` may be redirected before its deadline: it cascades failures and a
+/// dependency failed or was cancelled.
+pub(crate) fn cascade_failed(env: &Env, vault_id: u32) -> bool {
+    load(env, vault_id).is_some_and(|dependencies| dependencies.cascade_failure)
+        && unlock_status(env, vault_id) == UnlockStatus::Blocked
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Vaults this one waits on, or `None` for vaults without dependencies.
+    pub fn get_dependencies(env: Env, vault_id: u32) -> Option<Dependencies> {
+        load(&env, vault_id)
+    }
+
+    /// Whether the vault's dependencies let it be validated and released.
+    pub fn get_unlock_status(env: Env, vault_id: u32) -> UnlockStatus {
+        unlock_status(&env, vault_id)
+    }
+}
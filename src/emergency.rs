@@ -0,0 +1,197 @@
+//! Emergency unlock: a vault's verifier can hand the stake back in exceptional
+//! circumstances.
+//!
+//! For a medical emergency or force majeure, the verifier may return the whole escrow to
+//! whoever staked it, whatever the milestone's state: every depositor gets back their own
+//! stake, so both sides of an accepted wager and every group participant are refunded. The
+//! verifier records an approval with `approve_emergency`; anyone may then call
+//! `emergency_unlock`, which cancels the vault and refunds it without any early-withdrawal
+//! penalty.
+//!
+//! This is a single-verifier override. Vaults have one verifier, so
+//! `Config::emergency_quorum_bps` only switches unlocks on (any non-zero rate) or off (zero,
+//! the default): there is no M-of-N supermajority to reach. To keep that one approval from
+//! being self-serving, only an independent verifier counts: vaults without a verifier, or
+//! whose verifier is the creator or one of the refunded depositors, cannot be unlocked this
+//! way (`InvalidStatus`).
+//!
+//! An approval lapses after `Config::emergency_approval_window` seconds (never when zero).
+//! `revoke_emergency` only works before the quorum is met, so with one verifier an approval
+//! stands until it lapses or is used.
+
+use soroban_sdk::{contractimpl, vec, Address, Env, Vec};
+
+use crate::payout::BPS_DENOMINATOR;
+use crate::{
+    callback, config, events, group, load_vault, privacy, require_unsettled, settle, wager,
+    yield_adapter, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    ProductivityVault, VaultStatus,
+};
+
+/// Whom an unlock refunds: every depositor their own stake.
+fn refunds(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Vec<(Address, i128)> {
+    wager::stakes(env, vault_id, vault)
+        .unwrap_or_else(|| group::refund_recipients(env, vault_id, vault))
+}
+
+/// Addresses whose approvals count toward a vault's emergency quorum: its verifier, unless
+/// that is the creator or someone the refund pays. At most one address.
+fn verifiers(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Vec<Address> {
+    let Some(verifier) = &vault.verifier else {
+        return Vec::new(env);
+    };
+    let refunded = refunds(env, vault_id, vault)
+        .iter()
+        .any(|(depositor, _)| depositor == *verifier);
+    if *verifier == vault.creator || refunded {
+        return Vec::new(env);
+    }
+    vec![env, verifier.clone()]
+}
+
+/// `(verifier, approved_at)` of every approval still live.
+fn live_approvals(env: &Env, vault_id: u32) -> Vec<(Address, u64)> {
+    let window = config::load(env).emergency_approval_window;
+    let now = env.ledger().timestamp();
+    let mut live = Vec::new(env);
+    let approvals: Vec<(Address, u64)> = env
+        .storage()
+        .instance()
+        .get(&DataKey::EmergencyApprovals(vault_id))
+        .unwrap_or(Vec::new(env));
+    for (verifier, approved_at) in approvals.iter() {
+        if window == 0 || now < approved_at.saturating_add(window) {
+            live.push_back((verifier, approved_at));
+        }
+    }
+    live
+}
+
+/// Approvals needed out of `verifiers`: `quorum_bps` of them, rounded up. `None` when
+/// emergency unlocks are disabled or the vault has no independent verifier.
+fn quorum(env: &Env, verifiers: u32) -> Option<u32> {
+    let quorum_bps = config::load(env).emergency_quorum_bps;
+    if quorum_bps == 0 || verifiers == 0 {
+        return None;
+    }
+    // At most `verifiers`, since `set_config` caps the rate at 100%.
+    let needed = (verifiers as u64 * quorum_bps as u64).div_ceil(BPS_DENOMINATOR as u64);
+    Some(needed as u32)
+}
+
+/// Common checks for the approval entrypoints: `verifier` is one of the Active vault's
+/// verifiers and emergency unlocks are enabled. Returns the quorum and the live approvals.
+fn load_open(
+    env: &Env,
+    vault_id: u32,
+    verifier: &Address,
+) -> Result<(u32, Vec<(Address, u64)>), Error> {
+    let vault = load_vault(env, vault_id)?;
+    require_unsettled(&vault)?;
+    let verifiers = verifiers(env, vault_id, &vault);
+    let quorum = quorum(env, verifiers.len()).ok_or(Error::InvalidStatus)?;
+    if !verifiers.contains(verifier) {
+        return Err(Error::NotAuthorized);
+    }
+    Ok((quorum, live_approvals(env, vault_id)))
+}
+
+fn store(env: &Env, vault_id: u32, approvals: &Vec<(Address, u64)>) {
+    env.storage()
+        .instance()
+        .set(&DataKey::EmergencyApprovals(vault_id), approvals);
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// `verifier` approves an emergency unlock of an Active vault. Returns the number of live
+    /// approvals, including this one.
+    ///
+    /// Fails with `NotAuthorized` for an address other than the vault's verifier,
+    /// `AlreadyJoined` while this verifier's earlier approval is still live, and
+    /// `InvalidStatus` when emergency unlocks are disabled or the vault has no independent
+    /// verifier.
+    pub fn approve_emergency(env: Env, vault_id: u32, verifier: Address) -> Result<u32, Error> {
+        verifier.require_auth();
+        let (_, mut approvals) = load_open(&env, vault_id, &verifier)?;
+        if approvals.iter().any(|(approver, _)| approver == verifier) {
+            return Err(Error::AlreadyJoined);
+        }
+        approvals.push_back((verifier.clone(), env.ledger().timestamp()));
+        store(&env, vault_id, &approvals);
+
+        events::emergency_approved(&env, vault_id, &verifier);
+        Ok(approvals.len())
+    }
+
+    /// `verifier` withdraws their live approval. Fails with `NoPendingCancellation` when
+    /// there is none and `InvalidStatus` once the quorum has been reached.
+    pub fn revoke_emergency(env: Env, vault_id: u32, verifier: Address) -> Result<bool, Error> {
+        verifier.require_auth();
+        let (quorum, mut approvals) = load_open(&env, vault_id, &verifier)?;
+        if approvals.len() >= quorum {
+            return Err(Error::InvalidStatus);
+        }
+        let index = approvals
+            .iter()
+            .position(|(approver, _)| approver == verifier)
+            .ok_or(Error::NoPendingCancellation)?;
+        approvals.remove(index as u32);
+        store(&env, vault_id, &approvals);
+
+        events::emergency_revoked(&env, vault_id, &verifier);
+        Ok(true)
+    }
+
+    /// Refund every depositor of a vault whose verifier approved an emergency unlock, and
+    /// cancel it. Permissionless; fails with `NotAuthorized` without a live approval.
+    pub fn emergency_unlock(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        require_unsettled(&vault)?;
+        let quorum =
+            quorum(&env, verifiers(&env, vault_id, &vault).len()).ok_or(Error::InvalidStatus)?;
+        let approvals = live_approvals(&env, vault_id);
+        if approvals.len() < quorum {
+            return Err(Error::NotAuthorized);
+        }
+        let mut approvers = Vec::new(&env);
+        for (approver, _) in approvals.iter() {
+            approvers.push_back(approver);
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::EmergencyApprovals(vault_id));
+
+        let refunds = refunds(&env, vault_id, &vault);
+        settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &refunds,
+            VaultStatus::Cancelled,
+            env.current_contract_address(),
+        )?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::emergency_unlock(&env, vault_id, &vault.creator, &approvers);
+        events::vault_cancelled(
+            &env,
+            vault_id,
+            &vault.creator,
+            privacy::amount(&vault, vault.amount),
+        );
+        yield_adapter::publish(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// `(verifier, approved_at)` of the vault's live emergency approvals. Always empty for
+    /// private vaults.
+    pub fn get_emergency_approvals(env: Env, vault_id: u32) -> Vec<(Address, u64)> {
+        if privacy::is_private(&env, vault_id) {
+            return Vec::new(&env);
+        }
+        live_approvals(&env, vault_id)
+    }
+}
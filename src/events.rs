@@ -0,0 +1,708 @@
+//! Event schema and the publish helpers that own it.
+//!
+//! Every event the contract emits is published from this module, so the schema below is
+//! the complete list.
+//!
+//! # Topic layout
+//!
+//! Vault events use `(name: Symbol, vault_id: u32, party: Address)` topics, where `name` is
+//! snake_case and `party` is the address an indexer would filter on for that action. RPC
+//! event filters match topics only, so a wallet can subscribe to `("*", "*", <address>)` to
+//! follow every vault it takes part in. This layout is stable: new events use the same three
+//! positions, and details that are not useful as filters stay in the data. The one addition
+//! is a fourth topic on `vault_created`, the vault's category, so `("vault_created", "*",
+//! "*", <category>)` follows a category.
+//!
+//! | name                          | party          | data                                   |
+//! |-------------------------------|----------------|----------------------------------------|
+//! | `vault_created`               | creator        | `ProductivityVault`                    |
+//! | `milestone_validated`         | validator      | `()`                                   |
+//! | `milestone_graded`            | validator      | `score_bps: u32`                       |
+//! | `wager_accepted`              | challenger     | `stake: i128`                          |
+//! | `vault_renewed`               | creator        | `successor_id: u32`                    |
+//! | `auto_renew_set`              | creator        | `enabled: bool`                        |
+//! | `deadline_extended`           | creator        | `(old_deadline, new_deadline, fee)`    |
+//! | `bounty_committed`            | claimer        | `commitment: BytesN<32>`               |
+//! | `bounty_claimed`              | claimer        | `preimage: Bytes`                      |
+//! | `early_withdrawal`            | creator        | `penalty: i128`                        |
+//! | `installment_paid`            | creator        | `(index: u32, amount: i128)`           |
+//! | `emergency_approved`          | verifier       | `()`                                   |
+//! | `emergency_revoked`           | verifier       | `()`                                   |
+//! | `emergency_unlock`            | creator        | `approvers: Vec<Address>`              |
+//! | `claim_transferred`           | previous owner | `new_owner: Address`                   |
+//! | `milestone_attested`          | witness        | `()`                                   |
+//! | `participant_validated`       | validator      | `participant: Address`                 |
+//! | `joint_failed`                | creator        | `(validated, missing)`                 |
+//! | `vault_recommitted`           | creator        | `successor_id: u32`                    |
+//! | `vault_forfeited`             | creator        | `(amount: i128, burned: bool)`         |
+//! | `vault_expired`               | creator        | `funding_deadline: u64`                |
+//! | `gift_accepted`               | recipient      | `()`                                   |
+//! | `installment_missed`          | creator        | `index: u32`                           |
+//! | `wager_rejected`              | validator      | `()`                                   |
+//! | `wager_drawn`                 | validator      | `()`                                   |
+//! | `funds_released`              | recipient      | `PayoutBreakdown`                      |
+//! | `funds_redirected`            | recipient      | `PayoutBreakdown`                      |
+//! | `vault_cancelled`             | creator        | `amount: i128`                         |
+//! | `proof_submitted`             | creator        | `(proof_hash, index)`                  |
+//! | `changes_requested`           | validator      | `(proof_hash, reason)`                 |
+//! | `proof_approved`              | validator      | `proof_hash`                           |
+//! | `period_validated`            | validator      | `(period_index, slice)`                |
+//! | `period_missed`               | recipient      | `(period_index, slice)`                |
+//! | `participant_joined`          | participant    | `amount: i128`                         |
+//! | `vault_activated`             | creator        | `pooled: i128`                         |
+//! | `badge_minted`                | creator        | `tier: u32`                            |
+//! | `badge_mint_failed`           | creator        | `tier: u32`                            |
+//! | `referral_paid`               | referrer       | `reward: i128`                         |
+//! | `cancellation_proposed`       | creator        | `expires_at: u64`                      |
+//! | `vault_sponsored`             | funder         | `amount: i128`                         |
+//! | `success_destination_updated` | creator        | `(old, new)`                           |
+//! | `milestone_revealed`          | creator        | `description: Bytes`                   |
+//! | `creator_transfer_proposed`   | new creator    | `creator: Address`                     |
+//! | `creator_transferred`         | validator      | `(old, new)`                           |
+//! | `callback_failed`             | callback       | `status: VaultStatus`                  |
+//! | `callback_cleared`            | admin          | `()`                                   |
+//! | `yield_redeemed`              | creator        | `(principal, redeemed, creator_yield)` |
+//!
+//! Events that are not about a single vault drop the `vault_id` and use
+//! `(name: Symbol, party: Address)`:
+//!
+//! | name                | party   | data                            |
+//! |---------------------|---------|---------------------------------|
+//! | `streak_updated`    | creator | `StreakInfo`                    |
+//! | `config_updated`    | admin   | `Config`                        |
+//! | `fees_withdrawn`    | admin   | `amount: i128`                  |
+//! | `charity_added`     | admin   | `(index, Charity)`              |
+//! | `charity_removed`   | admin   | `(index, address)`              |
+//! | `template_created`  | owner   | `template_id: u32`              |
+//! | `template_deleted`  | owner   | `template_id: u32`              |
+//! | `category_added`    | admin   | `category: Symbol`              |
+//! | `category_removed`  | admin   | `category: Symbol`              |
+//! | `yield_adapter_set` | admin   | `(token, Option<adapter>)`      |
+//! | `price_oracle_set`  | admin   | `(quote_asset, Option<oracle>)` |
+//!
+//! `streak_updated` follows the settlement event of every completion or failure.
+//!
+//! The validator is the verifier, or the creator for vaults without one. The recipient of a
+//! settlement is the vault's success (release) or failure (redirect) destination; for a group
+//! vault paying out proportionally it is the creator, with every participant's net amount in
+//! the breakdown's shares. `vault_activated` follows the `participant_joined` that fills a
+//! group vault.
+//!
+//! `vault_sponsored` follows `vault_created` for a vault staked by someone other than its
+//! creator, so indexers can attribute the stake to the funder.
+//!
+//! `milestone_revealed` carries the plaintext behind a vault's `milestone_hash`, published
+//! once the contract has checked it against the commitment.
+//!
+//! `yield_redeemed` follows the settlement event of a vault whose stake was in a yield
+//! adapter; `redeemed` is what the adapter returned, so `redeemed - principal` is the yield
+//! (or, when negative, the haircut).
+//!
+//! `creator_transfer_proposed` is filtered on the proposed address so the new wallet can find
+//! it; `creator_transferred` goes to the validator, so the verifier learns who it now
+//! validates for.
+//!
+//! `callback_failed` ends a settlement whose registered callback did not accept the
+//! notification; the settlement itself stands.
+//!
+//! `milestone_graded` replaces `milestone_validated` for scores below full marks; the later
+//! `funds_released` of a graded vault lists the failure destination's share among its
+//! shares.
+//!
+//! `wager_accepted` is followed by `vault_activated`. The verifier's ruling on a wager is
+//! followed by its settlement: `funds_redirected` to the challenger after `wager_rejected`,
+//! `vault_cancelled` after `wager_drawn`.
+//!
+//! `vault_renewed` is the completion event of an `auto_renew` vault, in place of
+//! `funds_released`: nothing was paid out. The successor's `vault_created` follows it.
+//!
+//! `bounty_claimed` is followed by the `funds_released` paying the claimer, who is also that
+//! event's recipient; an expired bounty ends with `vault_cancelled`.
+//!
+//! `early_withdrawal` precedes the `vault_cancelled` of a savings lock cancelled before its
+//! deadline; that event's amount is the refund after the penalty.
+//!
+//! `installment_missed` precedes the `funds_redirected` of the vault it failed.
+//!
+//! A gift emits `vault_created` with the recipient as creator, then `vault_sponsored` with
+//! the gifter; `gift_accepted` is followed by `vault_activated`.
+//!
+//! `emergency_unlock` precedes the `vault_cancelled` of the vault it refunded.
+//!
+//! `vault_expired` precedes the `vault_cancelled` of a Pending vault swept at its funding
+//! deadline; `funding_deadline` is the deadline that passed.
+//!
+//! The first owner of a transferable claim is the `success_destination` in `vault_created`;
+//! every `claim_transferred` after that moves it on, so indexers can follow ownership.
+//!
+//! `vault_forfeited` follows the `funds_redirected` of a vault that burns its stake on
+//! failure, whose recipient is then this contract, and the `deadline_extended` of such a
+//! vault for its snooze fee. `burned` is `false` when the token
+//! refused the burn and the amount went to the protocol treasury instead.
+//!
+//! A joint vault emits `participant_validated` for every participant and `milestone_validated`
+//! with the last of them. `joint_failed` precedes the `funds_redirected` of a joint vault and
+//! lists the participants who were validated and those who were not; all of them lose
+//! their stake.
+//!
+//! `vault_recommitted` follows the `vault_cancelled` of a vault whose escrow moved into a new
+//! vault instead of being refunded; the successor's `vault_created` follows it.
+//!
+//! For private vaults (see `privacy`) every amount in the data is published as zero and
+//! `vault_created` carries the redacted vault; topics are unchanged.
+//!
+//! `cancellation_proposed` is what `cancel_vault` emits for a vault with an accountability
+//! partner; `vault_cancelled` follows later, from the partner's `approve_cancellation`.
+//!
+//! `referral_paid` follows the `funds_released` of every referred vault that completes; the
+//! reward is part of the breakdown's `fee`, and is zero when no protocol fee is configured.
+//!
+//! When a badge contract is configured, every completion ends with `badge_minted`, or
+//! `badge_mint_failed` if the badge contract's `mint` did not succeed; the settlement stands
+//! either way.
+//!
+//! `index` is the zero-based submission index and `reason` a `ReasonCode` (a `u32` on the
+//! wire). `proof_approved` follows `milestone_validated` in the same call whenever a proof
+//! had been submitted, naming the submission the validation approved.
+//!
+//! Recurring vaults emit one period event per settled period. When the last period settles,
+//! the same call then emits `funds_released` (or `funds_redirected` if no period was ever
+//! validated) for whatever was still escrowed.
+//!
+//! Settlement payloads are the exact `PayoutBreakdown` the transfers were made from: gross,
+//! every amount withheld, and the net `(recipient, amount)` pairs, summing to gross.
+//!
+//! Events are published as the last step of a successful call; a call that fails emits
+//! nothing.
+
+use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal, Symbol, Val, Vec};
+
+use crate::charity::Charity;
+use crate::config::Config;
+use crate::payout::PayoutBreakdown;
+use crate::privacy;
+use crate::proofs::ReasonCode;
+use crate::streak::StreakInfo;
+use crate::{ProductivityVault, VaultStatus};
+
+pub const VAULT_CREATED: &str = "vault_created";
+pub const MILESTONE_VALIDATED: &str = "milestone_validated";
+pub const FUNDS_RELEASED: &str = "funds_released";
+pub const FUNDS_REDIRECTED: &str = "funds_redirected";
+pub const VAULT_CANCELLED: &str = "vault_cancelled";
+pub const PROOF_SUBMITTED: &str = "proof_submitted";
+pub const CHANGES_REQUESTED: &str = "changes_requested";
+pub const PROOF_APPROVED: &str = "proof_approved";
+pub const PERIOD_VALIDATED: &str = "period_validated";
+pub const PERIOD_MISSED: &str = "period_missed";
+pub const PARTICIPANT_JOINED: &str = "participant_joined";
+pub const VAULT_ACTIVATED: &str = "vault_activated";
+pub const STREAK_UPDATED: &str = "streak_updated";
+pub const CONFIG_UPDATED: &str = "config_updated";
+pub const BADGE_MINTED: &str = "badge_minted";
+pub const BADGE_MINT_FAILED: &str = "badge_mint_failed";
+pub const REFERRAL_PAID: &str = "referral_paid";
+pub const FEES_WITHDRAWN: &str = "fees_withdrawn";
+pub const CHARITY_ADDED: &str = "charity_added";
+pub const CHARITY_REMOVED: &str = "charity_removed";
+pub const CANCELLATION_PROPOSED: &str = "cancellation_proposed";
+pub const TEMPLATE_CREATED: &str = "template_created";
+pub const TEMPLATE_DELETED: &str = "template_deleted";
+pub const VAULT_SPONSORED: &str = "vault_sponsored";
+pub const SUCCESS_DESTINATION_UPDATED: &str = "success_destination_updated";
+pub const MILESTONE_REVEALED: &str = "milestone_revealed";
+pub const YIELD_REDEEMED: &str = "yield_redeemed";
+pub const YIELD_ADAPTER_SET: &str = "yield_adapter_set";
+pub const CREATOR_TRANSFER_PROPOSED: &str = "creator_transfer_proposed";
+pub const CREATOR_TRANSFERRED: &str = "creator_transferred";
+pub const CATEGORY_ADDED: &str = "category_added";
+pub const CATEGORY_REMOVED: &str = "category_removed";
+pub const CALLBACK_FAILED: &str = "callback_failed";
+pub const CALLBACK_CLEARED: &str = "callback_cleared";
+pub const MILESTONE_GRADED: &str = "milestone_graded";
+pub const WAGER_ACCEPTED: &str = "wager_accepted";
+pub const WAGER_REJECTED: &str = "wager_rejected";
+pub const WAGER_DRAWN: &str = "wager_drawn";
+pub const VAULT_RENEWED: &str = "vault_renewed";
+pub const AUTO_RENEW_SET: &str = "auto_renew_set";
+pub const DEADLINE_EXTENDED: &str = "deadline_extended";
+pub const BOUNTY_COMMITTED: &str = "bounty_committed";
+pub const BOUNTY_CLAIMED: &str = "bounty_claimed";
+pub const EARLY_WITHDRAWAL: &str = "early_withdrawal";
+pub const INSTALLMENT_PAID: &str = "installment_paid";
+pub const INSTALLMENT_MISSED: &str = "installment_missed";
+pub const GIFT_ACCEPTED: &str = "gift_accepted";
+pub const EMERGENCY_APPROVED: &str = "emergency_approved";
+pub const EMERGENCY_REVOKED: &str = "emergency_revoked";
+pub const EMERGENCY_UNLOCK: &str = "emergency_unlock";
+pub const CLAIM_TRANSFERRED: &str = "claim_transferred";
+pub const PRICE_ORACLE_SET: &str = "price_oracle_set";
+pub const VAULT_FORFEITED: &str = "vault_forfeited";
+pub const VAULT_EXPIRED: &str = "vault_expired";
+pub const MILESTONE_ATTESTED: &str = "milestone_attested";
+pub const PARTICIPANT_VALIDATED: &str = "participant_validated";
+pub const JOINT_FAILED: &str = "joint_failed";
+pub const VAULT_RECOMMITTED: &str = "vault_recommitted";
+
+fn publish<D: IntoVal<Env, Val>>(env: &Env, name: &str, vault_id: u32, party: &Address, data: D) {
+    env.events()
+        .publish((Symbol::new(env, name), vault_id, party.clone()), data);
+}
+
+/// Publish a contract-level event with `(name, party)` topics.
+fn publish_global<D: IntoVal<Env, Val>>(env: &Env, name: &str, party: &Address, data: D) {
+    env.events()
+        .publish((Symbol::new(env, name), party.clone()), data);
+}
+
+/// The one event with a fourth topic: the vault's category, so indexers can follow a
+/// category from creation and join later events on `vault_id`. The data is the vault as
+/// `get_vault_state` returns it.
+pub(crate) fn vault_created(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    env.events().publish(
+        (
+            Symbol::new(env, VAULT_CREATED),
+            vault_id,
+            vault.creator.clone(),
+            vault.category.clone(),
+        ),
+        privacy::redact(env, vault),
+    );
+}
+
+pub(crate) fn milestone_validated(env: &Env, vault_id: u32, validator: &Address) {
+    publish(env, MILESTONE_VALIDATED, vault_id, validator, ());
+}
+
+pub(crate) fn funds_released(
+    env: &Env,
+    vault_id: u32,
+    recipient: &Address,
+    parts: &PayoutBreakdown,
+) {
+    publish(env, FUNDS_RELEASED, vault_id, recipient, parts.clone());
+}
+
+pub(crate) fn funds_redirected(
+    env: &Env,
+    vault_id: u32,
+    recipient: &Address,
+    parts: &PayoutBreakdown,
+) {
+    publish(env, FUNDS_REDIRECTED, vault_id, recipient, parts.clone());
+}
+
+pub(crate) fn vault_cancelled(env: &Env, vault_id: u32, creator: &Address, amount: i128) {
+    publish(env, VAULT_CANCELLED, vault_id, creator, amount);
+}
+
+pub(crate) fn proof_submitted(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    proof_hash: &BytesN<32>,
+    index: u32,
+) {
+    publish(
+        env,
+        PROOF_SUBMITTED,
+        vault_id,
+        creator,
+        (proof_hash.clone(), index),
+    );
+}
+
+pub(crate) fn changes_requested(
+    env: &Env,
+    vault_id: u32,
+    validator: &Address,
+    proof_hash: &BytesN<32>,
+    reason: ReasonCode,
+) {
+    publish(
+        env,
+        CHANGES_REQUESTED,
+        vault_id,
+        validator,
+        (proof_hash.clone(), reason),
+    );
+}
+
+pub(crate) fn proof_approved(
+    env: &Env,
+    vault_id: u32,
+    validator: &Address,
+    proof_hash: &BytesN<32>,
+) {
+    publish(env, PROOF_APPROVED, vault_id, validator, proof_hash.clone());
+}
+
+pub(crate) fn period_validated(
+    env: &Env,
+    vault_id: u32,
+    validator: &Address,
+    period_index: u32,
+    slice: i128,
+) {
+    publish(
+        env,
+        PERIOD_VALIDATED,
+        vault_id,
+        validator,
+        (period_index, slice),
+    );
+}
+
+pub(crate) fn period_missed(
+    env: &Env,
+    vault_id: u32,
+    recipient: &Address,
+    period_index: u32,
+    slice: i128,
+) {
+    publish(
+        env,
+        PERIOD_MISSED,
+        vault_id,
+        recipient,
+        (period_index, slice),
+    );
+}
+
+pub(crate) fn participant_joined(env: &Env, vault_id: u32, participant: &Address, amount: i128) {
+    publish(env, PARTICIPANT_JOINED, vault_id, participant, amount);
+}
+
+pub(crate) fn vault_activated(env: &Env, vault_id: u32, creator: &Address, pooled: i128) {
+    publish(env, VAULT_ACTIVATED, vault_id, creator, pooled);
+}
+
+pub(crate) fn streak_updated(env: &Env, creator: &Address, streak: &StreakInfo) {
+    publish_global(env, STREAK_UPDATED, creator, streak.clone());
+}
+
+pub(crate) fn config_updated(env: &Env, admin: &Address, config: &Config) {
+    publish_global(env, CONFIG_UPDATED, admin, config.clone());
+}
+
+pub(crate) fn badge_minted(env: &Env, vault_id: u32, creator: &Address, tier: u32) {
+    publish(env, BADGE_MINTED, vault_id, creator, tier);
+}
+
+pub(crate) fn badge_mint_failed(env: &Env, vault_id: u32, creator: &Address, tier: u32) {
+    publish(env, BADGE_MINT_FAILED, vault_id, creator, tier);
+}
+
+/// Publish `referral_paid` for `referral`, if the settlement owed one.
+pub(crate) fn referral_paid(env: &Env, vault_id: u32, referral: Option<(Address, i128)>) {
+    if let Some((referrer, reward)) = referral {
+        publish(env, REFERRAL_PAID, vault_id, &referrer, reward);
+    }
+}
+
+pub(crate) fn fees_withdrawn(env: &Env, admin: &Address, amount: i128) {
+    publish_global(env, FEES_WITHDRAWN, admin, amount);
+}
+
+pub(crate) fn charity_added(env: &Env, admin: &Address, index: u32, charity: &Charity) {
+    publish_global(env, CHARITY_ADDED, admin, (index, charity.clone()));
+}
+
+pub(crate) fn charity_removed(env: &Env, admin: &Address, index: u32, address: &Address) {
+    publish_global(env, CHARITY_REMOVED, admin, (index, address.clone()));
+}
+
+pub(crate) fn cancellation_proposed(env: &Env, vault_id: u32, creator: &Address, expires_at: u64) {
+    publish(env, CANCELLATION_PROPOSED, vault_id, creator, expires_at);
+}
+
+pub(crate) fn template_created(env: &Env, owner: &Address, template_id: u32) {
+    publish_global(env, TEMPLATE_CREATED, owner, template_id);
+}
+
+pub(crate) fn template_deleted(env: &Env, owner: &Address, template_id: u32) {
+    publish_global(env, TEMPLATE_DELETED, owner, template_id);
+}
+
+pub(crate) fn vault_sponsored(env: &Env, vault_id: u32, funder: &Address, amount: i128) {
+    publish(env, VAULT_SPONSORED, vault_id, funder, amount);
+}
+
+pub(crate) fn success_destination_updated(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    old: &Address,
+    new: &Address,
+) {
+    publish(
+        env,
+        SUCCESS_DESTINATION_UPDATED,
+        vault_id,
+        creator,
+        (old.clone(), new.clone()),
+    );
+}
+
+pub(crate) fn milestone_revealed(env: &Env, vault_id: u32, creator: &Address, description: &Bytes) {
+    publish(
+        env,
+        MILESTONE_REVEALED,
+        vault_id,
+        creator,
+        description.clone(),
+    );
+}
+
+pub(crate) fn yield_redeemed(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    principal: i128,
+    redeemed: i128,
+    creator_yield: i128,
+) {
+    publish(
+        env,
+        YIELD_REDEEMED,
+        vault_id,
+        creator,
+        (principal, redeemed, creator_yield),
+    );
+}
+
+pub(crate) fn yield_adapter_set(
+    env: &Env,
+    admin: &Address,
+    token: &Address,
+    adapter: &Option<Address>,
+) {
+    publish_global(
+        env,
+        YIELD_ADAPTER_SET,
+        admin,
+        (token.clone(), adapter.clone()),
+    );
+}
+
+pub(crate) fn creator_transfer_proposed(
+    env: &Env,
+    vault_id: u32,
+    new_creator: &Address,
+    creator: &Address,
+) {
+    publish(
+        env,
+        CREATOR_TRANSFER_PROPOSED,
+        vault_id,
+        new_creator,
+        creator.clone(),
+    );
+}
+
+pub(crate) fn creator_transferred(
+    env: &Env,
+    vault_id: u32,
+    validator: &Address,
+    old: &Address,
+    new: &Address,
+) {
+    publish(
+        env,
+        CREATOR_TRANSFERRED,
+        vault_id,
+        validator,
+        (old.clone(), new.clone()),
+    );
+}
+
+pub(crate) fn category_added(env: &Env, admin: &Address, category: &Symbol) {
+    publish_global(env, CATEGORY_ADDED, admin, category.clone());
+}
+
+pub(crate) fn category_removed(env: &Env, admin: &Address, category: &Symbol) {
+    publish_global(env, CATEGORY_REMOVED, admin, category.clone());
+}
+
+pub(crate) fn callback_failed(env: &Env, vault_id: u32, callback: &Address, status: VaultStatus) {
+    publish(env, CALLBACK_FAILED, vault_id, callback, status);
+}
+
+pub(crate) fn callback_cleared(env: &Env, vault_id: u32, admin: &Address) {
+    publish(env, CALLBACK_CLEARED, vault_id, admin, ());
+}
+
+pub(crate) fn milestone_graded(env: &Env, vault_id: u32, validator: &Address, score_bps: u32) {
+    publish(env, MILESTONE_GRADED, vault_id, validator, score_bps);
+}
+
+pub(crate) fn wager_accepted(env: &Env, vault_id: u32, challenger: &Address, stake: i128) {
+    publish(env, WAGER_ACCEPTED, vault_id, challenger, stake);
+}
+
+pub(crate) fn wager_rejected(env: &Env, vault_id: u32, validator: &Address) {
+    publish(env, WAGER_REJECTED, vault_id, validator, ());
+}
+
+pub(crate) fn wager_drawn(env: &Env, vault_id: u32, validator: &Address) {
+    publish(env, WAGER_DRAWN, vault_id, validator, ());
+}
+
+pub(crate) fn vault_renewed(env: &Env, vault_id: u32, creator: &Address, successor_id: u32) {
+    publish(env, VAULT_RENEWED, vault_id, creator, successor_id);
+}
+
+pub(crate) fn auto_renew_set(env: &Env, vault_id: u32, creator: &Address, enabled: bool) {
+    publish(env, AUTO_RENEW_SET, vault_id, creator, enabled);
+}
+
+pub(crate) fn deadline_extended(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    old_deadline: u64,
+    new_deadline: u64,
+    fee: i128,
+) {
+    publish(
+        env,
+        DEADLINE_EXTENDED,
+        vault_id,
+        creator,
+        (old_deadline, new_deadline, fee),
+    );
+}
+
+pub(crate) fn bounty_committed(
+    env: &Env,
+    vault_id: u32,
+    claimer: &Address,
+    commitment: &BytesN<32>,
+) {
+    publish(env, BOUNTY_COMMITTED, vault_id, claimer, commitment.clone());
+}
+
+pub(crate) fn bounty_claimed(env: &Env, vault_id: u32, claimer: &Address, preimage: &Bytes) {
+    publish(env, BOUNTY_CLAIMED, vault_id, claimer, preimage.clone());
+}
+
+pub(crate) fn early_withdrawal(env: &Env, vault_id: u32, creator: &Address, penalty: i128) {
+    publish(env, EARLY_WITHDRAWAL, vault_id, creator, penalty);
+}
+
+pub(crate) fn installment_paid(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    index: u32,
+    amount: i128,
+) {
+    publish(env, INSTALLMENT_PAID, vault_id, creator, (index, amount));
+}
+
+pub(crate) fn installment_missed(env: &Env, vault_id: u32, creator: &Address, index: u32) {
+    publish(env, INSTALLMENT_MISSED, vault_id, creator, index);
+}
+
+pub(crate) fn gift_accepted(env: &Env, vault_id: u32, recipient: &Address) {
+    publish(env, GIFT_ACCEPTED, vault_id, recipient, ());
+}
+
+pub(crate) fn emergency_approved(env: &Env, vault_id: u32, verifier: &Address) {
+    publish(env, EMERGENCY_APPROVED, vault_id, verifier, ());
+}
+
+pub(crate) fn emergency_revoked(env: &Env, vault_id: u32, verifier: &Address) {
+    publish(env, EMERGENCY_REVOKED, vault_id, verifier, ());
+}
+
+pub(crate) fn emergency_unlock(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    approvers: &Vec<Address>,
+) {
+    publish(env, EMERGENCY_UNLOCK, vault_id, creator, approvers.clone());
+}
+
+pub(crate) fn claim_transferred(
+    env: &Env,
+    vault_id: u32,
+    previous_owner: &Address,
+    new_owner: &Address,
+) {
+    publish(
+        env,
+        CLAIM_TRANSFERRED,
+        vault_id,
+        previous_owner,
+        new_owner.clone(),
+    );
+}
+
+pub(crate) fn price_oracle_set(
+    env: &Env,
+    admin: &Address,
+    quote_asset: &Symbol,
+    oracle: &Option<Address>,
+) {
+    publish_global(
+        env,
+        PRICE_ORACLE_SET,
+        admin,
+        (quote_asset.clone(), oracle.clone()),
+    );
+}
+
+pub(crate) fn vault_forfeited(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    amount: i128,
+    burned: bool,
+) {
+    publish(env, VAULT_FORFEITED, vault_id, creator, (amount, burned));
+}
+
+pub(crate) fn vault_expired(env: &Env, vault_id: u32, creator: &Address, funding_deadline: u64) {
+    publish(env, VAULT_EXPIRED, vault_id, creator, funding_deadline);
+}
+
+pub(crate) fn milestone_attested(env: &Env, vault_id: u32, witness: &Address) {
+    publish(env, MILESTONE_ATTESTED, vault_id, witness, ());
+}
+
+pub(crate) fn participant_validated(
+    env: &Env,
+    vault_id: u32,
+    validator: &Address,
+    participant: &Address,
+) {
+    publish(
+        env,
+        PARTICIPANT_VALIDATED,
+        vault_id,
+        validator,
+        participant.clone(),
+    );
+}
+
+pub(crate) fn joint_failed(
+    env: &Env,
+    vault_id: u32,
+    creator: &Address,
+    validated: &Vec<Address>,
+    missing: &Vec<Address>,
+) {
+    publish(
+        env,
+        JOINT_FAILED,
+        vault_id,
+        creator,
+        (validated.clone(), missing.clone()),
+    );
+}
+
+pub(crate) fn vault_recommitted(env: &Env, vault_id: u32, creator: &Address, successor_id: u32) {
+    publish(env, VAULT_RECOMMITTED, vault_id, creator, successor_id);
+}
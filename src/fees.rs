@@ -0,0 +1,137 @@
+//! Protocol fee and referral rewards.
+//!
+//! Completing settlements withhold `Config::fee_bps` of the escrow as a protocol fee (the
+//! breakdown's `fee`). A vault created with a `referrer` pays that referrer
+//! `Config::referral_bps` of the fee, never of the stake; the rest of the fee accrues in the
+//! contract per token until the admin withdraws it with `withdraw_fees`. Failed and cancelled
+//! vaults withhold no fee, so their referrers earn nothing.
+
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env};
+
+use crate::{
+    config, events, payout, validate_destination, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReferralStats {
+    /// Referred vaults that completed.
+    pub completed_referrals: u32,
+    /// Lifetime rewards paid, summed across tokens.
+    pub earned: i128,
+}
+
+/// Fee rate for a settlement into `status`: `Config::fee_bps` for completions, zero otherwise.
+pub(crate) fn fee_bps(env: &Env, status: VaultStatus) -> u32 {
+    match status {
+        VaultStatus::Completed => config::load(env).fee_bps,
+        _ => 0,
+    }
+}
+
+/// Referral reward owed out of a settlement's `fee`: `(referrer, reward)` for referred vaults
+/// that completed, `None` otherwise.
+pub(crate) fn referral_reward(
+    env: &Env,
+    vault: &ProductivityVault,
+    fee: i128,
+) -> Result<Option<(Address, i128)>, Error> {
+    match (&vault.referrer, vault.status) {
+        (Some(referrer), VaultStatus::Completed) => {
+            let reward = payout::bps_of(fee, config::load(env).referral_bps)?;
+            Ok(Some((referrer.clone(), reward)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn accrued(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AccruedFees(token.clone()))
+        .unwrap_or(0)
+}
+
+fn referral_stats(env: &Env, referrer: &Address) -> ReferralStats {
+    env.storage()
+        .instance()
+        .get(&DataKey::Referral(referrer.clone()))
+        .unwrap_or_default()
+}
+
+/// Book the `fee` withheld from a settlement of `vault`: credit its referrer's reward and
+/// accrue the rest. Returns the referral payment the caller must transfer.
+pub(crate) fn collect(
+    env: &Env,
+    token: &Address,
+    vault: &ProductivityVault,
+    fee: i128,
+) -> Result<Option<(Address, i128)>, Error> {
+    let referral = referral_reward(env, vault, fee)?;
+    let reward = referral.as_ref().map_or(0, |(_, reward)| *reward);
+    if let Some((referrer, reward)) = &referral {
+        let mut stats = referral_stats(env, referrer);
+        stats.completed_referrals = stats.completed_referrals.saturating_add(1);
+        stats.earned = stats
+            .earned
+            .checked_add(*reward)
+            .ok_or(Error::ArithmeticOverflow)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Referral(referrer.clone()), &stats);
+    }
+
+    let retained = fee.checked_sub(reward).ok_or(Error::ArithmeticOverflow)?;
+    accrue(env, token, retained)?;
+    Ok(referral)
+}
+
+/// Add `amount` to the protocol's withdrawable balance in `token`.
+pub(crate) fn accrue(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+    if amount > 0 {
+        let total = accrued(env, token)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::AccruedFees(token.clone()), &total);
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Protocol fees (and protocol yield, see `yield_adapter`) accrued in `usdc_token` and not
+    /// yet withdrawn.
+    pub fn get_accrued_fees(env: Env, usdc_token: Address) -> i128 {
+        accrued(&env, &usdc_token)
+    }
+
+    /// Send every accrued fee in `usdc_token` to `to`. Admin only; returns the amount sent.
+    /// Fails with `InvalidDestination` when `to` is this contract.
+    pub fn withdraw_fees(env: Env, usdc_token: Address, to: Address) -> Result<i128, Error> {
+        let admin = config::require_admin(&env)?;
+        validate_destination(&env, &to)?;
+        let amount = accrued(&env, &usdc_token);
+        env.storage()
+            .instance()
+            .remove(&DataKey::AccruedFees(usdc_token.clone()));
+
+        if amount > 0 {
+            token::Client::new(&env, &usdc_token).transfer(
+                &env.current_contract_address(),
+                &to,
+                &amount,
+            );
+        }
+
+        events::fees_withdrawn(&env, &admin, amount);
+        Ok(amount)
+    }
+
+    /// Referral record of `referrer`; all zero for addresses that never referred a vault.
+    pub fn get_referral_stats(env: Env, referrer: Address) -> ReferralStats {
+        referral_stats(&env, &referrer)
+    }
+}
@@ -0,0 +1,120 @@
+//! Funding deadlines of vaults that open `Pending`.
+//!
+//! Group vaults wait for every participant to join, wagers for the challenger to match and
+//! gifts for the recipient to accept. Each has until its funding deadline to get there:
+//! `start_timestamp` by default, or earlier when the party that set the vault up calls
+//! `set_funding_deadline`. `join_vault`, `accept_wager` and `accept_gift` close at the
+//! deadline. From then on anyone may call `expire_unfunded`, which refunds every deposit to
+//! whoever made it (each group participant their own contribution, the wager's creator, the
+//! gifter) and cancels the vault. The type-specific `reclaim_group_stakes` and
+//! `reclaim_wager` are the same sweep restricted to their kind, through `expire`.
+//!
+//! Expiry leaves the vault's kind-specific records (its group state, wager, gift, joint
+//! members and funding deadline) in storage, as every other settlement does. They are part
+//! of the settled vault's record, which `get_group`, `get_wager`, `get_gift`, `get_joint`
+//! and `get_funding_deadline` keep reporting, and they are what tells the other entrypoints
+//! that the vault is, say, a wager.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{
+    callback, events, group, load_vault, privacy, settle, sponsor, yield_adapter, DataKey,
+    DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// First second at which `vault_id` can no longer be funded.
+pub(crate) fn deadline(env: &Env, vault_id: u32, vault: &ProductivityVault) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::FundingDeadline(vault_id))
+        .unwrap_or(vault.start_timestamp)
+}
+
+/// `true` once the funding window of `vault_id` has closed.
+pub(crate) fn closed(env: &Env, vault_id: u32, vault: &ProductivityVault) -> bool {
+    env.ledger().timestamp() >= deadline(env, vault_id, vault)
+}
+
+fn load_pending(env: &Env, vault_id: u32) -> Result<ProductivityVault, Error> {
+    let vault = load_vault(env, vault_id)?;
+    match vault.status {
+        VaultStatus::Pending => Ok(vault),
+        VaultStatus::Active => Err(Error::InvalidStatus),
+        _ => Err(Error::AlreadySettled),
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Close a Pending vault's funding window at `funding_deadline` rather than its
+    /// `start_timestamp`. Called by whoever set the vault up: the gifter of a gift, the
+    /// creator otherwise.
+    ///
+    /// Fails with `InvalidStatus` once the vault is active, `AlreadySettled` once it is
+    /// cancelled and `InvalidTimestamp` unless `funding_deadline` is in the future and no
+    /// later than `start_timestamp`.
+    pub fn set_funding_deadline(
+        env: Env,
+        vault_id: u32,
+        funding_deadline: u64,
+    ) -> Result<bool, Error> {
+        let vault = load_pending(&env, vault_id)?;
+        sponsor::payer(&env, vault_id, &vault).require_auth();
+        if funding_deadline <= env.ledger().timestamp() || funding_deadline > vault.start_timestamp
+        {
+            return Err(Error::InvalidTimestamp);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::FundingDeadline(vault_id), &funding_deadline);
+        Ok(true)
+    }
+
+    /// First second at which a vault can no longer be funded.
+    pub fn get_funding_deadline(env: Env, vault_id: u32) -> Result<u64, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        Ok(deadline(&env, vault_id, &vault))
+    }
+
+    /// Refund every deposit of a vault still Pending at its funding deadline, and cancel it.
+    /// Permissionless; `vault_expired` precedes the `vault_cancelled`.
+    ///
+    /// Fails with `InvalidTimestamp` while the funding window is open, `InvalidStatus` for
+    /// a funded, active vault and `AlreadySettled` once the vault is settled.
+    pub fn expire_unfunded(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        expire(&env, vault_id, &usdc_token)
+    }
+}
+
+/// `expire_unfunded`, shared with the type-specific `reclaim_group_stakes` and
+/// `reclaim_wager`.
+pub(crate) fn expire(env: &Env, vault_id: u32, usdc_token: &Address) -> Result<bool, Error> {
+    let mut vault = load_pending(env, vault_id)?;
+    let funding_deadline = deadline(env, vault_id, &vault);
+    if env.ledger().timestamp() < funding_deadline {
+        return Err(Error::InvalidTimestamp);
+    }
+
+    let refunds = group::refund_recipients(env, vault_id, &vault);
+    settle(
+        env,
+        vault_id,
+        &mut vault,
+        usdc_token,
+        &refunds,
+        VaultStatus::Cancelled,
+        env.current_contract_address(),
+    )?;
+    let notification = callback::notify(env, vault_id, &vault);
+
+    events::vault_expired(env, vault_id, &vault.creator, funding_deadline);
+    events::vault_cancelled(
+        env,
+        vault_id,
+        &vault.creator,
+        privacy::amount(&vault, vault.amount),
+    );
+    yield_adapter::publish(env, vault_id, &vault);
+    callback::publish(env, vault_id, &vault, notification);
+    Ok(true)
+}
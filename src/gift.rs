@@ -0,0 +1,163 @@
+//! Gifted vaults: the gifter pays, the recipient does the work.
+//!
+//! `create_gift` escrows the gifter's stake in a `Pending` vault whose creator is the
+//! recipient: the recipient submits proofs, self-attests when there is no verifier and earns
+//! the streak and reputation, like any creator. Built on sponsored vaults (see `sponsor`),
+//! the gifter is the vault's funder and its failure destination, so a failed or cancelled
+//! gift returns to them. On success the pot goes to the recipient, or to a destination the
+//! gifter chose, which `Gift::custom_destination` flags.
+//!
+//! The recipient takes the gift on with `accept_gift` before the funding deadline
+//! (`start_timestamp` unless set earlier, see `funding`), which activates the vault. Until
+//! then the gifter may take it back with `reclaim_gift`, and after it anyone may refund the
+//! gifter with `expire_unfunded`. Those are the only ways a gift is cancelled: `cancel_vault`
+//! rejects gifts with `InvalidStatus`, so once accepted neither side can pull out.
+
+use soroban_sdk::{contractimpl, contracttype, token, Address, BytesN, Env};
+
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    cancel_and_refund, category, check_amount, check_balance, check_terms, events, funding,
+    load_vault, sponsor, store_new_vault, transition, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Gift {
+    /// The gifter chose the success destination instead of paying the recipient.
+    pub custom_destination: bool,
+}
+
+pub(crate) fn is_gift(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Gift(vault_id))
+}
+
+/// A gift still waiting for its recipient.
+fn load_pending(env: &Env, vault_id: u32) -> Result<ProductivityVault, Error> {
+    let vault = load_vault(env, vault_id)?;
+    if !is_gift(env, vault_id) {
+        return Err(Error::InvalidStatus);
+    }
+    match vault.status {
+        VaultStatus::Pending => Ok(vault),
+        VaultStatus::Active => Err(Error::InvalidStatus),
+        _ => Err(Error::AlreadySettled),
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Stake `amount` from `gifter` on `recipient` reaching the milestone. Gifter only; the
+    /// pot goes to `success_destination`, or to the recipient when `None`.
+    ///
+    /// Fails with `InvalidCreator` when the recipient is the gifter, `InvalidTimestamp` when
+    /// `start_timestamp` has already passed (there would be no time to accept), and
+    /// otherwise validates like `create_vault`, with the balance check applied to the gifter.
+    pub fn create_gift(
+        env: Env,
+        usdc_token: Address,
+        gifter: Address,
+        recipient: Address,
+        amount: i128,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        verifier: Option<Address>,
+        success_destination: Option<Address>,
+    ) -> Result<u32, Error> {
+        gifter.require_auth();
+        if recipient == gifter {
+            return Err(Error::InvalidCreator);
+        }
+        if env.ledger().timestamp() >= start_timestamp {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let gift = Gift {
+            custom_destination: success_destination.is_some(),
+        };
+        let vault = ProductivityVault {
+            creator: recipient.clone(),
+            amount,
+            token: usdc_token.clone(),
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier,
+            success_destination: success_destination.unwrap_or(recipient),
+            failure_destination: gifter.clone(),
+            status: VaultStatus::Pending,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_amount(amount)?;
+        check_terms(&env, &vault)?;
+        check_balance(&env, &usdc_token, &gifter, amount)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Funder(vault_id), &gifter);
+        env.storage()
+            .instance()
+            .set(&DataKey::Gift(vault_id), &gift);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &gifter,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::vault_created(&env, vault_id, &vault);
+        events::vault_sponsored(&env, vault_id, &gifter, amount);
+        Ok(vault_id)
+    }
+
+    /// The recipient accepts a gift, activating it. Recipient only; fails with
+    /// `InvalidTimestamp` once the funding deadline (see `funding`) has passed and
+    /// `InvalidStatus` for vaults that are not pending gifts.
+    pub fn accept_gift(env: Env, vault_id: u32) -> Result<bool, Error> {
+        let mut vault = load_pending(&env, vault_id)?;
+        vault.creator.require_auth();
+        if funding::closed(&env, vault_id, &vault) {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let recipient = vault.creator.clone();
+        transition(
+            &env,
+            vault_id,
+            &mut vault,
+            VaultStatus::Active,
+            recipient.clone(),
+        )?;
+
+        events::gift_accepted(&env, vault_id, &recipient);
+        events::vault_activated(&env, vault_id, &recipient, vault.amount);
+        Ok(true)
+    }
+
+    /// The gifter takes back a gift that was not accepted, cancelling it. Gifter only, at any
+    /// time before acceptance; fails with `InvalidStatus` once accepted.
+    pub fn reclaim_gift(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let vault = load_pending(&env, vault_id)?;
+        let gifter = sponsor::payer(&env, vault_id, &vault);
+        gifter.require_auth();
+
+        cancel_and_refund(&env, vault_id, vault, &usdc_token, gifter)
+    }
+
+    /// Terms of a gifted vault, or `None` for other vaults. The gifter is `get_funder`.
+    pub fn get_gift(env: Env, vault_id: u32) -> Option<Gift> {
+        env.storage().instance().get(&DataKey::Gift(vault_id))
+    }
+}
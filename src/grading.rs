@@ -0,0 +1,92 @@
+//! Graded completion: a verifier scores a milestone instead of passing or failing it.
+//!
+//! Only vaults created with `VaultOptions::allow_graded` accept `validate_graded`; every
+//! other vault stays binary. A score of `BPS_DENOMINATOR` is exactly `validate_milestone`.
+//! Any lower score is recorded and closes validation for good:
+//!
+//! - Between the two, the vault counts as validated and `release_funds` settles it in one go:
+//!   `score_bps` of the payout goes to the success side and the rest to the failure
+//!   destination, split by `payout::grade`, with both shares in the single `funds_released`.
+//! - Zero is a rejection. The vault is not validated, `release_funds` refuses it, and it
+//!   fails through `redirect_funds` once the deadline passes.
+
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+use crate::payout::{self, BPS_DENOMINATOR};
+use crate::{
+    dependency, events, load_vault, recurring, reputation, DataKey, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Recorded score of a graded vault; `None` unless it was graded below full marks.
+pub(crate) fn grade(env: &Env, vault_id: u32) -> Option<u32> {
+    env.storage().instance().get(&DataKey::Grade(vault_id))
+}
+
+/// Payout weights for releasing `vault`: `success` as is, or split by its grade.
+pub(crate) fn apply(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    success: Vec<(Address, i128)>,
+) -> Result<Vec<(Address, i128)>, Error> {
+    match grade(env, vault_id) {
+        Some(score_bps) => payout::grade(env, &success, &vault.failure_destination, score_bps),
+        None => Ok(success),
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Validator scores the milestone `score_bps` out of `BPS_DENOMINATOR`.
+    ///
+    /// Fails with `GradingNotAllowed` unless the vault was created with `allow_graded`,
+    /// `InvalidBps` above `BPS_DENOMINATOR`, `InvalidStatus` once the vault was validated or
+    /// graded, and otherwise like `validate_milestone`.
+    pub fn validate_graded(env: Env, vault_id: u32, score_bps: u32) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        if !vault.allow_graded {
+            return Err(Error::GradingNotAllowed);
+        }
+        if score_bps > BPS_DENOMINATOR {
+            return Err(Error::InvalidBps);
+        }
+        if score_bps == BPS_DENOMINATOR {
+            return Self::validate_milestone(env, vault_id);
+        }
+        if recurring::is_recurring(&env, vault_id) {
+            return Err(Error::InvalidStatus);
+        }
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        vault.validator().require_auth();
+        if !vault.validation_open(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        if vault.milestone_validated || grade(&env, vault_id).is_some() {
+            return Err(Error::InvalidStatus);
+        }
+        dependency::require_unlocked(&env, vault_id)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Grade(vault_id), &score_bps);
+        if score_bps > 0 {
+            vault.milestone_validated = true;
+            env.storage()
+                .instance()
+                .set(&DataKey::Vault(vault_id), &vault);
+        }
+        reputation::record_response(&env, vault_id, &vault);
+
+        events::milestone_graded(&env, vault_id, vault.validator(), score_bps);
+        Ok(true)
+    }
+
+    /// Score recorded by `validate_graded`, or `None` if the vault was not graded below full
+    /// marks.
+    pub fn get_grade(env: Env, vault_id: u32) -> Option<u32> {
+        grade(&env, vault_id)
+    }
+}
@@ -0,0 +1,274 @@
+//! Group vaults: several participants pool stakes behind one milestone.
+//!
+//! The creator lists the participants and sets the milestone, verifier and destinations;
+//! the vault starts `Pending` with nothing escrowed. Each participant then calls
+//! `join_vault` with their own stake before the funding deadline (`start_timestamp` unless
+//! set earlier, see `funding`). When the last listed participant joins the vault becomes
+//! `Active` and behaves like any other vault. If the join window closes first, anyone may
+//! call `reclaim_group_stakes` to refund every joiner and cancel the vault.
+//!
+//! On success the pot goes either back to the participants in proportion to their
+//! contributions (`GroupPayout::Proportional`, rounding per `payout::allocate`) or to the
+//! shared `success_destination`. Failure always goes to `failure_destination`, and
+//! cancellation refunds every participant their own contribution.
+
+use soroban_sdk::{contractimpl, contracttype, token, vec, Address, BytesN, Env, Vec};
+
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    category, check_amount, check_balance, check_terms, check_token, events, funding, joint,
+    load_vault, sponsor, store_new_vault, transition, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Upper bound on participants per group, keeping payouts to a bounded number of transfers.
+pub const MAX_GROUP_SIZE: u32 = 20;
+
+/// Where a successful group vault's pot goes.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupPayout {
+    /// Back to the participants, proportionally to what each contributed.
+    Proportional = 0,
+    /// To the vault's `success_destination`.
+    SharedDestination = 1,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupState {
+    /// Addresses that must all join before the vault activates.
+    pub participants: Vec<Address>,
+    /// `(participant, amount)` in join order.
+    pub contributions: Vec<(Address, i128)>,
+    pub payout: GroupPayout,
+}
+
+fn load_group(env: &Env, vault_id: u32) -> Option<GroupState> {
+    env.storage().instance().get(&DataKey::Group(vault_id))
+}
+
+/// Whether `vault_id` is a group vault, joint vaults included.
+pub(crate) fn is_group(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Group(vault_id))
+}
+
+/// Total `participant` contributed to group vault `vault_id`, or `None` for other vaults.
+pub(crate) fn contribution(env: &Env, vault_id: u32, participant: &Address) -> Option<i128> {
+    let group = load_group(env, vault_id)?;
+    Some(
+        group
+            .contributions
+            .iter()
+            .filter(|(contributor, _)| contributor == participant)
+            .map(|(_, amount)| amount)
+            .sum(),
+    )
+}
+
+/// Payout for a successful release: the event party and the weighted recipients.
+pub(crate) fn success_payout(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+) -> (Address, Vec<(Address, i128)>) {
+    match load_group(env, vault_id) {
+        Some(group) if group.payout == GroupPayout::Proportional => {
+            (vault.creator.clone(), group.contributions)
+        }
+        _ => (
+            vault.success_destination.clone(),
+            vec![env, (vault.success_destination.clone(), 1)],
+        ),
+    }
+}
+
+/// Weighted recipients of a refund: every participant's own contribution for group vaults,
+/// whoever staked the vault otherwise (see `sponsor::payer`).
+pub(crate) fn refund_recipients(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+) -> Vec<(Address, i128)> {
+    match load_group(env, vault_id) {
+        Some(group) if !group.contributions.is_empty() => group.contributions,
+        _ => vec![env, (sponsor::payer(env, vault_id, vault), 1)],
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Create a `Pending` group vault for `participants`, staked in `usdc_token`. No stake is
+    /// taken from the creator; list them as a participant if they contribute too.
+    ///
+    /// Fails with `InvalidParticipants` for an empty, oversized (`MAX_GROUP_SIZE`) or
+    /// duplicated list, `InvalidTimestamp` when `start_timestamp` has already passed (there
+    /// would be no time to join), and otherwise validates like `create_vault`.
+    pub fn create_group_vault(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        participants: Vec<Address>,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        verifier: Option<Address>,
+        success_destination: Address,
+        failure_destination: Address,
+        payout: GroupPayout,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+
+        if participants.is_empty() || participants.len() > MAX_GROUP_SIZE {
+            return Err(Error::InvalidParticipants);
+        }
+        for (i, participant) in participants.iter().enumerate() {
+            if participants.first_index_of(&participant) != Some(i as u32) {
+                return Err(Error::InvalidParticipants);
+            }
+        }
+        if env.ledger().timestamp() >= start_timestamp {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let vault = ProductivityVault {
+            creator,
+            amount: 0,
+            token: usdc_token,
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier,
+            success_destination,
+            failure_destination,
+            status: VaultStatus::Pending,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_terms(&env, &vault)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        let group = GroupState {
+            participants,
+            contributions: Vec::new(&env),
+            payout,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Group(vault_id), &group);
+
+        events::vault_created(&env, vault_id, &vault);
+        Ok(vault_id)
+    }
+
+    /// Listed `participant` stakes `amount` into a `Pending` group vault before its funding
+    /// deadline. Returns `true` if this join activated the vault.
+    ///
+    /// Fails with `InvalidStatus` unless the vault is a Pending group vault, `NotAuthorized`
+    /// for an unlisted address or a token other than the vault's, `AlreadyJoined` on a
+    /// second join and `InvalidTimestamp` once the join window has closed. The amount is
+    /// validated like a `create_vault` stake and the pooled total may not exceed the same
+    /// cap; a joint vault's participant must join with exactly their listed amount
+    /// (`InvalidAmount`, see `joint`).
+    pub fn join_vault(
+        env: Env,
+        vault_id: u32,
+        participant: Address,
+        amount: i128,
+        usdc_token: Address,
+    ) -> Result<bool, Error> {
+        participant.require_auth();
+
+        let mut vault = load_vault(&env, vault_id)?;
+        let mut group = load_group(&env, vault_id).ok_or(Error::InvalidStatus)?;
+        if vault.status != VaultStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        if !group.participants.contains(&participant) {
+            return Err(Error::NotAuthorized);
+        }
+        check_token(&vault, &usdc_token)?;
+        joint::check_stake(&env, vault_id, &participant, amount)?;
+        if group
+            .contributions
+            .iter()
+            .any(|(who, _)| who == participant)
+        {
+            return Err(Error::AlreadyJoined);
+        }
+        if funding::closed(&env, vault_id, &vault) {
+            return Err(Error::InvalidTimestamp);
+        }
+        check_amount(amount)?;
+        let pooled = vault
+            .amount
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        check_amount(pooled)?;
+        check_balance(&env, &usdc_token, &participant, amount)?;
+
+        vault.amount = pooled;
+        category::record_stake(&env, &vault, amount)?;
+        group.contributions.push_back((participant.clone(), amount));
+        let activated = group.contributions.len() == group.participants.len();
+        if activated {
+            transition(
+                &env,
+                vault_id,
+                &mut vault,
+                VaultStatus::Active,
+                participant.clone(),
+            )?;
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::Vault(vault_id), &vault);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Group(vault_id), &group);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &participant,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::participant_joined(&env, vault_id, &participant, amount);
+        if activated {
+            events::vault_activated(&env, vault_id, &vault.creator, vault.amount);
+        }
+        Ok(activated)
+    }
+
+    /// Refund every joiner of a group vault that did not fill before its funding deadline,
+    /// and cancel it. Permissionless; `expire_unfunded` restricted to group vaults.
+    ///
+    /// Fails with `InvalidTimestamp` while the join window is open, `AlreadySettled` if the
+    /// vault was already cancelled and `InvalidStatus` for vaults that are not Pending group
+    /// vaults.
+    pub fn reclaim_group_stakes(
+        env: Env,
+        vault_id: u32,
+        usdc_token: Address,
+    ) -> Result<bool, Error> {
+        load_vault(&env, vault_id)?;
+        if !is_group(&env, vault_id) {
+            return Err(Error::InvalidStatus);
+        }
+        funding::expire(&env, vault_id, &usdc_token)
+    }
+
+    /// Participants and contributions of a group vault, or `None` for other vaults.
+    pub fn get_group(env: Env, vault_id: u32) -> Option<GroupState> {
+        load_group(&env, vault_id)
+    }
+}
@@ -0,0 +1,61 @@
+//! Per-vault choice of hash function.
+//!
+//! Every hash the contract recomputes for a vault, be it a commit-reveal commitment, a
+//! revealed description or a bounty preimage or claim commitment, uses the vault's
+//! `HashAlg`. `Sha256` is the default and what vaults created before the choice existed use.
+//! `Keccak256` lets integrations built on EVM tooling keep the commitments they already
+//! produce. The algorithm is fixed at creation and only stored when it is not the default.
+//!
+//! `HashAlg` is an integer enum, so an unknown discriminant is rejected when the arguments
+//! are decoded, before any entrypoint runs.
+
+use soroban_sdk::{contractimpl, contracttype, Bytes, BytesN, Env};
+
+use crate::{DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(u32)]
+pub enum HashAlg {
+    #[default]
+    Sha256 = 0,
+    Keccak256 = 1,
+}
+
+/// `bytes` hashed with `alg`.
+pub fn digest(env: &Env, alg: HashAlg, bytes: &Bytes) -> BytesN<32> {
+    match alg {
+        HashAlg::Sha256 => env.crypto().sha256(bytes).into(),
+        HashAlg::Keccak256 => env.crypto().keccak256(bytes).into(),
+    }
+}
+
+/// The algorithm `vault_id` was created with.
+pub(crate) fn alg(env: &Env, vault_id: u32) -> HashAlg {
+    env.storage()
+        .instance()
+        .get(&DataKey::HashAlg(vault_id))
+        .unwrap_or_default()
+}
+
+/// Record the algorithm of a new vault.
+pub(crate) fn register(env: &Env, vault_id: u32, alg: HashAlg) {
+    if alg != HashAlg::Sha256 {
+        env.storage()
+            .instance()
+            .set(&DataKey::HashAlg(vault_id), &alg);
+    }
+}
+
+/// Give a renewed vault's successor the same algorithm, since it keeps the milestone hash.
+pub(crate) fn inherit(env: &Env, from: u32, to: u32) {
+    register(env, to, alg(env, from));
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Hash algorithm a vault's commitments are checked with.
+    pub fn get_hash_alg(env: Env, vault_id: u32) -> HashAlg {
+        alg(&env, vault_id)
+    }
+}
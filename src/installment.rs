@@ -0,0 +1,224 @@
+//! Installment vaults: the stake is paid in on a schedule, and a missed deposit fails it.
+//!
+//! A vault created with a non-zero `VaultOptions::installments.count` escrows its first
+//! deposit at creation, so `amount` must equal `InstallmentSchedule::amount`. Installment `i`
+//! is due at `start_timestamp + i * interval` and must be paid with `pay_installment`
+//! before `due + grace_period`; paying early is fine. The window of the last installment has to
+//! close by `end_timestamp`, so by the deadline a vault is either fully paid or overdue.
+//!
+//! Once an installment's window has closed unpaid, anyone may `check_installment` to fail
+//! the vault, redirecting whatever has accumulated so far to the failure destination.
+//! `release_funds` fails with `InvalidStatus` until every installment is paid; after that
+//! the vault releases its whole escrow as usual. `ProductivityVault::amount` is what has
+//! been paid in, so redirects and cancellations settle exactly the accumulated balance.
+
+use soroban_sdk::{contractimpl, contracttype, token, vec, Address, Env};
+
+use crate::{
+    burn, callback, category, check_amount, check_balance, check_token, events, load_vault,
+    privacy, settle, streak, yield_adapter, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Upper bound on `InstallmentSchedule::count`, e.g. weekly deposits for a year.
+pub const MAX_INSTALLMENTS: u32 = 52;
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InstallmentSchedule {
+    /// Number of deposits, the one made at creation included, up to `MAX_INSTALLMENTS`. `0`
+    /// (the default) makes a vault without installments.
+    pub count: u32,
+    /// Size of every deposit.
+    pub amount: i128,
+    /// Seconds between due dates.
+    pub interval: u64,
+    /// Seconds an installment may be late before the vault can be failed.
+    pub grace_period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstallmentState {
+    pub schedule: InstallmentSchedule,
+    /// Installments paid so far; the next one due has this index.
+    pub paid: u32,
+}
+
+fn load(env: &Env, vault_id: u32) -> Option<InstallmentState> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Installments(vault_id))
+}
+
+/// End of installment `index`'s window, `start + index * interval + grace_period`.
+fn closes_at(vault: &ProductivityVault, schedule: &InstallmentSchedule, index: u32) -> Option<u64> {
+    schedule
+        .interval
+        .checked_mul(index as u64)
+        .and_then(|offset| vault.start_timestamp.checked_add(offset))
+        .and_then(|due| due.checked_add(schedule.grace_period))
+}
+
+/// Validate `schedule` for the new vault `vault_id` and record it. Called by `create_vault`
+/// after `store_new_vault`.
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    schedule: InstallmentSchedule,
+) -> Result<(), Error> {
+    if schedule.count == 0 {
+        return Ok(());
+    }
+    if schedule.count > MAX_INSTALLMENTS || (schedule.count > 1 && schedule.interval == 0) {
+        return Err(Error::InvalidPeriod);
+    }
+    if schedule.amount != vault.amount {
+        return Err(Error::InvalidAmount);
+    }
+    let total = schedule
+        .amount
+        .checked_mul(schedule.count as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+    check_amount(total)?;
+    match closes_at(vault, &schedule, schedule.count - 1) {
+        Some(last) if last <= vault.end_timestamp => {}
+        _ => return Err(Error::InvalidPeriod),
+    }
+    env.storage().instance().set(
+        &DataKey::Installments(vault_id),
+        &InstallmentState { schedule, paid: 1 },
+    );
+    Ok(())
+}
+
+/// Whether `vault_id` still has installments to pay.
+pub(crate) fn outstanding(env: &Env, vault_id: u32) -> bool {
+    load(env, vault_id).is_some_and(|state| state.paid < state.schedule.count)
+}
+
+/// Installment state for the per-installment entrypoints: the vault must be an Active
+/// installment vault with an installment left to pay.
+fn load_pending(env: &Env, vault_id: u32) -> Result<(ProductivityVault, InstallmentState), Error> {
+    let vault = load_vault(env, vault_id)?;
+    let state = load(env, vault_id).ok_or(Error::InvalidStatus)?;
+    if vault.status != VaultStatus::Active {
+        return Err(Error::AlreadySettled);
+    }
+    if state.paid == state.schedule.count {
+        return Err(Error::PeriodAlreadySettled);
+    }
+    Ok((vault, state))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Pay the next installment of an installment vault from the creator. Returns the index
+    /// of the installment paid.
+    ///
+    /// Fails with `InvalidStatus` for other vaults, `PeriodAlreadySettled` once every
+    /// installment is paid, `MilestoneExpired` once the installment's window has closed and
+    /// `InsufficientBalance` when the creator cannot cover it.
+    pub fn pay_installment(env: Env, vault_id: u32, usdc_token: Address) -> Result<u32, Error> {
+        let (mut vault, mut state) = load_pending(&env, vault_id)?;
+        vault.creator.require_auth();
+        check_token(&vault, &usdc_token)?;
+        let index = state.paid;
+        let closes = closes_at(&vault, &state.schedule, index).ok_or(Error::InvalidTimestamps)?;
+        if env.ledger().timestamp() >= closes {
+            return Err(Error::MilestoneExpired);
+        }
+        let amount = state.schedule.amount;
+        check_balance(&env, &usdc_token, &vault.creator, amount)?;
+
+        vault.amount = vault
+            .amount
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        category::record_stake(&env, &vault, amount)?;
+        state.paid += 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &vault);
+        env.storage()
+            .instance()
+            .set(&DataKey::Installments(vault_id), &state);
+        let adapter = yield_adapter::top_up(&env, vault_id, amount)?;
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &vault.creator,
+            &env.current_contract_address(),
+            &amount,
+        );
+        if let Some(adapter) = adapter {
+            yield_adapter::deposit(&env, &usdc_token, &adapter, amount);
+        }
+
+        events::installment_paid(
+            &env,
+            vault_id,
+            &vault.creator,
+            index,
+            privacy::amount(&vault, amount),
+        );
+        Ok(index)
+    }
+
+    /// Keeper call: fail an installment vault whose next installment's window closed
+    /// unpaid, redirecting what it accumulated to the failure destination. Permissionless;
+    /// fails with `InvalidTimestamp` while the window is still open.
+    pub fn check_installment(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let (mut vault, state) = load_pending(&env, vault_id)?;
+        let index = state.paid;
+        let closes = closes_at(&vault, &state.schedule, index).ok_or(Error::InvalidTimestamps)?;
+        if env.ledger().timestamp() < closes {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let recipient = burn::failure_recipient(&env, vault_id, &vault);
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &vec![&env, (recipient.clone(), 1)],
+            VaultStatus::Failed,
+            env.current_contract_address(),
+        )?;
+
+        let forfeited = burn::forfeit(&env, &usdc_token, &parts)?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::installment_missed(&env, vault_id, &vault.creator, index);
+        events::funds_redirected(
+            &env,
+            vault_id,
+            &recipient,
+            &privacy::breakdown(&env, &vault, &parts),
+        );
+        burn::publish(&env, vault_id, &vault, forfeited);
+        yield_adapter::publish(&env, vault_id, &vault);
+        streak::publish_update(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// Schedule and progress of an installment vault, or `None` for other vaults. The
+    /// deposit size is zero for private vaults.
+    pub fn get_installments(env: Env, vault_id: u32) -> Option<InstallmentState> {
+        let mut state = load(&env, vault_id)?;
+        if privacy::is_private(&env, vault_id) {
+            state.schedule.amount = 0;
+        }
+        Some(state)
+    }
+
+    /// Last second to pay the next installment, or `None` once every installment is paid
+    /// (or for other vaults).
+    pub fn get_next_installment_deadline(env: Env, vault_id: u32) -> Option<u64> {
+        let state = load(&env, vault_id).filter(|state| state.paid < state.schedule.count)?;
+        let vault = load_vault(&env, vault_id).ok()?;
+        closes_at(&vault, &state.schedule, state.paid).map(|closes| closes - 1)
+    }
+}
@@ -0,0 +1,191 @@
+//! Joint vaults: every co-creator must complete their own milestone.
+//!
+//! A joint vault is a group vault (see `group`) whose participants each bring their own
+//! milestone and a fixed stake. `create_joint_vault` lists `(participant, amount,
+//! milestone_hash)` for every member and the vault waits, `Pending`, for each of them to
+//! `join_vault` with exactly their amount. Once Active, the verifier (or, without one, each
+//! participant for themselves) validates participants one by one with
+//! `validate_participant`. The vault counts as validated only when the last of them is.
+//!
+//! Settlement is all or nothing. `release_funds` requires every participant to be
+//! validated; the verifier-timeout release is not available. Otherwise the whole pot is
+//! redirected at the deadline, including the stakes of participants who did complete, and
+//! `joint_failed` lists who was and was not validated. Cancellation and unfunded expiry
+//! refund each participant their own stake, like any group vault.
+//!
+//! The vault's own `milestone_hash` is the sha256 of the members' hashes concatenated in
+//! list order, so the record still commits to every milestone.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env, Vec};
+
+use crate::group::GroupPayout;
+use crate::{
+    check_amount, events, load_vault, reputation, DataKey, DataKeyExt, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// One participant of a joint vault, as returned by `get_joint`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JointMember {
+    pub participant: Address,
+    /// Stake the participant must join with.
+    pub amount: i128,
+    pub milestone_hash: BytesN<32>,
+    pub validated: bool,
+}
+
+fn load_members(env: &Env, vault_id: u32) -> Option<Vec<JointMember>> {
+    env.storage().instance().get(&DataKeyExt::Joint(vault_id))
+}
+
+/// Whether `vault_id` is a joint vault.
+pub(crate) fn is_joint(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKeyExt::Joint(vault_id))
+}
+
+/// Check a `join_vault` stake against the participant's fixed amount (`InvalidAmount`).
+pub(crate) fn check_stake(
+    env: &Env,
+    vault_id: u32,
+    participant: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let Some(members) = load_members(env, vault_id) else {
+        return Ok(());
+    };
+    match members.iter().find(|m| m.participant == *participant) {
+        Some(member) if member.amount == amount => Ok(()),
+        _ => Err(Error::InvalidAmount),
+    }
+}
+
+/// Publish `joint_failed` for a joint vault about to be redirected.
+pub(crate) fn publish_failure(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    let Some(members) = load_members(env, vault_id) else {
+        return;
+    };
+    let mut validated = Vec::new(env);
+    let mut missing = Vec::new(env);
+    for member in members.iter() {
+        if member.validated {
+            validated.push_back(member.participant);
+        } else {
+            missing.push_back(member.participant);
+        }
+    }
+    events::joint_failed(env, vault_id, &vault.creator, &validated, &missing);
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Create a `Pending` joint vault for `members`, each `(participant, amount,
+    /// milestone_hash)`. The pot pays out per `payout` once every participant is validated.
+    ///
+    /// Fails with `InvalidAmount` for a non-positive stake or an oversized total, and
+    /// otherwise validates like `create_group_vault`.
+    pub fn create_joint_vault(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        members: Vec<(Address, i128, BytesN<32>)>,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        verifier: Option<Address>,
+        success_destination: Address,
+        failure_destination: Address,
+        payout: GroupPayout,
+    ) -> Result<u32, Error> {
+        let mut participants = Vec::new(&env);
+        let mut hashes = Bytes::new(&env);
+        let mut joint = Vec::new(&env);
+        let mut total: i128 = 0;
+        for (participant, amount, milestone_hash) in members.iter() {
+            check_amount(amount)?;
+            total = total.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            participants.push_back(participant.clone());
+            hashes.append(&milestone_hash.clone().into());
+            joint.push_back(JointMember {
+                participant,
+                amount,
+                milestone_hash,
+                validated: false,
+            });
+        }
+        check_amount(total)?;
+
+        let vault_id = Self::create_group_vault(
+            env.clone(),
+            usdc_token,
+            creator,
+            participants,
+            start_timestamp,
+            end_timestamp,
+            env.crypto().sha256(&hashes).into(),
+            verifier,
+            success_destination,
+            failure_destination,
+            payout,
+        )?;
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Joint(vault_id), &joint);
+        Ok(vault_id)
+    }
+
+    /// Validate one participant's milestone: by the verifier, or by the participant when
+    /// the vault has none. Validating the last participant validates the vault.
+    ///
+    /// Fails with `InvalidStatus` for vaults that are not joint or a participant already
+    /// validated, `NotAuthorized` for an address that is not a participant, `VaultNotActive`
+    /// unless the vault is Active and `MilestoneExpired` from the deadline on.
+    pub fn validate_participant(
+        env: Env,
+        vault_id: u32,
+        participant: Address,
+    ) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        let mut members = load_members(&env, vault_id).ok_or(Error::InvalidStatus)?;
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        let validator = vault.verifier.clone().unwrap_or(participant.clone());
+        validator.require_auth();
+        if !vault.validation_open(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        let index = members
+            .iter()
+            .position(|m| m.participant == participant)
+            .ok_or(Error::NotAuthorized)? as u32;
+        let mut member = members.get_unchecked(index);
+        if member.validated {
+            return Err(Error::InvalidStatus);
+        }
+
+        member.validated = true;
+        members.set(index, member);
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Joint(vault_id), &members);
+        let complete = members.iter().all(|m| m.validated);
+        if complete {
+            vault.milestone_validated = true;
+            env.storage()
+                .instance()
+                .set(&DataKey::Vault(vault_id), &vault);
+            reputation::record_response(&env, vault_id, &vault);
+        }
+
+        events::participant_validated(&env, vault_id, &validator, &participant);
+        if complete {
+            events::milestone_validated(&env, vault_id, &validator);
+        }
+        Ok(complete)
+    }
+
+    /// Members of a joint vault and their validation, or `None` for other vaults.
+    pub fn get_joint(env: Env, vault_id: u32) -> Option<Vec<JointMember>> {
+        load_members(&env, vault_id)
+    }
+}
@@ -0,0 +1,151 @@
+//! On-chain leaderboards of the top creators.
+//!
+//! Two boards of at most `MAX_LEADERBOARD` entries are kept sorted in storage, highest score
+//! first: creators by current streak and by lifetime completed stake, counting only what
+//! the creator staked themselves (see `credited_stake`). `transition` updates
+//! them on every completion or failure, touching only the settling creator's entry, so a
+//! settlement writes at most two bounded lists whatever the number of creators.
+//!
+//! - An address is listed at most once; a new score moves its existing entry.
+//! - Ties keep whoever reached the score first ahead.
+//! - A score that no longer makes the board, or a streak reset to zero, drops the entry,
+//!   and inserting into a full board evicts its last entry.
+//!
+//! Settlements that do not count toward reputation (see `reputation::counts`) leave both
+//! boards alone.
+//!
+//! Because only the settling creator is re-ranked, someone who fell off the board returns
+//! at their next settlement rather than as soon as the board frees up.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::{
+    group, recurring, reputation, streak, wager, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Entries kept per board.
+pub const MAX_LEADERBOARD: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeaderboardKind {
+    /// `StreakInfo::current` of each creator.
+    Streak = 0,
+    /// Total own stake of each creator's completed vaults.
+    CompletedStake = 1,
+}
+
+fn board(env: &Env, kind: LeaderboardKind) -> Vec<(Address, i128)> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Leaderboard(kind))
+        .unwrap_or(Vec::new(env))
+}
+
+fn completed_stake(env: &Env, creator: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CompletedStake(creator.clone()))
+        .unwrap_or(0)
+}
+
+/// Give `address` `score` on `board`, which holds at most `capacity` entries sorted by
+/// descending score. A non-positive score removes the address. Returns whether the board
+/// changed.
+pub(crate) fn place(
+    board: &mut Vec<(Address, i128)>,
+    address: &Address,
+    score: i128,
+    capacity: u32,
+) -> bool {
+    let current = board.iter().position(|(entry, _)| entry == *address);
+    if let Some(index) = current {
+        if board.get_unchecked(index as u32).1 == score {
+            return false;
+        }
+        board.remove(index as u32);
+    }
+    if score > 0 {
+        // After every entry with the same score: they reached it first.
+        let rank = board
+            .iter()
+            .position(|(_, entry)| entry < score)
+            .map_or(board.len(), |rank| rank as u32);
+        if rank < capacity {
+            board.insert(rank, (address.clone(), score));
+            if board.len() > capacity {
+                board.pop_back();
+            }
+            return true;
+        }
+    }
+    current.is_some()
+}
+
+fn update(env: &Env, kind: LeaderboardKind, address: &Address, score: i128) {
+    let mut entries = board(env, kind);
+    if place(&mut entries, address, score, MAX_LEADERBOARD) {
+        env.storage()
+            .instance()
+            .set(&DataKey::Leaderboard(kind), &entries);
+    }
+}
+
+/// What a completed `vault` adds to its creator's completed stake: their own share of the
+/// escrow. That is one side's stake of a wager, the creator's own contribution to a group or
+/// joint vault (nothing if they only organised it), and the validated slices of a recurring
+/// vault.
+fn credited_stake(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Result<i128, Error> {
+    if let Some(stake) = wager::stake(env, vault_id) {
+        return Ok(stake);
+    }
+    if let Some(contribution) = group::contribution(env, vault_id, &vault.creator) {
+        return Ok(contribution);
+    }
+    Ok(recurring::validated_stake(env, vault_id)?.unwrap_or(vault.amount))
+}
+
+/// Re-rank `vault`'s creator after its terminal transition. Called by `transition` once the
+/// streak has been recorded.
+pub(crate) fn record(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Result<(), Error> {
+    if !reputation::counts(env, vault_id, vault) {
+        return Ok(());
+    }
+    match vault.status {
+        VaultStatus::Completed => {
+            let stake = credited_stake(env, vault_id, vault)?;
+            let total = completed_stake(env, &vault.creator)
+                .checked_add(stake)
+                .ok_or(Error::ArithmeticOverflow)?;
+            env.storage()
+                .instance()
+                .set(&DataKey::CompletedStake(vault.creator.clone()), &total);
+            update(env, LeaderboardKind::CompletedStake, &vault.creator, total);
+        }
+        VaultStatus::Failed => {}
+        _ => return Ok(()),
+    }
+    let current = streak::load(env, &vault.creator).current;
+    update(
+        env,
+        LeaderboardKind::Streak,
+        &vault.creator,
+        current as i128,
+    );
+    Ok(())
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Top `limit` entries of the `kind` board as `(creator, score)`, best first.
+    pub fn get_leaderboard(env: Env, kind: LeaderboardKind, limit: u32) -> Vec<(Address, i128)> {
+        let entries = board(&env, kind);
+        entries.slice(0..limit.min(entries.len()))
+    }
+
+    /// Total own stake of `creator`'s completed vaults (see `LeaderboardKind::CompletedStake`).
+    pub fn get_completed_stake(env: Env, creator: Address) -> i128 {
+        completed_stake(&env, &creator)
+    }
+}
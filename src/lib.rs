@@ -2,9 +2,139 @@
 #![allow(clippy::too_many_arguments)]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, token, Address, BytesN, Env, Symbol,
+    contract, contracterror, contractimpl, contracttype, token, vec, Address, BytesN, Env, Symbol,
+    Vec,
 };
 
+use hashing::HashAlg;
+use installment::InstallmentSchedule;
+use leaderboard::LeaderboardKind;
+use privacy::Visibility;
+use recommit::VaultTerms;
+use savings::VaultMode;
+
+pub mod badge;
+pub mod bounty;
+pub mod burn;
+pub mod callback;
+pub mod category;
+pub mod charity;
+pub mod claim;
+pub mod config;
+pub mod creator_transfer;
+pub mod dependency;
+pub mod emergency;
+pub mod events;
+pub mod fees;
+pub mod funding;
+pub mod gift;
+pub mod grading;
+pub mod group;
+pub mod hashing;
+pub mod installment;
+pub mod joint;
+pub mod leaderboard;
+pub mod oracle;
+pub mod partner;
+pub mod payout;
+pub mod privacy;
+pub mod proofs;
+pub mod recommit;
+pub mod recurring;
+pub mod renewal;
+pub mod reputation;
+pub mod reveal;
+pub mod savings;
+pub mod snooze;
+pub mod sponsor;
+pub mod streak;
+pub mod template;
+#[cfg(test)]
+mod tests_badge;
+#[cfg(test)]
+mod tests_bounty;
+#[cfg(test)]
+mod tests_burn;
+#[cfg(test)]
+mod tests_callback;
+#[cfg(test)]
+mod tests_category;
+#[cfg(test)]
+mod tests_charity;
+#[cfg(test)]
+mod tests_claim;
+#[cfg(test)]
+mod tests_config;
+#[cfg(test)]
+mod tests_creator_transfer;
+#[cfg(test)]
+mod tests_dependency;
+#[cfg(test)]
+mod tests_destinations;
+#[cfg(test)]
+mod tests_emergency;
+#[cfg(test)]
+mod tests_events;
+#[cfg(test)]
+mod tests_fees;
+#[cfg(test)]
+mod tests_funding;
+#[cfg(test)]
+mod tests_gift;
+#[cfg(test)]
+mod tests_grading;
+#[cfg(test)]
+mod tests_group;
+#[cfg(test)]
+mod tests_hashing;
+#[cfg(test)]
+mod tests_installment;
+#[cfg(test)]
+mod tests_invariants;
+#[cfg(test)]
+mod tests_joint;
+#[cfg(test)]
+mod tests_leaderboard;
+#[cfg(test)]
+mod tests_oracle;
+#[cfg(test)]
+mod tests_partner;
+#[cfg(test)]
+mod tests_privacy;
+#[cfg(test)]
+mod tests_proofs;
+#[cfg(test)]
+mod tests_recommit;
+#[cfg(test)]
+mod tests_recurring;
+#[cfg(test)]
+mod tests_renewal;
+#[cfg(test)]
+mod tests_reputation;
+#[cfg(test)]
+mod tests_reveal;
+#[cfg(test)]
+mod tests_savings;
+#[cfg(test)]
+mod tests_snooze;
+#[cfg(test)]
+mod tests_sponsor;
+#[cfg(test)]
+mod tests_streak;
+#[cfg(test)]
+mod tests_template;
+#[cfg(test)]
+mod tests_wager;
+#[cfg(test)]
+mod tests_witness;
+#[cfg(test)]
+mod tests_yield;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+pub mod wager;
+pub mod witness;
+pub mod yield_adapter;
+
 /// Upper bound for vault creation amounts to limit pathological transfers.
 const MAX_AMOUNT: i128 = 1_000_000_000_000_000;
 
@@ -15,6 +145,9 @@ const MAX_AMOUNT: i128 = 1_000_000_000_000_000;
 // Contract-specific errors used in revert paths. Follows Soroban error
 // conventions: use Result<T, Error> and return Err(Error::Variant) instead
 // of generic panics where appropriate.
+//
+// The contract spec allows at most 50 variants per error enum, and this one is full. New
+// failure modes reuse the closest existing variant and extend its doc comment.
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -22,22 +155,121 @@ const MAX_AMOUNT: i128 = 1_000_000_000_000_000;
 pub enum Error {
     /// Vault with the given id does not exist.
     VaultNotFound = 1,
-    /// Caller is not authorized for this operation (e.g. not verifier/creator, or release before deadline without validation).
+    /// Caller is not authorized for this operation (e.g. not verifier/creator, or release
+    /// before deadline without validation), or named a `usdc_token` other than the vault's.
     NotAuthorized = 2,
-    /// Vault is not in Active status (e.g. already Completed, Failed, or Cancelled).
+    /// Vault is not in Active status (e.g. already Completed, Failed, or Cancelled, or a
+    /// group vault still Pending).
     VaultNotActive = 3,
-    /// Timestamp constraint violated (e.g. redirect before end_timestamp, or invalid time window).
+    /// Timestamp constraint violated (e.g. redirect before end_timestamp, or invalid time window),
+    /// or an oracle price older than `Config::max_price_age`.
     InvalidTimestamp = 4,
     /// Validation is no longer allowed because current time is at or past end_timestamp.
     MilestoneExpired = 5,
     /// Vault is in an invalid status for the requested operation.
     InvalidStatus = 6,
-    /// Amount must be positive (e.g. create_vault amount <= 0).
+    /// Amount must be positive (e.g. create_vault amount <= 0), or a joint vault stake
+    /// differs from the participant's listed amount.
     InvalidAmount = 7,
     /// start_timestamp must be strictly less than end_timestamp.
     InvalidTimestamps = 8,
     /// Vault duration (end − start) exceeds MAX_VAULT_DURATION.
     DurationTooLong = 9,
+    /// Vault already reached a terminal status (Completed, Failed or Cancelled); its funds
+    /// have been paid out and it can never be settled again.
+    AlreadySettled = 10,
+    /// Checked payout arithmetic overflowed.
+    ArithmeticOverflow = 11,
+    /// Basis-point value is above 10_000 (100%).
+    InvalidBps = 12,
+    /// Payout destination is the vault contract itself, which would strand the funds.
+    InvalidDestination = 13,
+    /// Creator's token balance cannot cover the stake. The shortfall is
+    /// `amount - token.balance(creator)` at the time of the call.
+    InsufficientBalance = 14,
+    /// `request_changes` was called before any proof was submitted.
+    NoProofSubmitted = 15,
+    /// The vault already holds `MAX_PROOF_SUBMISSIONS` proofs.
+    TooManyProofs = 16,
+    /// Recurring schedule is malformed, a period index is out of range, or a renewal period
+    /// is shorter than the vault or missing.
+    InvalidPeriod = 17,
+    /// The period was already validated or redirected as missed.
+    PeriodAlreadySettled = 18,
+    /// Group participant list, or a vault's `depends_on` or witnesses, is empty, too long or
+    /// has duplicates, or the witnesses include the creator.
+    InvalidParticipants = 19,
+    /// Participant already joined this group vault, a verifier already approved an emergency
+    /// unlock, or a witness already attested.
+    AlreadyJoined = 20,
+    /// The admin was already set. Kept for its code: the constructor now sets the admin once,
+    /// at deployment.
+    AlreadyInitialized = 21,
+    /// Admin-only call on an instance without an admin, or a price lookup for a quote asset
+    /// without an oracle or a token without a positive price.
+    NotInitialized = 22,
+    /// A creator named themselves as their own referrer.
+    SelfReferral = 23,
+    /// No registered charity at the given index.
+    CharityNotFound = 24,
+    /// The address is already in the charity registry.
+    CharityAlreadyRegistered = 25,
+    /// The vault requires a registered failure destination and this one is not.
+    UnregisteredDestination = 26,
+    /// The accountability partner is the creator.
+    InvalidPartner = 27,
+    /// No cancellation is awaiting the partner's approval, or no emergency approval of this
+    /// verifier to revoke.
+    NoPendingCancellation = 28,
+    /// The pending cancellation was not approved within `CANCEL_APPROVAL_WINDOW`.
+    CancellationExpired = 29,
+    /// No template with the given id.
+    TemplateNotFound = 30,
+    /// The owner already has `MAX_TEMPLATES_PER_OWNER` templates.
+    TooManyTemplates = 31,
+    /// The vault was created with `destinations_locked`.
+    DestinationsLocked = 32,
+    /// The revealed description and salt do not hash to the vault's `milestone_hash`.
+    RevealMismatch = 33,
+    /// The vault requires its milestone to be revealed before this release.
+    RevealRequired = 34,
+    /// The milestone was already revealed.
+    AlreadyRevealed = 35,
+    /// The yield adapter returned less than the vault deposited and
+    /// `Config::yield_shortfall` is `Revert`.
+    YieldShortfall = 36,
+    /// The proposed creator is the current creator or this contract, or a gift's recipient
+    /// is its gifter.
+    InvalidCreator = 37,
+    /// No creator transfer is awaiting acceptance.
+    NoPendingTransfer = 38,
+    /// The category is not in the admin's list.
+    UnknownCategory = 39,
+    /// The category is already allowed.
+    CategoryAlreadyListed = 40,
+    /// `MAX_CATEGORIES` categories are already listed.
+    TooManyCategories = 41,
+    /// The vault was not created with `allow_graded`.
+    GradingNotAllowed = 42,
+    /// The wager's challenger is its creator, or its verifier is one of the two parties.
+    InvalidWagerParties = 43,
+    /// The vault was already snoozed once.
+    AlreadySnoozed = 44,
+    /// Snooze of zero seconds or more than `Config::max_snooze_seconds`.
+    InvalidSnooze = 45,
+    /// The preimage does not hash to the bounty's `milestone_hash`, or does not match the
+    /// claimer's commitment.
+    InvalidPreimage = 46,
+    /// A commit-reveal bounty claim without a commitment from an earlier ledger.
+    ClaimNotCommitted = 47,
+    /// A savings lock with a verifier, a destination other than its creator, or a milestone
+    /// option.
+    InvalidSavingsLock = 48,
+    /// Cancelling a savings lock before its deadline while early withdrawal is disabled.
+    SavingsLocked = 49,
+    /// Validating or releasing a vault whose dependencies have not all completed, or
+    /// creating one that depends on a failed or cancelled vault.
+    DependencyNotMet = 50,
 }
 
 /// Maximum allowed vault duration: 365 days in seconds.
@@ -54,6 +286,8 @@ pub enum VaultStatus {
     Completed = 1,
     Failed = 2,
     Cancelled = 3,
+    /// Group vault waiting for every listed participant to join; activates once they have.
+    Pending = 4,
 }
 
 /// Core vault record persisted in contract storage.
@@ -62,8 +296,13 @@ pub enum VaultStatus {
 pub struct ProductivityVault {
     /// Address that created (and funded) the vault.
     pub creator: Address,
-    /// USDC amount locked in the vault (in stroops / smallest unit).
+    /// USDC amount locked in the vault (in stroops / smallest unit). For recurring vaults this
+    /// is what is still escrowed; paid-out period slices are deducted. For installment vaults
+    /// it is what has been paid in so far.
     pub amount: i128,
+    /// Token the stake is escrowed in, fixed at creation. Every payout and top-up of the vault
+    /// is made in it; entrypoints reject any other `usdc_token` (`NotAuthorized`).
+    pub token: Address,
     /// Ledger timestamp when the commitment period starts.
     pub start_timestamp: u64,
     /// Ledger timestamp after which deadline-based release is allowed.
@@ -84,6 +323,119 @@ pub struct ProductivityVault {
     /// Set to `true` once the verifier (or authorised party) calls `validate_milestone`.
     /// Used by `release_funds` to allow early release before the deadline.
     pub milestone_validated: bool,
+    /// Address that referred the creator; paid a share of the protocol fee on completion.
+    pub referrer: Option<Address>,
+    /// When set, cancelling needs this address's approval (see `partner`). It has no other
+    /// powers over the vault.
+    pub accountability_partner: Option<Address>,
+    /// When set, `update_success_destination` is disabled for this vault.
+    pub destinations_locked: bool,
+    /// When set, a vault without a verifier cannot be released until its milestone has been
+    /// revealed (see `reveal`).
+    pub reveal_required: bool,
+    /// Category the vault is filed under (see `category`).
+    pub category: Symbol,
+    /// When set, the validator may score the milestone with `validate_graded`.
+    pub allow_graded: bool,
+    /// Whether public reads and events redact the vault (see `privacy`).
+    pub visibility: Visibility,
+    /// When set, completing the vault rolls its escrow into a successor (see `renewal`).
+    pub auto_renew: bool,
+    /// `SavingsLock` for a pure time lock paying back the creator (see `savings`).
+    pub mode: VaultMode,
+}
+
+/// Optional creation settings for `create_vault`. `VaultOptions::default()` asks for none of
+/// them.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VaultOptions {
+    /// Address that referred the creator; stored as `ProductivityVault::referrer`.
+    pub referrer: Option<Address>,
+    /// Reject the vault unless its failure destination is in the charity registry.
+    pub require_registered_charity: bool,
+    /// Stored as `ProductivityVault::accountability_partner`.
+    pub accountability_partner: Option<Address>,
+    /// Stored as `ProductivityVault::destinations_locked`.
+    pub destinations_locked: bool,
+    /// Stored as `ProductivityVault::reveal_required`.
+    pub reveal_required: bool,
+    /// Listed category for the vault; `None` files it under `category::DEFAULT_CATEGORY`.
+    pub category: Option<Symbol>,
+    /// Contract notified when the vault settles (see `callback`).
+    pub callback: Option<Address>,
+    /// Stored as `ProductivityVault::allow_graded`.
+    pub allow_graded: bool,
+    /// Stored as `ProductivityVault::visibility`.
+    pub visibility: Visibility,
+    /// Stored as `ProductivityVault::auto_renew`.
+    pub auto_renew: bool,
+    /// Shift between an `auto_renew` vault's window and its successor's, in seconds; at
+    /// least the vault's duration. Ignored without `auto_renew`.
+    pub renewal_period: u64,
+    /// Stored as `ProductivityVault::mode`.
+    pub mode: VaultMode,
+    /// Earlier vaults of the same creator that must complete before this one unlocks (see
+    /// `dependency`).
+    pub depends_on: Option<Vec<u32>>,
+    /// Let the vault be redirected as soon as one of `depends_on` fails.
+    pub cascade_failure: bool,
+    /// Pay the stake in over time, `amount` at creation (see `installment`). The default
+    /// count of zero asks for none.
+    pub installments: InstallmentSchedule,
+    /// Record `success_destination` as the owner of a transferable claim on the success
+    /// payout (see `claim`).
+    pub transferable_claim: bool,
+    /// Hash function the vault's commitments are checked with (see `hashing`).
+    pub hash_alg: HashAlg,
+    /// Denominate the stake in this quote asset (see `oracle`); `amount` then caps the
+    /// converted stake.
+    pub quote_asset: Option<Symbol>,
+    /// Value the vault commits to in `quote_asset`. Ignored without `quote_asset`.
+    pub quote_amount: i128,
+    /// Burn the stake on failure instead of paying `failure_destination` (see `burn`).
+    pub burn_on_failure: bool,
+    /// Friends who may publicly attest to the milestone, at most `MAX_WITNESSES` (see
+    /// `witness`).
+    pub witnesses: Option<Vec<Address>>,
+}
+
+/// Deadline boundary rules. Every entrypoint that compares the ledger time against
+/// `end_timestamp` goes through these helpers so they can never disagree by one second.
+///
+/// The validation window is `[.., end_timestamp)` and the deadline window is
+/// `[end_timestamp, ..)`. The two are exact complements: at any instant either the
+/// milestone can still be validated or deadline-based settlement (redirect, release without
+/// validation) is open, never both and never neither. There is no grace period between them.
+impl ProductivityVault {
+    /// `true` while the milestone may still be validated: `now < end_timestamp`.
+    pub fn validation_open(&self, now: u64) -> bool {
+        now < self.end_timestamp
+    }
+
+    /// `true` once the deadline has been reached: `now >= end_timestamp`.
+    pub fn deadline_reached(&self, now: u64) -> bool {
+        !self.validation_open(now)
+    }
+
+    /// Party whose authorization validates the milestone: the verifier, or the creator when
+    /// there is none.
+    pub fn validator(&self) -> &Address {
+        self.verifier.as_ref().unwrap_or(&self.creator)
+    }
+}
+
+/// One entry in a vault's status history, recorded by every transition.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusChange {
+    pub from: VaultStatus,
+    pub to: VaultStatus,
+    /// Party whose authorization drove the change. Permissionless transitions (e.g. a
+    /// deadline-based redirect anyone may trigger) are attributed to the contract itself.
+    pub actor: Address,
+    /// Ledger timestamp of the change.
+    pub timestamp: u64,
 }
 
 // ---------------------------------------------------------------------------
@@ -95,6 +447,565 @@ pub struct ProductivityVault {
 pub enum DataKey {
     Vault(u32),
     VaultCount,
+    /// Number of vaults currently in the given status.
+    StatusCount(VaultStatus),
+    /// `Vec<StatusChange>` for a vault, oldest first.
+    VaultHistory(u32),
+    /// Number of entries in a vault's proof log; persistent storage, removed on settlement.
+    ProofCount(u32),
+    /// `RecurringState` of a recurring vault; absent for single-milestone vaults.
+    Recurring(u32),
+    /// `PeriodStatus` of period `.1` of recurring vault `.0`; absent while Pending.
+    Period(u32, u32),
+    /// `GroupState` of a group vault; absent for single-creator vaults.
+    Group(u32),
+    /// Contract admin, set by the constructor.
+    Admin,
+    /// `config::Config`; absent until the admin sets one.
+    Config,
+    /// `StreakInfo` of a creator.
+    Streak(Address),
+    /// Protocol fees accrued in a token and not yet withdrawn.
+    AccruedFees(Address),
+    /// `fees::ReferralStats` of a referrer.
+    Referral(Address),
+    /// Number of charity registry slots ever assigned.
+    CharityCount,
+    /// `charity::Charity` registered at an index; removed entries leave their slot empty.
+    Charity(u32),
+    /// Registry index of a registered charity address.
+    CharityIndex(Address),
+    /// Expiry of a cancellation awaiting the accountability partner's approval.
+    PendingCancel(u32),
+    /// Number of template ids ever assigned.
+    TemplateCount,
+    /// `template::VaultTemplate` by id; removed when deleted.
+    Template(u32),
+    /// Ids of an owner's templates, oldest first.
+    OwnerTemplates(Address),
+    /// Funder of a sponsored vault; absent when the creator staked it.
+    Funder(u32),
+    /// `reputation::Reputation` of an address.
+    Reputation(Address),
+    /// Set once the vault's verifier has been credited for responding.
+    VerifierResponded(u32),
+    /// `hash(description)` of a vault whose milestone has been revealed.
+    Revealed(u32),
+    /// `yield_adapter::YieldAdapter` registered for a token.
+    YieldAdapter(Address),
+    /// Principal currently deposited in an adapter, summed over vaults.
+    YieldPrincipal(Address),
+    /// `yield_adapter::YieldPosition` of a vault whose stake went to an adapter.
+    YieldPosition(u32),
+    /// Address proposed by `transfer_creator`, awaiting `accept_creator`.
+    PendingCreator(u32),
+    /// First creator of a vault whose creator role was transferred.
+    OriginalCreator(u32),
+    /// Admin-listed categories, in the order they were added.
+    Categories,
+    /// `category::CategoryStats` of a category.
+    CategoryStats(Symbol),
+    /// `callback::SettlementListener` notified when a vault settles.
+    Callback(u32),
+    /// Score of a vault graded below full marks by `validate_graded`.
+    Grade(u32),
+    /// Sorted entries of a `leaderboard::LeaderboardKind` board.
+    Leaderboard(LeaderboardKind),
+    /// Lifetime completed stake of a creator.
+    CompletedStake(Address),
+    /// `wager::Wager` of a peer wager vault.
+    Wager(u32),
+    /// Renewal period of an `auto_renew` vault.
+    RenewalPeriod(u32),
+    /// Vault a renewed vault rolled into.
+    Successor(u32),
+    /// Set once a vault used its snooze.
+    Snoozed(u32),
+    /// `bounty::Bounty` terms of a bounty vault.
+    Bounty(u32),
+    /// `bounty::ClaimCommitment` of a claimer on a commit-reveal bounty; temporary storage.
+    BountyCommitment(u32, Address),
+    /// `dependency::Dependencies` of a vault created with `depends_on`.
+    Dependencies(u32),
+    /// `installment::InstallmentState` of an installment vault.
+    Installments(u32),
+    /// `gift::Gift` terms of a gifted vault.
+    Gift(u32),
+    /// Live `(verifier, approved_at)` emergency approvals of a vault.
+    EmergencyApprovals(u32),
+    /// `Vec<proofs::ProofEntry>` holding entries `[.1 * PROOF_BUCKET_SIZE, ..)` of a vault's
+    /// proof log; persistent storage, removed on settlement.
+    ProofBucket(u32, u32),
+    /// Index of the proof log entry a vault's validation approved.
+    ApprovedProof(u32),
+    /// Current owner of a vault's transferable success claim.
+    ClaimOwner(u32),
+    /// `hashing::HashAlg` of a vault; absent for `Sha256`.
+    HashAlg(u32),
+    /// Funding deadline of a Pending vault; absent when it is `start_timestamp`.
+    FundingDeadline(u32),
+}
+
+/// Storage keys added once `DataKey` reached the 50 variants a contract type may have.
+/// Both enums encode as the variant name followed by its fields, so a name here must not
+/// repeat one in `DataKey`.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKeyExt {
+    /// `oracle::PriceOracle` registered for a quote asset.
+    PriceOracle(Symbol),
+    /// `oracle::QuoteTarget` of a quote-denominated vault.
+    Quote(u32),
+    /// Set for vaults that burn their stake on failure (see `burn`).
+    BurnOnFailure(u32),
+    /// Witnesses of a vault (see `witness`).
+    Witnesses(u32),
+    /// `witness::Attestation`s made on a vault, oldest first.
+    Attestations(u32),
+    /// `joint::JointMember`s of a joint vault.
+    Joint(u32),
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn load_vault(env: &Env, vault_id: u32) -> Result<ProductivityVault, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Vault(vault_id))
+        .ok_or(Error::VaultNotFound)
+}
+
+/// Reject payout destinations equal to this contract. Paying the contract "succeeds" but
+/// leaves the funds stuck in escrow with no vault tracking them.
+fn validate_destination(env: &Env, destination: &Address) -> Result<(), Error> {
+    if *destination == env.current_contract_address() {
+        return Err(Error::InvalidDestination);
+    }
+    Ok(())
+}
+
+fn is_terminal(status: VaultStatus) -> bool {
+    matches!(
+        status,
+        VaultStatus::Completed | VaultStatus::Failed | VaultStatus::Cancelled
+    )
+}
+
+/// Settlement pre-state check: the vault must be `Active`. Any terminal status means the
+/// escrow has already been paid out exactly once, so we refuse with `AlreadySettled`; a
+/// `Pending` group vault has not started and is `VaultNotActive`.
+fn require_unsettled(vault: &ProductivityVault) -> Result<(), Error> {
+    match vault.status {
+        VaultStatus::Active => Ok(()),
+        VaultStatus::Pending => Err(Error::VaultNotActive),
+        VaultStatus::Completed | VaultStatus::Failed | VaultStatus::Cancelled => {
+            Err(Error::AlreadySettled)
+        }
+    }
+}
+
+/// Creation checks shared by every way of opening a vault.
+fn check_new_vault(
+    env: &Env,
+    usdc_token: &Address,
+    vault: &ProductivityVault,
+) -> Result<(), Error> {
+    check_amount(vault.amount)?;
+    check_terms(env, vault)?;
+    check_balance(env, usdc_token, &vault.creator, vault.amount)
+}
+
+fn check_amount(amount: i128) -> Result<(), Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if amount > MAX_AMOUNT {
+        return Err(Error::InvalidAmount);
+    }
+    Ok(())
+}
+
+/// Time window, destination and referrer checks.
+fn check_terms(env: &Env, vault: &ProductivityVault) -> Result<(), Error> {
+    if vault.referrer.as_ref() == Some(&vault.creator) {
+        return Err(Error::SelfReferral);
+    }
+    if vault.accountability_partner.as_ref() == Some(&vault.creator) {
+        return Err(Error::InvalidPartner);
+    }
+
+    // Validate that start_timestamp is strictly before end_timestamp.
+    if vault.end_timestamp <= vault.start_timestamp {
+        return Err(Error::InvalidTimestamps);
+    }
+
+    if vault.end_timestamp - vault.start_timestamp > MAX_VAULT_DURATION {
+        return Err(Error::DurationTooLong);
+    }
+
+    validate_destination(env, &vault.success_destination)?;
+    validate_destination(env, &vault.failure_destination)
+}
+
+/// Pre-flight balance check so wallets get a clear error instead of a token trap. The
+/// caller's transfer stays the source of truth: if the balance changes in between, the
+/// transfer traps and the whole invocation reverts.
+fn check_balance(
+    env: &Env,
+    usdc_token: &Address,
+    payer: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    if token::Client::new(env, usdc_token).balance(payer) < amount {
+        return Err(Error::InsufficientBalance);
+    }
+    Ok(())
+}
+
+/// Reject a `usdc_token` argument other than the token `vault` is staked in. `settle` checks
+/// it for every settlement; paths that move the vault's tokens without settling check it
+/// themselves.
+fn check_token(vault: &ProductivityVault, usdc_token: &Address) -> Result<(), Error> {
+    if *usdc_token != vault.token {
+        return Err(Error::NotAuthorized);
+    }
+    Ok(())
+}
+
+/// Assign the next vault id and persist `vault` in its initial status (Active, or Pending for
+/// group vaults). The caller pulls the stake.
+fn store_new_vault(env: &Env, vault: &ProductivityVault) -> Result<u32, Error> {
+    let mut vault_count: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::VaultCount)
+        .unwrap_or(0);
+    let vault_id = vault_count;
+    vault_count += 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::VaultCount, &vault_count);
+
+    env.storage()
+        .instance()
+        .set(&DataKey::Vault(vault_id), vault);
+    let count = status_count(env, vault.status)
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    set_status_count(env, vault.status, count);
+    category::record_created(env, vault)?;
+    Ok(vault_id)
+}
+
+/// Store and fund a new vault for `create_vault` and `recommit`. `escrowed` is what this
+/// contract already holds for it, at most `amount`: the creator pays in the rest of the
+/// stake and gets back whatever a quote-denominated stake leaves unused. The caller
+/// publishes `vault_created`.
+fn open_vault(
+    env: &Env,
+    usdc_token: &Address,
+    creator: Address,
+    amount: i128,
+    terms: VaultTerms,
+    options: VaultOptions,
+    escrowed: i128,
+) -> Result<(u32, ProductivityVault), Error> {
+    if options.require_registered_charity
+        && charity::index_of(env, &terms.failure_destination).is_none()
+    {
+        return Err(Error::UnregisteredDestination);
+    }
+    let category = category::resolve(env, options.category)?;
+    let (amount, quote) = oracle::stake(
+        env,
+        usdc_token,
+        amount,
+        options.quote_asset,
+        options.quote_amount,
+    )?;
+
+    let vault = ProductivityVault {
+        creator,
+        amount,
+        token: usdc_token.clone(),
+        start_timestamp: terms.start_timestamp,
+        end_timestamp: terms.end_timestamp,
+        milestone_hash: terms.milestone_hash,
+        verifier: terms.verifier,
+        success_destination: terms.success_destination,
+        failure_destination: terms.failure_destination,
+        status: VaultStatus::Active,
+        milestone_validated: false,
+        referrer: options.referrer,
+        accountability_partner: options.accountability_partner,
+        destinations_locked: options.destinations_locked,
+        reveal_required: options.reveal_required,
+        category,
+        allow_graded: options.allow_graded,
+        visibility: options.visibility,
+        auto_renew: options.auto_renew,
+        mode: options.mode,
+    };
+    savings::check(&vault)?;
+    let owed = amount
+        .checked_sub(escrowed)
+        .ok_or(Error::ArithmeticOverflow)?;
+    check_amount(amount)?;
+    check_terms(env, &vault)?;
+    if owed > 0 {
+        check_balance(env, usdc_token, &vault.creator, owed)?;
+    }
+    let vault_id = store_new_vault(env, &vault)?;
+    renewal::register(env, vault_id, &vault, options.renewal_period)?;
+    dependency::register(
+        env,
+        vault_id,
+        &vault,
+        options.depends_on,
+        options.cascade_failure,
+    )?;
+    installment::register(env, vault_id, &vault, options.installments)?;
+    claim::register(env, vault_id, &vault, options.transferable_claim)?;
+    hashing::register(env, vault_id, options.hash_alg);
+    oracle::register(env, vault_id, quote);
+    burn::register(env, vault_id, &vault, options.burn_on_failure)?;
+    witness::register(env, vault_id, &vault, options.witnesses)?;
+    let adapter = yield_adapter::open_position(env, vault_id, usdc_token, amount)?;
+    callback::register(env, vault_id, &options.callback);
+
+    // Settle the stake with the creator. Storage is written first so the token calls are
+    // the last interactions; a failed transfer reverts the vault record with it.
+    let token_client = token::Client::new(env, usdc_token);
+    let contract = env.current_contract_address();
+    if owed > 0 {
+        token_client.transfer(&vault.creator, &contract, &owed);
+    } else if owed < 0 {
+        token_client.transfer(&contract, &vault.creator, &-owed);
+    }
+    if let Some(adapter) = adapter {
+        yield_adapter::deposit(env, usdc_token, &adapter, amount);
+    }
+    Ok((vault_id, vault))
+}
+
+/// The lifecycle graph. This match is the single definition of which status changes exist.
+pub fn is_allowed_transition(from: VaultStatus, to: VaultStatus) -> bool {
+    matches!(
+        (from, to),
+        (VaultStatus::Pending, VaultStatus::Active)
+            | (VaultStatus::Pending, VaultStatus::Cancelled)
+            | (VaultStatus::Active, VaultStatus::Completed)
+            | (VaultStatus::Active, VaultStatus::Failed)
+            | (VaultStatus::Active, VaultStatus::Cancelled)
+    )
+}
+
+fn status_count(env: &Env, status: VaultStatus) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StatusCount(status))
+        .unwrap_or(0)
+}
+
+fn set_status_count(env: &Env, status: VaultStatus, count: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::StatusCount(status), &count);
+}
+
+/// Move `vault` to `to`, persisting the record, the per-status counters, the history and, for
+/// terminal statuses, the creator's streak, reputation and category statistics.
+///
+/// This is the only place allowed to mutate `ProductivityVault::status`. Changes outside the
+/// graph in `is_allowed_transition` are rejected: `AlreadySettled` when the vault is already
+/// terminal, `InvalidStatus` otherwise. Nothing is written on rejection.
+fn transition(
+    env: &Env,
+    vault_id: u32,
+    vault: &mut ProductivityVault,
+    to: VaultStatus,
+    actor: Address,
+) -> Result<(), Error> {
+    let from = vault.status;
+    if !is_allowed_transition(from, to) {
+        if is_terminal(from) {
+            return Err(Error::AlreadySettled);
+        }
+        return Err(Error::InvalidStatus);
+    }
+
+    let from_count = status_count(env, from)
+        .checked_sub(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let to_count = status_count(env, to)
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    vault.status = to;
+    env.storage()
+        .instance()
+        .set(&DataKey::Vault(vault_id), vault);
+    set_status_count(env, from, from_count);
+    set_status_count(env, to, to_count);
+
+    let history_key = DataKey::VaultHistory(vault_id);
+    let mut history: Vec<StatusChange> = env
+        .storage()
+        .instance()
+        .get(&history_key)
+        .unwrap_or(Vec::new(env));
+    history.push_back(StatusChange {
+        from,
+        to,
+        actor,
+        timestamp: env.ledger().timestamp(),
+    });
+    env.storage().instance().set(&history_key, &history);
+
+    if is_terminal(to) {
+        streak::record(env, vault_id, vault)?;
+        reputation::record(env, vault_id, vault)?;
+        category::record_settled(env, vault)?;
+        leaderboard::record(env, vault_id, vault)?;
+        proofs::clear(env, vault_id);
+    }
+    Ok(())
+}
+
+/// Transition to the terminal `status`, then pay the escrow to `recipients`, split by weight
+/// (see `payout::allocate`). Returns the payout breakdown the transfers were made from, for
+/// the settlement event.
+///
+/// Every settlement path (release, redirect, cancel) funnels through here once all of its
+/// checks have passed. The shares are computed and the transition validated up front; those
+/// are the only fallible steps. Ordering then follows checks-effects-interactions: the status
+/// write is committed before the cross-contract token calls, so anything the token contract
+/// does during `transfer` can only ever observe the vault as already settled. There is no
+/// fallible step between the two; if a transfer traps, the whole invocation (including the
+/// status write and any transfers already made) reverts. Any payout that needs more than
+/// one transfer must keep all of them after the status write and rely on that same
+/// all-or-nothing revert.
+///
+/// The one external call before the status write is the yield adapter withdrawal (see
+/// `yield_adapter`), which has to come first because it determines the amount settled. The
+/// adapter is admin-registered, and Soroban rejects any attempt to re-enter this contract
+/// from it.
+fn settle(
+    env: &Env,
+    vault_id: u32,
+    vault: &mut ProductivityVault,
+    usdc_token: &Address,
+    recipients: &Vec<(Address, i128)>,
+    status: VaultStatus,
+    actor: Address,
+) -> Result<payout::PayoutBreakdown, Error> {
+    check_token(vault, usdc_token)?;
+    let redemption = yield_adapter::redeem(env, vault_id, usdc_token, status)?;
+    if let Some(redemption) = &redemption {
+        vault.amount = redemption.escrow;
+    }
+    let parts = payout::breakdown(env, vault.amount, fees::fee_bps(env, status), recipients)?;
+
+    transition(env, vault_id, vault, status, actor)?;
+    let referral = fees::collect(env, usdc_token, vault, parts.fee)?;
+
+    let token_client = token::Client::new(env, usdc_token);
+    let contract = env.current_contract_address();
+    // A share addressed to this contract stays in its balance for `burn::forfeit`.
+    for (to, share) in parts.shares.iter() {
+        if share > 0 && to != contract {
+            token_client.transfer(&contract, &to, &share);
+        }
+    }
+    if let Some((referrer, reward)) = referral {
+        if reward > 0 {
+            token_client.transfer(&contract, &referrer, &reward);
+        }
+    }
+    if let Some(redemption) = redemption {
+        if redemption.creator_yield > 0 {
+            token_client.transfer(&contract, &vault.creator, &redemption.creator_yield);
+        }
+    }
+    Ok(parts)
+}
+
+/// Cancel `vault` and refund its escrow, attributing the transition to `actor`. Shared by
+/// `cancel_vault` and `approve_cancellation` once their own checks have passed.
+fn cancel_and_refund(
+    env: &Env,
+    vault_id: u32,
+    mut vault: ProductivityVault,
+    usdc_token: &Address,
+    actor: Address,
+) -> Result<bool, Error> {
+    let penalty = savings::withhold_penalty(env, vault_id, &mut vault, usdc_token)?;
+    let refunds = group::refund_recipients(env, vault_id, &vault);
+    settle(
+        env,
+        vault_id,
+        &mut vault,
+        usdc_token,
+        &refunds,
+        VaultStatus::Cancelled,
+        actor,
+    )?;
+
+    let notification = callback::notify(env, vault_id, &vault);
+
+    savings::publish(env, vault_id, &vault, penalty);
+    events::vault_cancelled(
+        env,
+        vault_id,
+        &vault.creator,
+        privacy::amount(&vault, vault.amount),
+    );
+    yield_adapter::publish(env, vault_id, &vault);
+    callback::publish(env, vault_id, &vault, notification);
+    Ok(true)
+}
+
+/// Validate `vault_id`'s milestone, approving proof `proof` or the latest one. Shared by
+/// `validate_milestone` and `approve_proof`.
+fn validate(env: &Env, vault_id: u32, proof: Option<u32>) -> Result<bool, Error> {
+    let mut vault = load_vault(env, vault_id)?;
+    if bounty::is_bounty(env, vault_id) || savings::is_savings_lock(&vault) {
+        return Err(Error::InvalidStatus);
+    }
+    if recurring::is_recurring(env, vault_id) || joint::is_joint(env, vault_id) {
+        return Err(Error::InvalidStatus);
+    }
+
+    if vault.status != VaultStatus::Active {
+        return Err(Error::VaultNotActive);
+    }
+
+    // When verifier is Some, only that address may validate; when None, only creator may validate.
+    vault.validator().require_auth();
+
+    // Timestamp check: rejects when current time >= end_timestamp
+    if !vault.validation_open(env.ledger().timestamp()) {
+        return Err(Error::MilestoneExpired);
+    }
+    if grading::grade(env, vault_id).is_some() {
+        return Err(Error::InvalidStatus);
+    }
+    dependency::require_unlocked(env, vault_id)?;
+    let approved = proofs::approve(env, vault_id, proof)?;
+
+    vault.milestone_validated = true;
+    env.storage()
+        .instance()
+        .set(&DataKey::Vault(vault_id), &vault);
+    reputation::record_response(env, vault_id, &vault);
+
+    events::milestone_validated(env, vault_id, vault.validator());
+    if let Some(proof_hash) = approved {
+        events::proof_approved(env, vault_id, vault.validator(), &proof_hash);
+    }
+    Ok(true)
 }
 
 // ---------------------------------------------------------------------------
@@ -103,6 +1014,11 @@ pub enum DataKey {
 #[contract]
 pub struct DisciplrVault;
 
+// Every mutating entrypoint is laid out as checks, storage writes, token transfers and, as
+// the very last step, event publication through `events`. A call that fails returns its
+// error before anything is published, so indexers never see an event for an operation that
+// did not happen.
+
 #[contractimpl]
 impl DisciplrVault {
     // -----------------------------------------------------------------------
@@ -114,6 +1030,11 @@ impl DisciplrVault {
     /// # Validation Rules
     /// - `amount` must be positive; otherwise returns `Error::InvalidAmount`.
     /// - `start_timestamp` must be strictly less than `end_timestamp`; otherwise returns `Error::InvalidTimestamps`.
+    /// - Neither destination may be this contract's address; otherwise returns `Error::InvalidDestination`.
+    /// - `creator` must hold at least `amount` of `usdc_token`; otherwise returns `Error::InsufficientBalance`.
+    /// - `options.referrer` may not be the creator; otherwise returns `Error::SelfReferral`.
+    /// - With `options.require_registered_charity`, `failure_destination` must be
+    ///   a registered charity; otherwise returns `Error::UnregisteredDestination`.
     pub fn create_vault(
         env: Env,
         usdc_token: Address,
@@ -125,61 +1046,21 @@ impl DisciplrVault {
         verifier: Option<Address>,
         success_destination: Address,
         failure_destination: Address,
+        options: VaultOptions,
     ) -> Result<u32, Error> {
         creator.require_auth();
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        if amount > MAX_AMOUNT {
-            return Err(Error::InvalidAmount);
-        }
-
-        // Validate that start_timestamp is strictly before end_timestamp.
-        if end_timestamp <= start_timestamp {
-            return Err(Error::InvalidTimestamps);
-        }
-
-        if end_timestamp - start_timestamp > MAX_VAULT_DURATION {
-            return Err(Error::DurationTooLong);
-        }
-
-        // Pull USDC from creator into this contract.
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(&creator, &env.current_contract_address(), &amount);
-
-        let mut vault_count: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultCount)
-            .unwrap_or(0);
-        let vault_id = vault_count;
-        vault_count += 1;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultCount, &vault_count);
-
-        let vault = ProductivityVault {
-            creator,
-            amount,
+        let terms = VaultTerms {
             start_timestamp,
             end_timestamp,
             milestone_hash,
             verifier,
             success_destination,
             failure_destination,
-            status: VaultStatus::Active,
-            milestone_validated: false,
         };
+        let (vault_id, vault) = open_vault(&env, &usdc_token, creator, amount, terms, options, 0)?;
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Vault(vault_id), &vault);
-
-        env.events().publish(
-            (Symbol::new(&env, "vault_created"), vault_id),
-            vault.clone(),
-        );
+        events::vault_created(&env, vault_id, &vault);
 
         Ok(vault_id)
     }
@@ -193,78 +1074,109 @@ impl DisciplrVault {
     /// **Optional verifier behavior:** If `verifier` is `Some(addr)`, only that address may call
     /// this function. If `verifier` is `None`, only the creator may call it (no validation by
     /// other parties). Rejects when current time >= end_timestamp (MilestoneExpired).
+    ///
+    /// If proofs were submitted, the validation approves the latest one (`approve_proof`
+    /// approves a given one). A vault already graded by `validate_graded` cannot be validated
+    /// (`InvalidStatus`), nor can one whose dependencies have not all completed
+    /// (`DependencyNotMet`; see `dependency`).
     pub fn validate_milestone(env: Env, vault_id: u32) -> Result<bool, Error> {
-        let vault_key = DataKey::Vault(vault_id);
-        let mut vault: ProductivityVault = env
-            .storage()
-            .instance()
-            .get(&vault_key)
-            .ok_or(Error::VaultNotFound)?;
-
-        if vault.status != VaultStatus::Active {
-            return Err(Error::VaultNotActive);
-        }
-
-        // When verifier is Some, only that address may validate; when None, only creator may validate.
-        if let Some(ref verifier) = vault.verifier {
-            verifier.require_auth();
-        } else {
-            vault.creator.require_auth();
-        }
-
-        // Timestamp check: rejects when current time >= end_timestamp
-        if env.ledger().timestamp() >= vault.end_timestamp {
-            return Err(Error::MilestoneExpired);
-        }
-
-        vault.milestone_validated = true;
-        env.storage().instance().set(&vault_key, &vault);
-
-        env.events()
-            .publish((Symbol::new(&env, "milestone_validated"), vault_id), ());
-        Ok(true)
+        validate(&env, vault_id, None)
     }
 
     // -----------------------------------------------------------------------
     // release_funds
     // -----------------------------------------------------------------------
 
-    /// Release vault funds to `success_destination`.
+    /// Release vault funds to `success_destination` (or, for proportional group vaults, back
+    /// to the participants).
+    ///
+    /// Fails with `AlreadySettled` if the vault was already released, redirected or cancelled,
+    /// with `RevealRequired` for a `reveal_required` vault without a verifier whose milestone
+    /// has not been revealed yet, and with `NotAuthorized` for a vault graded zero. A vault
+    /// graded between zero and full marks pays the failure destination its share too. An
+    /// `auto_renew` vault pays nothing and rolls into its successor instead (see `renewal`).
+    /// A vault whose dependencies have not all completed fails with `DependencyNotMet`, and
+    /// one with installments left to pay with `InvalidStatus`. Past the deadline an
+    /// unvalidated vault with a verifier also needs `Config::timeout_min_proofs` proofs
+    /// (`NotAuthorized`).
     pub fn release_funds(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
-        let vault_key = DataKey::Vault(vault_id);
-        let mut vault: ProductivityVault = env
-            .storage()
-            .instance()
-            .get(&vault_key)
-            .ok_or(Error::VaultNotFound)?;
-
-        if vault.status != VaultStatus::Active {
-            return Err(Error::VaultNotActive); // Or InvalidStatus as appropriate
+        let mut vault = load_vault(&env, vault_id)?;
+        require_unsettled(&vault)?;
+        if recurring::is_recurring(&env, vault_id)
+            || bounty::is_bounty(&env, vault_id)
+            || installment::outstanding(&env, vault_id)
+        {
+            return Err(Error::InvalidStatus);
         }
 
         // Check release conditions.
-        let now = env.ledger().timestamp();
-        let deadline_reached = now >= vault.end_timestamp;
+        let deadline_reached = vault.deadline_reached(env.ledger().timestamp());
         let validated = vault.milestone_validated;
 
-        if !validated && !deadline_reached {
+        // Wagers are a bet on validation, so an unvalidated deadline pays the challenger, and
+        // a joint vault only pays out once every participant was validated.
+        if !validated
+            && (!deadline_reached
+                || wager::is_wager(&env, vault_id)
+                || joint::is_joint(&env, vault_id))
+        {
             return Err(Error::NotAuthorized);
         }
+        // Without validation the proof log is the only evidence the creator did the work while
+        // the verifier stayed silent. Vaults without a verifier, savings locks included, have
+        // no verifier to time out and release on the deadline alone.
+        if !validated
+            && vault.verifier.is_some()
+            && proofs::count(&env, vault_id) < config::load(&env).timeout_min_proofs
+        {
+            return Err(Error::NotAuthorized);
+        }
+        // A zero grade is a rejection: the vault can only fail.
+        if grading::grade(&env, vault_id) == Some(0) {
+            return Err(Error::NotAuthorized);
+        }
+        dependency::require_unlocked(&env, vault_id)?;
+        if vault.verifier.is_none() && vault.reveal_required && !reveal::is_revealed(&env, vault_id)
+        {
+            return Err(Error::RevealRequired);
+        }
 
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &vault.success_destination,
-            &vault.amount,
-        );
-
-        vault.status = VaultStatus::Completed;
-        env.storage().instance().set(&vault_key, &vault);
+        if renewal::renews(&env, vault_id, &vault) {
+            renewal::renew(&env, vault_id, vault)?;
+            return Ok(true);
+        }
 
-        env.events().publish(
-            (Symbol::new(&env, "funds_released"), vault_id),
-            vault.amount,
+        let (recipient, recipients) = group::success_payout(&env, vault_id, &vault);
+        let recipients = grading::apply(&env, vault_id, &vault, recipients)?;
+        let recipients = oracle::apply(&env, vault_id, &vault, &usdc_token, recipients)?;
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &recipients,
+            VaultStatus::Completed,
+            env.current_contract_address(),
+        )?;
+        let badge = badge::mint(&env, vault_id, &vault);
+        let referral = fees::referral_reward(&env, &vault, parts.fee)?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::funds_released(
+            &env,
+            vault_id,
+            &recipient,
+            &privacy::breakdown(&env, &vault, &parts),
         );
+        events::referral_paid(
+            &env,
+            vault_id,
+            referral.map(|(referrer, reward)| (referrer, privacy::amount(&vault, reward))),
+        );
+        yield_adapter::publish(&env, vault_id, &vault);
+        streak::publish_update(&env, vault_id, &vault);
+        badge::publish(&env, vault_id, &vault, badge);
+        callback::publish(&env, vault_id, &vault, notification);
         Ok(true)
     }
 
@@ -272,20 +1184,23 @@ impl DisciplrVault {
     // redirect_funds
     // -----------------------------------------------------------------------
 
-    /// Redirect funds to `failure_destination` (e.g. after deadline without validation).
+    /// Redirect funds to `failure_destination` (e.g. after deadline without validation). A
+    /// `cascade_failure` vault whose dependency failed can be redirected before its deadline.
+    ///
+    /// Fails with `AlreadySettled` if the vault was already released, redirected or cancelled.
     pub fn redirect_funds(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
-        let vault_key = DataKey::Vault(vault_id);
-        let mut vault: ProductivityVault = env
-            .storage()
-            .instance()
-            .get(&vault_key)
-            .ok_or(Error::VaultNotFound)?;
-
-        if vault.status != VaultStatus::Active {
-            return Err(Error::VaultNotActive);
+        let mut vault = load_vault(&env, vault_id)?;
+        require_unsettled(&vault)?;
+        if recurring::is_recurring(&env, vault_id)
+            || bounty::is_bounty(&env, vault_id)
+            || savings::is_savings_lock(&vault)
+        {
+            return Err(Error::InvalidStatus);
         }
 
-        if env.ledger().timestamp() < vault.end_timestamp {
+        if !vault.deadline_reached(env.ledger().timestamp())
+            && !dependency::cascade_failed(&env, vault_id)
+        {
             return Err(Error::InvalidTimestamp); // Too early to redirect
         }
 
@@ -294,20 +1209,38 @@ impl DisciplrVault {
             return Err(Error::NotAuthorized);
         }
 
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &vault.failure_destination,
-            &vault.amount,
-        );
-
-        vault.status = VaultStatus::Failed;
-        env.storage().instance().set(&vault_key, &vault);
-
-        env.events().publish(
-            (Symbol::new(&env, "funds_redirected"), vault_id),
-            vault.amount,
+        let recipient = burn::failure_recipient(&env, vault_id, &vault);
+        let recipients = oracle::apply(
+            &env,
+            vault_id,
+            &vault,
+            &usdc_token,
+            vec![&env, (recipient.clone(), 1)],
+        )?;
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &recipients,
+            VaultStatus::Failed,
+            env.current_contract_address(),
+        )?;
+
+        let forfeited = burn::forfeit(&env, &usdc_token, &parts)?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        joint::publish_failure(&env, vault_id, &vault);
+        events::funds_redirected(
+            &env,
+            vault_id,
+            &recipient,
+            &privacy::breakdown(&env, &vault, &parts),
         );
+        burn::publish(&env, vault_id, &vault, forfeited);
+        yield_adapter::publish(&env, vault_id, &vault);
+        streak::publish_update(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
         Ok(true)
     }
 
@@ -315,33 +1248,86 @@ impl DisciplrVault {
     // cancel_vault
     // -----------------------------------------------------------------------
 
-    /// Cancel vault and return funds to creator.
+    /// Cancel vault and return funds to creator. Group vaults refund every participant their
+    /// own contribution instead, and sponsored vaults refund the funder, who must authorize
+    /// (together with the creator once the vault has started; see `sponsor`). Wagers,
+    /// bounties and gifts cannot be cancelled (`InvalidStatus`; see `wager`, `bounty` and
+    /// `gift`), and a savings lock only pays an early-withdrawal penalty to cancel before its
+    /// deadline (`SavingsLocked` while that is disabled; see `savings`).
+    ///
+    /// For a vault with an accountability partner this only proposes the cancellation and
+    /// returns `false`; the partner must confirm it with `approve_cancellation`. Returns
+    /// `true` once the vault is cancelled.
+    ///
+    /// Fails with `AlreadySettled` if the vault was already released, redirected or cancelled.
     pub fn cancel_vault(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
-        let vault_key = DataKey::Vault(vault_id);
-        let mut vault: ProductivityVault = env
-            .storage()
-            .instance()
-            .get(&vault_key)
-            .ok_or(Error::VaultNotFound)?;
+        let vault = load_vault(&env, vault_id)?;
+
+        let actor = sponsor::authorize_cancel(&env, vault_id, &vault);
+        require_unsettled(&vault)?;
+        if wager::is_wager(&env, vault_id)
+            || bounty::is_bounty(&env, vault_id)
+            || gift::is_gift(&env, vault_id)
+        {
+            return Err(Error::InvalidStatus);
+        }
+        savings::check_cancel(&env, &vault)?;
+
+        if vault.accountability_partner.is_some() {
+            partner::propose_cancellation(&env, vault_id, &vault);
+            return Ok(false);
+        }
+        cancel_and_refund(&env, vault_id, vault, &usdc_token, actor)
+    }
 
+    // -----------------------------------------------------------------------
+    // update_success_destination
+    // -----------------------------------------------------------------------
+
+    /// Point an Active, not yet validated vault at a new success destination before its
+    /// deadline. Creator only, plus the funder for sponsored vaults.
+    ///
+    /// Fails with `DestinationsLocked` for vaults created with `destinations_locked` or a
+    /// transferable claim (whose destination moves with `transfer_claim`),
+    /// `VaultNotActive` / `InvalidStatus` once the vault is settled or validated,
+    /// `MilestoneExpired` at or past the deadline and `InvalidDestination` like
+    /// `create_vault`.
+    pub fn update_success_destination(
+        env: Env,
+        vault_id: u32,
+        new_destination: Address,
+    ) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
         vault.creator.require_auth();
+        if let Some(funder) = sponsor::funder(&env, vault_id) {
+            funder.require_auth();
+        }
+        if vault.destinations_locked || claim::is_transferable(&env, vault_id) {
+            return Err(Error::DestinationsLocked);
+        }
         if vault.status != VaultStatus::Active {
             return Err(Error::VaultNotActive);
         }
+        if vault.milestone_validated {
+            return Err(Error::InvalidStatus);
+        }
+        if !vault.validation_open(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        validate_destination(&env, &new_destination)?;
 
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &vault.creator,
-            &vault.amount,
-        );
-
-        vault.status = VaultStatus::Cancelled;
-        env.storage().instance().set(&vault_key, &vault);
+        let old_destination = vault.success_destination.clone();
+        vault.success_destination = new_destination;
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &vault);
 
-        env.events().publish(
-            (Symbol::new(&env, "vault_cancelled"), vault_id),
-            vault.amount,
+        events::success_destination_updated(
+            &env,
+            vault_id,
+            &vault.creator,
+            &old_destination,
+            &vault.success_destination,
         );
         Ok(true)
     }
@@ -350,9 +1336,39 @@ impl DisciplrVault {
     // get_vault_state
     // -----------------------------------------------------------------------
 
-    /// Return current vault state, or `None` if the vault does not exist.
+    /// Return current vault state, or `None` if the vault does not exist. Private vaults are
+    /// returned redacted (see `privacy`); their parties read them with
+    /// `get_vault_state_authed`.
     pub fn get_vault_state(env: Env, vault_id: u32) -> Option<ProductivityVault> {
-        env.storage().instance().get(&DataKey::Vault(vault_id))
+        load_vault(&env, vault_id)
+            .ok()
+            .map(|vault| privacy::redact(&env, &vault))
+    }
+
+    /// Number of vaults currently in `status`.
+    pub fn get_status_count(env: Env, status: VaultStatus) -> u32 {
+        status_count(&env, status)
+    }
+
+    /// Status changes of a vault, oldest first. Empty for unknown or still-Active vaults.
+    /// Private vaults report this contract as the actor of every change.
+    pub fn get_vault_history(env: Env, vault_id: u32) -> Vec<StatusChange> {
+        let history: Vec<StatusChange> = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultHistory(vault_id))
+            .unwrap_or(Vec::new(&env));
+        if !privacy::is_private(&env, vault_id) {
+            return history;
+        }
+        let mut redacted = Vec::new(&env);
+        for change in history.iter() {
+            redacted.push_back(StatusChange {
+                actor: env.current_contract_address(),
+                ..change
+            });
+        }
+        redacted
     }
 }
 
@@ -365,116 +1381,20 @@ mod tests {
     extern crate std;
 
     use super::*;
+    use crate::testutils::VaultTestContext;
     use soroban_sdk::{
-        testutils::{Address as _, AuthorizedFunction, Events, Ledger},
-        token::{StellarAssetClient, TokenClient},
-        Address, BytesN, Env, Symbol, TryIntoVal,
+        testutils::{Address as _, AuthorizedFunction, Events},
+        token::StellarAssetClient,
+        vec, Address, BytesN, Env, IntoVal, Symbol, TryIntoVal,
     };
 
-    // -----------------------------------------------------------------------
-    // Helpers
-    // -----------------------------------------------------------------------
-
-    struct TestSetup {
-        env: Env,
-        contract_id: Address,
-        usdc_token: Address,
-        creator: Address,
-        verifier: Address,
-        success_dest: Address,
-        failure_dest: Address,
-        amount: i128,
-        start_timestamp: u64,
-        end_timestamp: u64,
-    }
-
-    impl TestSetup {
-        fn new() -> Self {
-            let env = Env::default();
-            env.mock_all_auths();
-
-            // Deploy USDC mock token.
-            let usdc_admin = Address::generate(&env);
-            let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin.clone());
-            let usdc_addr = usdc_token.address();
-            let usdc_asset = StellarAssetClient::new(&env, &usdc_addr);
-
-            // Actors.
-            let creator = Address::generate(&env);
-            let verifier = Address::generate(&env);
-            let success_dest = Address::generate(&env);
-            let failure_dest = Address::generate(&env);
-
-            // Mint USDC to creator.
-            let amount: i128 = 1_000_000; // 1 USDC (6 decimals)
-            usdc_asset.mint(&creator, &amount);
-
-            // Deploy contract.
-            let contract_id = env.register(DisciplrVault, ());
-
-            TestSetup {
-                env,
-                contract_id,
-                usdc_token: usdc_addr,
-                creator,
-                verifier,
-                success_dest,
-                failure_dest,
-                amount,
-                start_timestamp: 100,
-                end_timestamp: 1_000,
-            }
-        }
-
-        fn client(&self) -> DisciplrVaultClient<'_> {
-            DisciplrVaultClient::new(&self.env, &self.contract_id)
-        }
-
-        fn usdc_client(&self) -> TokenClient<'_> {
-            TokenClient::new(&self.env, &self.usdc_token)
-        }
-
-        fn milestone_hash(&self) -> BytesN<32> {
-            BytesN::from_array(&self.env, &[1u8; 32])
-        }
-
-        fn create_default_vault(&self) -> u32 {
-            self.client().create_vault(
-                &self.usdc_token,
-                &self.creator,
-                &self.amount,
-                &self.start_timestamp,
-                &self.end_timestamp,
-                &self.milestone_hash(),
-                &Some(self.verifier.clone()),
-                &self.success_dest,
-                &self.failure_dest,
-            )
-        }
-
-        /// Create vault with verifier = None (only creator can validate).
-        fn create_vault_no_verifier(&self) -> u32 {
-            self.client().create_vault(
-                &self.usdc_token,
-                &self.creator,
-                &self.amount,
-                &self.start_timestamp,
-                &self.end_timestamp,
-                &self.milestone_hash(),
-                &None,
-                &self.success_dest,
-                &self.failure_dest,
-            )
-        }
-    }
-
     // -----------------------------------------------------------------------
     // Upstream Tests (Migrated & Merged)
     // -----------------------------------------------------------------------
 
     #[test]
     fn get_vault_state_returns_some_with_matching_fields() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let vault_id = setup.create_default_vault();
@@ -497,11 +1417,11 @@ mod tests {
     /// Issue #42: milestone_hash passed to create_vault is stored and returned by get_vault_state.
     #[test]
     fn test_milestone_hash_storage_and_retrieval() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let custom_hash = BytesN::from_array(&setup.env, &[0xab; 32]);
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
 
         let vault_id = client.create_vault(
             &setup.usdc_token,
@@ -513,6 +1433,7 @@ mod tests {
             &Some(setup.verifier.clone()),
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
 
         let vault = client.get_vault_state(&vault_id).unwrap();
@@ -521,7 +1442,7 @@ mod tests {
 
     #[test]
     fn test_create_vault_invalid_amount_returns_error() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let result = client.try_create_vault(
@@ -534,6 +1455,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
         assert!(
             result.is_err(),
@@ -543,7 +1465,7 @@ mod tests {
 
     #[test]
     fn test_create_vault_invalid_timestamps_returns_error() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let result = client.try_create_vault(
@@ -556,6 +1478,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
         assert!(
             result.is_err(),
@@ -565,7 +1488,7 @@ mod tests {
 
     #[test]
     fn test_create_vault_rejects_duration_above_max() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let start = 1_000u64;
@@ -581,6 +1504,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
         assert!(
             result.is_err(),
@@ -590,7 +1514,7 @@ mod tests {
 
     #[test]
     fn test_create_vault_accepts_duration_at_max() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let start = 1_000u64;
@@ -606,6 +1530,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
         assert!(
             result.is_ok(),
@@ -614,22 +1539,161 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_milestone_rejects_after_end() {
-        let setup = TestSetup::new();
+    fn test_create_vault_rejects_contract_as_success_destination() {
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        let result = client.try_create_vault(
+            &setup.usdc_token,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.contract_id,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidDestination)));
+        setup.assert_balances(&[(&setup.creator, setup.amount), (&setup.contract_id, 0)]);
+    }
+
+    #[test]
+    fn test_create_vault_rejects_contract_as_failure_destination() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        let result = client.try_create_vault(
+            &setup.usdc_token,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.success_dest,
+            &setup.contract_id,
+            &VaultOptions::default(),
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidDestination)));
+        setup.assert_balances(&[(&setup.creator, setup.amount), (&setup.contract_id, 0)]);
+    }
+
+    fn create_with_balance(setup: &VaultTestContext, balance: i128) -> Result<u32, Error> {
+        let creator = Address::generate(&setup.env);
+        if balance > 0 {
+            setup.mint(&creator, balance);
+        }
+        setup
+            .client()
+            .try_create_vault(
+                &setup.usdc_token,
+                &creator,
+                &setup.amount,
+                &setup.start_timestamp,
+                &setup.end_timestamp,
+                &setup.milestone_hash(),
+                &None,
+                &setup.success_dest,
+                &setup.failure_dest,
+                &VaultOptions::default(),
+            )
+            .map(|id| id.unwrap())
+            .map_err(|e| e.unwrap())
+    }
+
+    #[test]
+    fn test_create_vault_with_exact_balance_succeeds() {
+        let setup = VaultTestContext::new();
+        assert_eq!(create_with_balance(&setup, setup.amount), Ok(0));
+        setup.assert_balances(&[(&setup.contract_id, setup.amount)]);
+    }
+
+    #[test]
+    fn test_create_vault_one_stroop_short_rejected() {
+        let setup = VaultTestContext::new();
+        assert_eq!(
+            create_with_balance(&setup, setup.amount - 1),
+            Err(Error::InsufficientBalance)
+        );
+        setup.assert_balances(&[(&setup.contract_id, 0)]);
+    }
+
+    #[test]
+    fn test_create_vault_zero_balance_rejected() {
+        let setup = VaultTestContext::new();
+        assert_eq!(
+            create_with_balance(&setup, 0),
+            Err(Error::InsufficientBalance)
+        );
+        assert!(setup.client().get_vault_state(&0).is_none());
+    }
+
+    mod overreporting_token {
+        use soroban_sdk::{contract, contractimpl, Address, Env};
+
+        /// Token whose `balance` over-reports while `transfer` always traps, standing in for a
+        /// balance that changes between the pre-check and the pull.
+        #[contract]
+        pub struct OverreportingToken;
+
+        #[contractimpl]
+        impl OverreportingToken {
+            pub fn balance(_env: Env, _id: Address) -> i128 {
+                i128::MAX
+            }
+
+            pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {
+                panic!("balance is not actually available");
+            }
+        }
+    }
+
+    #[test]
+    fn test_create_vault_reverts_when_transfer_fails_after_precheck() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        let token = setup
+            .env
+            .register(overreporting_token::OverreportingToken, ());
+
+        let result = client.try_create_vault(
+            &token,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.success_dest,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        assert!(result.is_err());
+        assert!(client.get_vault_state(&0).is_none());
+
+        // The failed attempt did not consume a vault id either.
+        assert_eq!(setup.create_default_vault(), 0);
+    }
+
+    #[test]
+    fn test_validate_milestone_rejects_after_end() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Advance ledger to exactly end_timestamp
-        setup.env.ledger().set_timestamp(setup.end_timestamp);
+        setup.advance_to_deadline();
 
         // Try to validate milestone - should fail with MilestoneExpired
         let result = client.try_validate_milestone(&vault_id);
         assert!(result.is_err());
 
         // Advance ledger past end_timestamp
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         // Try to validate milestone - should also fail
         let result = client.try_validate_milestone(&vault_id);
@@ -638,14 +1702,14 @@ mod tests {
 
     #[test]
     fn test_validate_milestone_succeeds_before_end() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Set time to just before end
-        setup.env.ledger().set_timestamp(setup.end_timestamp - 1);
+        setup.advance_to(setup.end_timestamp - 1);
 
         let success = client.validate_milestone(&vault_id);
         assert!(success);
@@ -659,13 +1723,13 @@ mod tests {
     /// Issue #14: When verifier is None, only creator may validate. Creator succeeds.
     #[test]
     fn test_validate_milestone_verifier_none_creator_succeeds() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_vault_no_verifier();
 
-        setup.env.ledger().set_timestamp(setup.end_timestamp - 1);
+        setup.advance_to(setup.end_timestamp - 1);
 
         let result = client.validate_milestone(&vault_id);
         assert!(result);
@@ -678,13 +1742,13 @@ mod tests {
     /// Issue #14: When verifier is None, release_funds after deadline (no validation) still works.
     #[test]
     fn test_release_funds_verifier_none_after_deadline() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_vault_no_verifier();
 
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         let result = client.release_funds(&vault_id, &setup.usdc_token);
         assert!(result);
@@ -695,7 +1759,7 @@ mod tests {
 
     #[test]
     fn test_release_funds_rejects_non_existent_vault() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let result = client.try_release_funds(&999, &setup.usdc_token);
@@ -704,7 +1768,7 @@ mod tests {
 
     #[test]
     fn test_validate_milestone_rejects_non_existent_vault() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let result = client.try_validate_milestone(&999);
@@ -713,7 +1777,7 @@ mod tests {
 
     #[test]
     fn test_redirect_funds_rejects_non_existent_vault() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         let result = client.try_redirect_funds(&999, &setup.usdc_token);
@@ -723,7 +1787,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "Error(Contract, #8)")]
     fn create_vault_rejects_start_equal_end() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         client.create_vault(
@@ -736,13 +1800,14 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #8)")]
     fn create_vault_rejects_start_greater_than_end() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         client.create_vault(
@@ -755,6 +1820,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
     }
 
@@ -764,11 +1830,10 @@ mod tests {
 
     #[test]
     fn test_create_vault_increments_id() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
 
         // Mint extra USDC for second vault.
-        let usdc_asset = StellarAssetClient::new(&setup.env, &setup.usdc_token);
-        usdc_asset.mint(&setup.creator, &setup.amount);
+        setup.mint(&setup.creator, setup.amount);
 
         let id_a = setup.create_default_vault();
         let id_b = setup.create_default_vault();
@@ -778,10 +1843,10 @@ mod tests {
 
     #[test]
     fn test_release_funds_after_validation() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Validate milestone.
@@ -809,11 +1874,11 @@ mod tests {
     /// the success destination).
     #[test]
     fn test_release_funds_updates_contract_and_success_balances() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         // Create vault at start_timestamp.
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Validate milestone so we can release before the deadline.
@@ -839,14 +1904,14 @@ mod tests {
 
     #[test]
     fn test_release_funds_after_deadline() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Advance ledger PAST end_timestamp.
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         let usdc = setup.usdc_client();
         let before = usdc.balance(&setup.success_dest);
@@ -862,13 +1927,13 @@ mod tests {
 
     #[test]
     fn test_double_release_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         client.release_funds(&vault_id, &setup.usdc_token);
         // Second call — must error.
@@ -879,10 +1944,10 @@ mod tests {
 
     #[test]
     fn test_release_cancelled_vault_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         client.cancel_vault(&vault_id, &setup.usdc_token);
@@ -894,10 +1959,10 @@ mod tests {
 
     #[test]
     fn test_release_not_validated_before_deadline_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Neither validated nor past deadline — must error.
@@ -908,13 +1973,13 @@ mod tests {
 
     #[test]
     fn test_validate_milestone_on_completed_vault_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
         client.release_funds(&vault_id, &setup.usdc_token);
 
         // Validate after completion — must error.
@@ -923,13 +1988,13 @@ mod tests {
 
     #[test]
     fn test_redirect_funds_after_deadline_without_validation() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         let usdc = setup.usdc_client();
         let before = usdc.balance(&setup.failure_dest);
@@ -948,14 +2013,14 @@ mod tests {
     /// the failure destination).
     #[test]
     fn test_redirect_funds_updates_contract_and_failure_balances() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Move past the deadline without validation so redirect is allowed.
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         let usdc = setup.usdc_client();
 
@@ -976,10 +2041,10 @@ mod tests {
 
     #[test]
     fn test_redirect_funds_before_deadline_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Still before deadline — must error.
@@ -990,12 +2055,12 @@ mod tests {
 
     #[test]
     fn test_double_redirect_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
 
         let result = client.redirect_funds(&vault_id, &setup.usdc_token);
         assert!(result);
@@ -1005,12 +2070,623 @@ mod tests {
             .is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // Single settlement guarantee
+    // -----------------------------------------------------------------------
+
+    /// Exactly one payout of `amount` landed on `paid`; nobody else holds any of it.
+    fn assert_paid_once(setup: &VaultTestContext, paid: &Address) {
+        let mut expected = [
+            (&setup.contract_id, 0),
+            (&setup.success_dest, 0),
+            (&setup.failure_dest, 0),
+            (&setup.creator, 0),
+        ];
+        for entry in expected.iter_mut() {
+            if entry.0 == paid {
+                entry.1 = setup.amount;
+            }
+        }
+        setup.assert_balances(&expected);
+    }
+
+    #[test]
+    fn test_release_then_redirect_rejected() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        client.validate_milestone(&vault_id);
+        client.release_funds(&vault_id, &setup.usdc_token);
+
+        setup.advance_past_deadline();
+        let result = client.try_redirect_funds(&vault_id, &setup.usdc_token);
+        assert_eq!(result, Err(Ok(Error::AlreadySettled)));
+
+        assert_paid_once(&setup, &setup.success_dest);
+    }
+
+    #[test]
+    fn test_redirect_then_release_rejected() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+
+        setup.advance_past_deadline();
+        client.redirect_funds(&vault_id, &setup.usdc_token);
+
+        let result = client.try_release_funds(&vault_id, &setup.usdc_token);
+        assert_eq!(result, Err(Ok(Error::AlreadySettled)));
+
+        assert_paid_once(&setup, &setup.failure_dest);
+    }
+
+    #[test]
+    fn test_redirect_twice_transfers_once() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+
+        setup.advance_past_deadline();
+        client.redirect_funds(&vault_id, &setup.usdc_token);
+
+        let result = client.try_redirect_funds(&vault_id, &setup.usdc_token);
+        assert_eq!(result, Err(Ok(Error::AlreadySettled)));
+
+        assert_paid_once(&setup, &setup.failure_dest);
+    }
+
+    #[test]
+    fn test_cancel_after_release_rejected() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        client.validate_milestone(&vault_id);
+        client.release_funds(&vault_id, &setup.usdc_token);
+
+        let result = client.try_cancel_vault(&vault_id, &setup.usdc_token);
+        assert_eq!(result, Err(Ok(Error::AlreadySettled)));
+
+        assert_paid_once(&setup, &setup.success_dest);
+        let vault = client.get_vault_state(&vault_id).unwrap();
+        assert_eq!(vault.status, VaultStatus::Completed);
+    }
+
+    // -----------------------------------------------------------------------
+    // Deadline boundaries
+    // -----------------------------------------------------------------------
+
+    /// Whether (validate_milestone, redirect_funds) would each succeed at `now`, probed on
+    /// independent fresh vaults so one attempt cannot influence the other.
+    fn permitted_at(now: u64) -> (bool, bool) {
+        let probe = |validate: bool| {
+            let setup = VaultTestContext::new();
+            let client = setup.client();
+            setup.advance_to_start();
+            let vault_id = setup.create_default_vault();
+            setup.advance_to(now);
+            if validate {
+                client.try_validate_milestone(&vault_id).is_ok()
+            } else {
+                client
+                    .try_redirect_funds(&vault_id, &setup.usdc_token)
+                    .is_ok()
+            }
+        };
+        (probe(true), probe(false))
+    }
+
+    #[test]
+    fn test_boundary_one_second_before_end_only_validation() {
+        let end = VaultTestContext::new().end_timestamp;
+        assert_eq!(permitted_at(end - 1), (true, false));
+    }
+
+    #[test]
+    fn test_boundary_at_end_only_redirect() {
+        let end = VaultTestContext::new().end_timestamp;
+        assert_eq!(permitted_at(end), (false, true));
+    }
+
+    #[test]
+    fn test_boundary_after_end_only_redirect() {
+        let end = VaultTestContext::new().end_timestamp;
+        assert_eq!(permitted_at(end + 1), (false, true));
+    }
+
+    #[test]
+    fn test_boundary_helpers_are_complementary() {
+        let setup = VaultTestContext::new();
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        let vault = setup.client().get_vault_state(&vault_id).unwrap();
+
+        for now in [0, vault.end_timestamp - 1, vault.end_timestamp, u64::MAX] {
+            assert_ne!(vault.validation_open(now), vault.deadline_reached(now));
+        }
+        assert!(vault.validation_open(vault.end_timestamp - 1));
+        assert!(vault.deadline_reached(vault.end_timestamp));
+    }
+
+    // -----------------------------------------------------------------------
+    // Checks-effects-interactions
+    // -----------------------------------------------------------------------
+
+    /// Minimal token that, whenever the vault pays out of its balance, tries to call back into
+    /// the vault: once through the `get_vault_state` view and once through a second
+    /// settlement. The outcome of each attempt is recorded so tests can inspect it afterwards.
+    #[contract]
+    struct ReentrantToken;
+
+    #[contracttype]
+    enum ReentrantKey {
+        Balance(Address),
+        Target,
+        ViewReentry,
+        SettleReentry,
+    }
+
+    #[contractimpl]
+    impl ReentrantToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let key = ReentrantKey::Balance(to);
+            let balance: i128 = env.storage().instance().get(&key).unwrap_or(0);
+            env.storage().instance().set(&key, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage()
+                .instance()
+                .get(&ReentrantKey::Balance(id))
+                .unwrap_or(0)
+        }
+
+        pub fn set_target(env: Env, vault_contract: Address, vault_id: u32) {
+            env.storage()
+                .instance()
+                .set(&ReentrantKey::Target, &(vault_contract, vault_id));
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let from_balance = Self::balance(env.clone(), from.clone());
+            assert!(from_balance >= amount, "insufficient balance");
+            env.storage().instance().set(
+                &ReentrantKey::Balance(from.clone()),
+                &(from_balance - amount),
+            );
+            Self::mint(env.clone(), to, amount);
+
+            let target: Option<(Address, u32)> =
+                env.storage().instance().get(&ReentrantKey::Target);
+            if let Some((vault_contract, vault_id)) = target {
+                if from == vault_contract {
+                    let view = env
+                        .try_invoke_contract::<Option<ProductivityVault>, soroban_sdk::Error>(
+                            &vault_contract,
+                            &Symbol::new(&env, "get_vault_state"),
+                            vec![&env, vault_id.into_val(&env)],
+                        );
+                    let settle = env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                        &vault_contract,
+                        &Symbol::new(&env, "redirect_funds"),
+                        vec![
+                            &env,
+                            vault_id.into_val(&env),
+                            env.current_contract_address().into_val(&env),
+                        ],
+                    );
+                    env.storage()
+                        .instance()
+                        .set(&ReentrantKey::ViewReentry, &matches!(view, Ok(Ok(_))));
+                    env.storage()
+                        .instance()
+                        .set(&ReentrantKey::SettleReentry, &matches!(settle, Ok(Ok(_))));
+                }
+            }
+        }
+
+        /// `(view_succeeded, settlement_succeeded)` for the last payout callback.
+        pub fn reentry_results(env: Env) -> (bool, bool) {
+            let view = env
+                .storage()
+                .instance()
+                .get(&ReentrantKey::ViewReentry)
+                .unwrap();
+            let settle = env
+                .storage()
+                .instance()
+                .get(&ReentrantKey::SettleReentry)
+                .unwrap();
+            (view, settle)
+        }
+    }
+
+    fn setup_reentrant_vault(setup: &VaultTestContext) -> (ReentrantTokenClient<'_>, u32) {
+        let token_id = setup.env.register(ReentrantToken, ());
+        let token = ReentrantTokenClient::new(&setup.env, &token_id);
+        token.mint(&setup.creator, &setup.amount);
+
+        setup.advance_to_start();
+        let vault_id = setup.client().create_vault(
+            &token_id,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &Some(setup.verifier.clone()),
+            &setup.success_dest,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        token.set_target(&setup.contract_id, &vault_id);
+        (token, vault_id)
+    }
+
+    /// The Soroban host refuses contract re-entry outright, so the token's callbacks fail. The
+    /// vault does not rely on that: its status is already terminal before the transfer runs,
+    /// so even a permitted re-entrant settlement would be rejected with `AlreadySettled`.
+    #[test]
+    fn test_release_commits_state_before_reentrant_transfer() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        let (token, vault_id) = setup_reentrant_vault(&setup);
+
+        client.validate_milestone(&vault_id);
+        assert!(client.release_funds(&vault_id, &token.address));
+
+        assert_eq!(token.reentry_results(), (false, false));
+        assert_eq!(
+            client.get_vault_state(&vault_id).unwrap().status,
+            VaultStatus::Completed
+        );
+        assert_eq!(token.balance(&setup.success_dest), setup.amount);
+        assert_eq!(token.balance(&setup.failure_dest), 0);
+        assert_eq!(token.balance(&setup.contract_id), 0);
+    }
+
+    #[test]
+    fn test_cancel_commits_state_before_reentrant_transfer() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        let (token, vault_id) = setup_reentrant_vault(&setup);
+
+        assert!(client.cancel_vault(&vault_id, &token.address));
+
+        assert_eq!(token.reentry_results(), (false, false));
+        assert_eq!(
+            client.get_vault_state(&vault_id).unwrap().status,
+            VaultStatus::Cancelled
+        );
+        assert_eq!(token.balance(&setup.creator), setup.amount);
+        assert_eq!(token.balance(&setup.failure_dest), 0);
+        assert_eq!(token.balance(&setup.contract_id), 0);
+    }
+
+    /// With the host's re-entry guard out of the way, a second settlement attempted while the
+    /// first is paying out is exactly what `require_unsettled` sees: an already terminal vault.
+    #[test]
+    fn test_settled_state_rejects_second_settlement() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        let (token, vault_id) = setup_reentrant_vault(&setup);
+
+        setup.advance_past_deadline();
+        assert!(client.redirect_funds(&vault_id, &token.address));
+
+        assert_eq!(
+            client.try_redirect_funds(&vault_id, &token.address),
+            Err(Ok(Error::AlreadySettled))
+        );
+        assert_eq!(token.balance(&setup.failure_dest), setup.amount);
+    }
+
+    /// A vault staked in a worthless token can only ever pay out that token, never the USDC
+    /// other vaults hold in escrow.
+    #[test]
+    fn test_settlement_is_bound_to_the_vault_token() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        let honest = setup.create_default_vault();
+        let junk = setup
+            .env
+            .register_stellar_asset_contract_v2(setup.creator.clone())
+            .address();
+        StellarAssetClient::new(&setup.env, &junk).mint(&setup.creator, &setup.amount);
+        let drained = client.create_vault(
+            &junk,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.success_dest,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        assert_eq!(client.get_vault_state(&drained).unwrap().token, junk);
+
+        assert_eq!(
+            client.try_cancel_vault(&drained, &setup.usdc_token),
+            Err(Ok(Error::NotAuthorized))
+        );
+        setup.advance_to_deadline();
+        assert_eq!(
+            client.try_release_funds(&drained, &setup.usdc_token),
+            Err(Ok(Error::NotAuthorized))
+        );
+        assert_eq!(
+            client.try_redirect_funds(&honest, &junk),
+            Err(Ok(Error::NotAuthorized))
+        );
+
+        client.release_funds(&drained, &junk);
+        let junk_client = token::Client::new(&setup.env, &junk);
+        assert_eq!(junk_client.balance(&setup.success_dest), setup.amount);
+        setup.assert_balances(&[(&setup.contract_id, setup.amount), (&setup.success_dest, 0)]);
+    }
+
+    // -----------------------------------------------------------------------
+    // State machine
+    // -----------------------------------------------------------------------
+
+    const ALL_STATUSES: [VaultStatus; 5] = [
+        VaultStatus::Pending,
+        VaultStatus::Active,
+        VaultStatus::Completed,
+        VaultStatus::Failed,
+        VaultStatus::Cancelled,
+    ];
+
+    /// The intended lifecycle, spelled out independently of `is_allowed_transition`.
+    fn intended_transition(from: VaultStatus, to: VaultStatus) -> bool {
+        match from {
+            VaultStatus::Pending => matches!(to, VaultStatus::Active | VaultStatus::Cancelled),
+            VaultStatus::Active => !matches!(to, VaultStatus::Active | VaultStatus::Pending),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_transition_accepts_exactly_the_intended_graph() {
+        let setup = VaultTestContext::new();
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        let template = setup.client().get_vault_state(&vault_id).unwrap();
+        let env = &setup.env;
+
+        for from in ALL_STATUSES {
+            for to in ALL_STATUSES {
+                let (result, stored) = env.as_contract(&setup.contract_id, || {
+                    let mut vault = template.clone();
+                    vault.status = from;
+                    env.storage()
+                        .instance()
+                        .set(&DataKey::Vault(vault_id), &vault);
+                    set_status_count(env, from, 1);
+                    let result = transition(env, vault_id, &mut vault, to, setup.creator.clone());
+                    (result, load_vault(env, vault_id).unwrap().status)
+                });
+
+                assert_eq!(
+                    is_allowed_transition(from, to),
+                    intended_transition(from, to)
+                );
+                if intended_transition(from, to) {
+                    assert_eq!(result, Ok(()), "{from:?} -> {to:?}");
+                    assert_eq!(stored, to);
+                } else {
+                    let expected = if matches!(from, VaultStatus::Active | VaultStatus::Pending) {
+                        Error::InvalidStatus
+                    } else {
+                        Error::AlreadySettled
+                    };
+                    assert_eq!(result, Err(expected), "{from:?} -> {to:?}");
+                    assert_eq!(stored, from, "rejected transition must not persist");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_status_counts_track_transitions() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+        setup.mint(&setup.creator, 2 * setup.amount);
+
+        setup.advance_to_start();
+        let released = setup.create_default_vault();
+        let redirected = setup.create_default_vault();
+        let cancelled = setup.create_default_vault();
+        assert_eq!(client.get_status_count(&VaultStatus::Active), 3);
+
+        client.validate_milestone(&released);
+        client.release_funds(&released, &setup.usdc_token);
+        client.cancel_vault(&cancelled, &setup.usdc_token);
+        setup.advance_past_deadline();
+        client.redirect_funds(&redirected, &setup.usdc_token);
+
+        for status in ALL_STATUSES {
+            let expected = match status {
+                VaultStatus::Active | VaultStatus::Pending => 0,
+                _ => 1,
+            };
+            assert_eq!(client.get_status_count(&status), expected, "{status:?}");
+        }
+    }
+
+    #[test]
+    fn test_vault_history_records_actor_and_time() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        assert!(client.get_vault_history(&vault_id).is_empty());
+
+        setup.advance_to(setup.start_timestamp + 5);
+        client.cancel_vault(&vault_id, &setup.usdc_token);
+
+        let history = client.get_vault_history(&vault_id);
+        assert_eq!(
+            history,
+            vec![
+                &setup.env,
+                StatusChange {
+                    from: VaultStatus::Active,
+                    to: VaultStatus::Cancelled,
+                    actor: setup.creator.clone(),
+                    timestamp: setup.start_timestamp + 5,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_permissionless_transition_attributed_to_contract() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        setup.advance_past_deadline();
+        client.redirect_funds(&vault_id, &setup.usdc_token);
+
+        let change = client.get_vault_history(&vault_id).get(0).unwrap();
+        assert_eq!(change.to, VaultStatus::Failed);
+        assert_eq!(change.actor, setup.contract_id);
+    }
+
+    // -----------------------------------------------------------------------
+    // Events only on success
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_failed_create_emits_no_events() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        let result = client.try_create_vault(
+            &setup.usdc_token,
+            &setup.creator,
+            &0i128,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.success_dest,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        assert!(result.is_err());
+        setup.assert_no_events();
+
+        let result = client.try_create_vault(
+            &setup.usdc_token,
+            &setup.creator,
+            &setup.amount,
+            &setup.start_timestamp,
+            &setup.end_timestamp,
+            &setup.milestone_hash(),
+            &None,
+            &setup.contract_id,
+            &setup.failure_dest,
+            &VaultOptions::default(),
+        );
+        assert!(result.is_err());
+        setup.assert_no_events();
+    }
+
+    #[test]
+    fn test_failed_validation_emits_no_events() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        assert!(client.try_validate_milestone(&999).is_err());
+        setup.assert_no_events();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        setup.advance_to_deadline();
+        assert!(client.try_validate_milestone(&vault_id).is_err());
+        setup.assert_no_events();
+
+        client.redirect_funds(&vault_id, &setup.usdc_token);
+        assert!(client.try_validate_milestone(&vault_id).is_err());
+        setup.assert_no_events();
+    }
+
+    #[test]
+    fn test_failed_release_emits_no_events() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        assert!(client
+            .try_release_funds(&vault_id, &setup.usdc_token)
+            .is_err());
+        setup.assert_no_events();
+
+        client.validate_milestone(&vault_id);
+        client.release_funds(&vault_id, &setup.usdc_token);
+        assert!(client
+            .try_release_funds(&vault_id, &setup.usdc_token)
+            .is_err());
+        setup.assert_no_events();
+    }
+
+    #[test]
+    fn test_failed_redirect_emits_no_events() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        assert!(client
+            .try_redirect_funds(&vault_id, &setup.usdc_token)
+            .is_err());
+        setup.assert_no_events();
+
+        client.validate_milestone(&vault_id);
+        setup.advance_past_deadline();
+        assert!(client
+            .try_redirect_funds(&vault_id, &setup.usdc_token)
+            .is_err());
+        setup.assert_no_events();
+    }
+
+    #[test]
+    fn test_failed_cancel_emits_no_events() {
+        let setup = VaultTestContext::new();
+        let client = setup.client();
+
+        assert!(client.try_cancel_vault(&999, &setup.usdc_token).is_err());
+        setup.assert_no_events();
+
+        setup.advance_to_start();
+        let vault_id = setup.create_default_vault();
+        client.cancel_vault(&vault_id, &setup.usdc_token);
+        assert!(client
+            .try_cancel_vault(&vault_id, &setup.usdc_token)
+            .is_err());
+        setup.assert_no_events();
+    }
+
     #[test]
     fn test_cancel_vault_returns_funds_to_creator() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         let usdc = setup.usdc_client();
@@ -1051,13 +2727,14 @@ mod tests {
             Some(verifier),
             success_addr,
             failure_addr,
+            VaultOptions::default(),
         );
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #7)")]
     fn test_create_vault_zero_amount() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
 
         client.create_vault(
@@ -1070,13 +2747,14 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #7)")]
     fn test_create_vault_amount_above_max_rejected() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
         let amount_above_max = MAX_AMOUNT
             .checked_add(1)
@@ -1092,6 +2770,7 @@ mod tests {
             &None,
             &setup.success_dest,
             &setup.failure_dest,
+            &VaultOptions::default(),
         );
     }
 
@@ -1120,6 +2799,7 @@ mod tests {
             Some(verifier),
             success_addr,
             failure_addr,
+            VaultOptions::default(),
         );
     }
 
@@ -1180,6 +2860,7 @@ mod tests {
             None,
             success_addr,
             failure_addr,
+            VaultOptions::default(),
         );
     }
 
@@ -1193,7 +2874,7 @@ mod tests {
         let usdc_addr = usdc_token.address();
         let usdc_asset = StellarAssetClient::new(&env, &usdc_addr);
 
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
 
         let creator = Address::generate(&env);
@@ -1217,6 +2898,7 @@ mod tests {
             &Some(verifier.clone()),
             &success_destination,
             &failure_destination,
+            &VaultOptions::default(),
         );
 
         // Vault count starts at 0, first vault gets ID 0
@@ -1261,31 +2943,31 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #3)")]
+    #[should_panic(expected = "Error(Contract, #10)")]
     fn test_cancel_vault_when_completed_fails() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Release funds to make it Completed
         client.validate_milestone(&vault_id);
         client.release_funds(&vault_id, &setup.usdc_token);
 
-        // Attempt to cancel - should panic with error VaultNotActive
+        // Attempt to cancel - should panic with error AlreadySettled
         client.cancel_vault(&vault_id, &setup.usdc_token);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #3)")]
+    #[should_panic(expected = "Error(Contract, #10)")]
     fn test_cancel_vault_when_failed_fails() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Expire and redirect funds to make it Failed
-        setup.env.ledger().set_timestamp(setup.end_timestamp + 1);
+        setup.advance_past_deadline();
         client.redirect_funds(&vault_id, &setup.usdc_token);
 
         // Attempt to cancel - should panic
@@ -1293,11 +2975,11 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #3)")]
+    #[should_panic(expected = "Error(Contract, #10)")]
     fn test_cancel_vault_when_cancelled_fails() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Cancel it
@@ -1310,8 +2992,8 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_cancel_vault_non_creator_fails() {
-        let setup = TestSetup::new();
-        setup.env.ledger().set_timestamp(setup.start_timestamp);
+        let setup = VaultTestContext::new();
+        setup.advance_to_start();
         let vault_id = setup.create_default_vault();
 
         // Try to cancel with a different address
@@ -1320,7 +3002,7 @@ mod tests {
         // But since mock_all_auths allows everything, we just rely on `VaultNotFound`
         // or we manually create a test without mock_all_auths
         let env = Env::default();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client_no_auth = DisciplrVaultClient::new(&env, &contract_id);
 
         client_no_auth.cancel_vault(&vault_id, &setup.usdc_token);
@@ -1329,7 +3011,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "Error(Contract, #1)")]
     fn test_cancel_vault_nonexistent_fails() {
-        let setup = TestSetup::new();
+        let setup = VaultTestContext::new();
         let client = setup.client();
         client.cancel_vault(&999u32, &setup.usdc_token);
     }
@@ -1357,6 +3039,7 @@ mod tests {
         let _vault = ProductivityVault {
             creator: Address::generate(&Env::default()),
             amount: 1000i128,
+            token: Address::generate(&Env::default()),
             start_timestamp: 100u64,
             end_timestamp: 200u64,
             milestone_hash: {
@@ -1370,6 +3053,15 @@ mod tests {
             failure_destination: Address::generate(&Env::default()),
             status: VaultStatus::Active,
             milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
         };
     }
 
@@ -1377,7 +3069,7 @@ mod tests {
     fn test_validate_milestone_returns_bool() {
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
 
         // Try to validate a non-existent vault (returns error, but doesn't crash)
@@ -1390,7 +3082,7 @@ mod tests {
     fn test_release_funds_returns_bool() {
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
         let usdc_token = Address::generate(&env);
 
@@ -1404,7 +3096,7 @@ mod tests {
     fn test_redirect_funds_returns_bool() {
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
         let usdc_token = Address::generate(&env);
 
@@ -1418,7 +3110,7 @@ mod tests {
     fn test_cancel_vault_returns_bool() {
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
         let usdc_token = Address::generate(&env);
 
@@ -1432,7 +3124,7 @@ mod tests {
     fn test_get_vault_state_returns_option() {
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
         let result = client.get_vault_state(&0u32);
         // Non-existent vault returns None
@@ -1482,6 +3174,7 @@ mod tests {
             VaultStatus::Completed => (),
             VaultStatus::Failed => (),
             VaultStatus::Cancelled => (),
+            VaultStatus::Pending => (),
         }
     }
 
@@ -1490,7 +3183,7 @@ mod tests {
         // Tests that the validate_milestone function exists with expected parameters
         let env = Env::default();
         env.mock_all_auths();
-        let contract_id = env.register(DisciplrVault, ());
+        let contract_id = env.register(DisciplrVault, (Address::generate(&env),));
         let client = DisciplrVaultClient::new(&env, &contract_id);
 
         // Verify function can be called through client
@@ -1540,6 +3233,7 @@ mod tests {
         let _vault = ProductivityVault {
             creator,
             amount: 1000i128,
+            token: Address::generate(&Env::default()),
             start_timestamp: 100u64,
             end_timestamp: 200u64,
             milestone_hash,
@@ -1548,6 +3242,15 @@ mod tests {
             failure_destination: failure_dest,
             status: VaultStatus::Active,
             milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
         };
     }
 
@@ -1564,6 +3267,7 @@ mod tests {
         let _vault = ProductivityVault {
             creator,
             amount: 1000i128,
+            token: Address::generate(&Env::default()),
             start_timestamp: 100u64,
             end_timestamp: 200u64,
             milestone_hash,
@@ -1572,6 +3276,15 @@ mod tests {
             failure_destination: failure_dest,
             status: VaultStatus::Active,
             milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
         };
     }
 
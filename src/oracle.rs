@@ -0,0 +1,233 @@
+//! Stakes denominated in a quote asset, priced by an external oracle.
+//!
+//! The admin registers one `PriceOracle` per quote asset symbol (e.g. `USD`) with
+//! `set_price_oracle`; the interface is SEP-40's `lastprice` / `decimals`, so a Reflector
+//! feed quoted in that asset plugs in directly. A vault created with
+//! `VaultOptions::quote_asset` commits to `quote_amount` of that asset rather than to a token
+//! amount: `create_vault` converts it at the oracle's last price and escrows the result,
+//! treating the `amount` argument as the most the creator is willing to stake. The vault
+//! remembers the oracle even if the registration later changes.
+//!
+//! When the vault completes or fails, the quote is converted again at the then-current
+//! price. The destinations receive that much of the escrow and no more than all of it; any
+//! surplus left by a price rise goes back to whoever staked the vault. If no fresh price can
+//! be read at that point (the feed is gone, traps or is stale), the destinations receive the
+//! whole escrow, which never blocks the settlement. A cancelled vault refunds everything, as
+//! usual.
+//!
+//! Amounts use the token's precision on both sides: a price `p` with `decimals` `d` means
+//! one token unit is worth `p / 10^d` quote units, so `quote_amount` buys
+//! `quote_amount * 10^d / p` token units, rounded down. At creation, prices older than
+//! `Config::max_price_age` are refused with `InvalidTimestamp`; a quote asset without an
+//! oracle, or a token the oracle has no positive price for, fails with `NotInitialized`.
+
+use soroban_sdk::{contractclient, contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::{
+    config, events, payout, privacy, sponsor, DataKeyExt, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault,
+};
+
+/// SEP-40 asset identifier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    /// A Stellar asset contract.
+    Stellar(Address),
+    /// An off-chain asset such as a fiat currency.
+    Other(Symbol),
+}
+
+/// SEP-40 price record.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Price of one unit of the asset in the oracle's base asset, scaled by `10^decimals`.
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// The subset of SEP-40 the contract calls.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Most recent price of `asset`, if the oracle has one.
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+    /// Decimals of every price the oracle reports.
+    fn decimals(env: Env) -> u32;
+}
+
+/// What a quote-denominated vault committed to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuoteTarget {
+    pub oracle: Address,
+    pub quote_asset: Symbol,
+    /// Committed value, in quote units at the token's precision.
+    pub quote_amount: i128,
+}
+
+fn target(env: &Env, vault_id: u32) -> Option<QuoteTarget> {
+    env.storage().instance().get(&DataKeyExt::Quote(vault_id))
+}
+
+/// Token units worth `quote_amount` at `oracle`'s fresh price for `usdc_token`.
+fn convert(
+    env: &Env,
+    oracle: &Address,
+    usdc_token: &Address,
+    quote_amount: i128,
+) -> Result<i128, Error> {
+    // A missing or broken oracle contract reads as a missing feed rather than trapping.
+    let client = PriceOracleClient::new(env, oracle);
+    let data = client
+        .try_lastprice(&Asset::Stellar(usdc_token.clone()))
+        .ok()
+        .and_then(Result::ok)
+        .flatten()
+        .filter(|data| data.price > 0)
+        .ok_or(Error::NotInitialized)?;
+    let decimals = client
+        .try_decimals()
+        .ok()
+        .and_then(Result::ok)
+        .ok_or(Error::NotInitialized)?;
+    let max_age = config::load(env).max_price_age;
+    let age = env.ledger().timestamp().saturating_sub(data.timestamp);
+    if max_age > 0 && age > max_age {
+        return Err(Error::InvalidTimestamp);
+    }
+    let scale = 10i128
+        .checked_pow(decimals)
+        .ok_or(Error::ArithmeticOverflow)?;
+    payout::mul_div_floor(quote_amount, scale, data.price)
+}
+
+/// Stake for a new vault: `amount` as given, or, with a `quote_asset`, `quote_amount`
+/// converted at the registered oracle's price, which may not exceed `amount`. Returns the
+/// stake and the target to `register` once the vault has an id.
+pub(crate) fn stake(
+    env: &Env,
+    usdc_token: &Address,
+    amount: i128,
+    quote_asset: Option<Symbol>,
+    quote_amount: i128,
+) -> Result<(i128, Option<QuoteTarget>), Error> {
+    let Some(quote_asset) = quote_asset else {
+        return Ok((amount, None));
+    };
+    if quote_amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let oracle: Address = env
+        .storage()
+        .instance()
+        .get(&DataKeyExt::PriceOracle(quote_asset.clone()))
+        .ok_or(Error::NotInitialized)?;
+    let stake = convert(env, &oracle, usdc_token, quote_amount)?;
+    if stake > amount {
+        return Err(Error::InvalidAmount);
+    }
+    Ok((
+        stake,
+        Some(QuoteTarget {
+            oracle,
+            quote_asset,
+            quote_amount,
+        }),
+    ))
+}
+
+pub(crate) fn register(env: &Env, vault_id: u32, target: Option<QuoteTarget>) {
+    if let Some(target) = target {
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Quote(vault_id), &target);
+    }
+}
+
+/// Move a renewed vault's quote target to its successor.
+pub(crate) fn carry_over(env: &Env, from: u32, to: u32) {
+    if let Some(target) = target(env, from) {
+        env.storage().instance().remove(&DataKeyExt::Quote(from));
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Quote(to), &target);
+    }
+}
+
+/// Payout weights for settling `vault` to `recipients`: unchanged for token-denominated
+/// vaults. Otherwise the recipients share the escrow worth the quote at the current price,
+/// capped at the whole escrow, and the vault's payer gets the rest. Without a fresh price the
+/// recipients share the whole escrow.
+pub(crate) fn apply(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    usdc_token: &Address,
+    recipients: Vec<(Address, i128)>,
+) -> Result<Vec<(Address, i128)>, Error> {
+    let Some(target) = target(env, vault_id) else {
+        return Ok(recipients);
+    };
+    let Ok(due) = convert(env, &target.oracle, usdc_token, target.quote_amount) else {
+        return Ok(recipients);
+    };
+    let due = due.min(vault.amount);
+    let surplus = vault.amount - due;
+    if surplus == 0 {
+        return Ok(recipients);
+    }
+
+    let mut total_weight: i128 = 0;
+    let mut weights = Vec::new(env);
+    for (recipient, weight) in recipients.iter() {
+        total_weight = total_weight
+            .checked_add(weight)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let weight = weight.checked_mul(due).ok_or(Error::ArithmeticOverflow)?;
+        weights.push_back((recipient, weight));
+    }
+    let surplus_weight = surplus
+        .checked_mul(total_weight)
+        .ok_or(Error::ArithmeticOverflow)?;
+    weights.push_back((sponsor::payer(env, vault_id, vault), surplus_weight));
+    Ok(weights)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Register `oracle` as the price source for `quote_asset`, or unregister with `None`.
+    /// Admin only. Only vaults created afterwards are affected.
+    pub fn set_price_oracle(
+        env: Env,
+        quote_asset: Symbol,
+        oracle: Option<Address>,
+    ) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        let key = DataKeyExt::PriceOracle(quote_asset.clone());
+        match &oracle {
+            Some(oracle) => env.storage().instance().set(&key, oracle),
+            None => env.storage().instance().remove(&key),
+        }
+
+        events::price_oracle_set(&env, &admin, &quote_asset, &oracle);
+        Ok(())
+    }
+
+    /// Oracle registered for `quote_asset`, if any.
+    pub fn get_price_oracle(env: Env, quote_asset: Symbol) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKeyExt::PriceOracle(quote_asset))
+    }
+
+    /// Quote a vault committed to, or `None` for token-denominated vaults. The quote amount
+    /// is zero for private vaults.
+    pub fn get_quote_target(env: Env, vault_id: u32) -> Option<QuoteTarget> {
+        let mut target = target(&env, vault_id)?;
+        if privacy::is_private(&env, vault_id) {
+            target.quote_amount = 0;
+        }
+        Some(target)
+    }
+}
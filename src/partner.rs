@@ -0,0 +1,74 @@
+//! Accountability partners: a second signature on cancellation.
+//!
+//! A vault created with `VaultOptions::accountability_partner` cannot be cancelled by its
+//! creator alone. `cancel_vault` records a pending cancellation instead, and the partner has
+//! `CANCEL_APPROVAL_WINDOW` seconds to confirm it with `approve_cancellation`. If the partner
+//! never approves, the proposal lapses and the vault carries on; the creator may propose
+//! again. The partner has no other powers: it cannot validate, release or redirect.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{
+    cancel_and_refund, events, load_vault, require_unsettled, DataKey, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault,
+};
+
+/// How long a proposed cancellation waits for the partner: 3 days.
+pub const CANCEL_APPROVAL_WINDOW: u64 = 259_200;
+
+/// Record (or renew) the creator's cancellation request on `vault_id`.
+pub(crate) fn propose_cancellation(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    let expires_at = env
+        .ledger()
+        .timestamp()
+        .saturating_add(CANCEL_APPROVAL_WINDOW);
+    env.storage()
+        .instance()
+        .set(&DataKey::PendingCancel(vault_id), &expires_at);
+
+    events::cancellation_proposed(env, vault_id, &vault.creator, expires_at);
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// The accountability partner confirms the creator's pending cancellation, which cancels
+    /// and refunds the vault like `cancel_vault`.
+    ///
+    /// Fails with `NoPendingCancellation` when the vault has no partner or nothing is pending,
+    /// `CancellationExpired` once `CANCEL_APPROVAL_WINDOW` has passed since the proposal, and
+    /// `AlreadySettled` if the vault settled in the meantime.
+    pub fn approve_cancellation(
+        env: Env,
+        vault_id: u32,
+        usdc_token: Address,
+    ) -> Result<bool, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        let partner = vault
+            .accountability_partner
+            .clone()
+            .ok_or(Error::NoPendingCancellation)?;
+        partner.require_auth();
+        require_unsettled(&vault)?;
+
+        let key = DataKey::PendingCancel(vault_id);
+        let expires_at: u64 = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(Error::NoPendingCancellation)?;
+        if env.ledger().timestamp() >= expires_at {
+            return Err(Error::CancellationExpired);
+        }
+        env.storage().instance().remove(&key);
+
+        cancel_and_refund(&env, vault_id, vault, &usdc_token, partner)
+    }
+
+    /// Expiry of the cancellation awaiting the partner's approval, if one is pending. A past
+    /// timestamp means the proposal lapsed.
+    pub fn get_pending_cancellation(env: Env, vault_id: u32) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingCancel(vault_id))
+    }
+}
@@ -0,0 +1,377 @@
+//! Payout math shared by every settlement path.
+//!
+//! All arithmetic is checked: an overflow surfaces as `Error::ArithmeticOverflow` instead of
+//! wrapping or trapping. The rounding policy is defined here and nowhere else:
+//!
+//! - Basis-point cuts (fees, penalties, rewards) round **down**, so a cut is never larger
+//!   than its exact share.
+//! - When an amount is split across several recipients, every share but the last rounds
+//!   down and the **last recipient absorbs the remainder**.
+//! - When an amount is paid out in tranches, every tranche but the last is
+//!   `amount / count` and the **last tranche sweeps the dust**.
+//!
+//! Together these guarantee that the parts computed for a payout always sum to exactly the
+//! amount that was escrowed.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::Error;
+
+/// Basis-point denominator: 10_000 bps == 100%.
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// `amount * numerator / denominator`, rounded down.
+///
+/// `amount` and `numerator` must be non-negative and `denominator` strictly positive.
+pub fn mul_div_floor(amount: i128, numerator: i128, denominator: i128) -> Result<i128, Error> {
+    if amount < 0 || numerator < 0 || denominator <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    amount
+        .checked_mul(numerator)
+        .and_then(|product| product.checked_div(denominator))
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// The `bps` share of `amount`, rounded down. Rejects `bps` above `BPS_DENOMINATOR`.
+pub fn bps_of(amount: i128, bps: u32) -> Result<i128, Error> {
+    if bps > BPS_DENOMINATOR {
+        return Err(Error::InvalidBps);
+    }
+    mul_div_floor(amount, bps as i128, BPS_DENOMINATOR as i128)
+}
+
+/// Split `amount` into `(cut, remainder)` where `cut` is the `bps` share rounded down.
+pub fn deduct_bps(amount: i128, bps: u32) -> Result<(i128, i128), Error> {
+    let cut = bps_of(amount, bps)?;
+    let rest = amount.checked_sub(cut).ok_or(Error::ArithmeticOverflow)?;
+    Ok((cut, rest))
+}
+
+/// Size of tranche `index` (zero-based) when `amount` is paid out in `count` tranches.
+pub fn tranche(amount: i128, count: u32, index: u32) -> Result<i128, Error> {
+    if amount < 0 || count == 0 || index >= count {
+        return Err(Error::InvalidAmount);
+    }
+    let base = amount
+        .checked_div(count as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+    if index + 1 < count {
+        return Ok(base);
+    }
+    let earlier = base
+        .checked_mul((count - 1) as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+    amount.checked_sub(earlier).ok_or(Error::ArithmeticOverflow)
+}
+
+/// Allocate `amount` across `recipients` proportionally to their weights.
+///
+/// Weights are arbitrary non-negative numbers (bps, contributions, ...) with a positive
+/// total. Each share rounds down except the last, which absorbs the remainder, so the
+/// returned amounts always sum to `amount`.
+pub fn allocate(
+    env: &Env,
+    amount: i128,
+    recipients: &Vec<(Address, i128)>,
+) -> Result<Vec<(Address, i128)>, Error> {
+    if amount < 0 || recipients.is_empty() {
+        return Err(Error::InvalidAmount);
+    }
+    let mut total_weight: i128 = 0;
+    for (_, weight) in recipients.iter() {
+        if weight < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        total_weight = total_weight
+            .checked_add(weight)
+            .ok_or(Error::ArithmeticOverflow)?;
+    }
+    if total_weight == 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let last = recipients.len() - 1;
+    let mut shares = Vec::new(env);
+    let mut allocated: i128 = 0;
+    for (i, (recipient, weight)) in recipients.iter().enumerate() {
+        let share = if i as u32 == last {
+            amount
+                .checked_sub(allocated)
+                .ok_or(Error::ArithmeticOverflow)?
+        } else {
+            mul_div_floor(amount, weight, total_weight)?
+        };
+        allocated = allocated
+            .checked_add(share)
+            .ok_or(Error::ArithmeticOverflow)?;
+        shares.push_back((recipient, share));
+    }
+    Ok(shares)
+}
+
+/// Weights for a graded settlement: `score_bps` of the payout to the `success` recipients
+/// (keeping their relative weights) and the rest to `failure`, listed last so that it absorbs
+/// the rounding remainder when passed to `allocate`. A full score returns `success` as is.
+pub fn grade(
+    env: &Env,
+    success: &Vec<(Address, i128)>,
+    failure: &Address,
+    score_bps: u32,
+) -> Result<Vec<(Address, i128)>, Error> {
+    if score_bps > BPS_DENOMINATOR {
+        return Err(Error::InvalidBps);
+    }
+    if score_bps == BPS_DENOMINATOR {
+        return Ok(success.clone());
+    }
+    let mut weights = Vec::new(env);
+    let mut total: i128 = 0;
+    for (recipient, weight) in success.iter() {
+        total = total.checked_add(weight).ok_or(Error::ArithmeticOverflow)?;
+        let scaled = weight
+            .checked_mul(score_bps as i128)
+            .ok_or(Error::ArithmeticOverflow)?;
+        weights.push_back((recipient, scaled));
+    }
+    let remainder = total
+        .checked_mul((BPS_DENOMINATOR - score_bps) as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+    weights.push_back((failure.clone(), remainder));
+    Ok(weights)
+}
+
+/// Sum of the amounts in `shares`, checked.
+pub fn total(shares: &Vec<(Address, i128)>) -> Result<i128, Error> {
+    let mut sum: i128 = 0;
+    for (_, share) in shares.iter() {
+        sum = sum.checked_add(share).ok_or(Error::ArithmeticOverflow)?;
+    }
+    Ok(sum)
+}
+
+/// Everything a settlement pays out, computed once and used both for the token transfers and
+/// as the settlement event payload, so the two can never disagree.
+///
+/// `fee + penalty + verifier_reward + total(shares) == gross` always holds. `shares` has one
+/// entry per payout recipient, so its length is bounded by the recipient count.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutBreakdown {
+    /// Escrowed amount being settled.
+    pub gross: i128,
+    /// Protocol fee withheld from `gross`.
+    pub fee: i128,
+    /// Penalty withheld from `gross`.
+    pub penalty: i128,
+    /// Reward paid to the verifier.
+    pub verifier_reward: i128,
+    /// `(recipient, net)` for every recipient of the remainder.
+    pub shares: Vec<(Address, i128)>,
+}
+
+impl PayoutBreakdown {
+    /// Sum of every part of the breakdown; equals `gross` for any breakdown built here.
+    pub fn parts_total(&self) -> Result<i128, Error> {
+        [self.fee, self.penalty, self.verifier_reward]
+            .into_iter()
+            .try_fold(total(&self.shares)?, |sum, part| sum.checked_add(part))
+            .ok_or(Error::ArithmeticOverflow)
+    }
+}
+
+/// Breakdown of `gross` with a `fee_bps` protocol fee withheld and the rest split across
+/// `recipients` by weight.
+pub fn breakdown(
+    env: &Env,
+    gross: i128,
+    fee_bps: u32,
+    recipients: &Vec<(Address, i128)>,
+) -> Result<PayoutBreakdown, Error> {
+    let (fee, net) = deduct_bps(gross, fee_bps)?;
+    Ok(PayoutBreakdown {
+        gross,
+        fee,
+        penalty: 0,
+        verifier_reward: 0,
+        shares: allocate(env, net, recipients)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MAX_AMOUNT;
+    use soroban_sdk::{testutils::Address as _, vec};
+
+    /// Small deterministic xorshift generator so failing cases can be replayed from the seed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+
+        fn amount(&mut self) -> i128 {
+            // Mix tiny dust amounts with amounts near the cap.
+            match self.below(3) {
+                0 => self.below(100) as i128,
+                1 => self.below(1_000_000_000) as i128,
+                _ => MAX_AMOUNT - self.below(1_000_000) as i128,
+            }
+        }
+    }
+
+    #[test]
+    fn test_bps_of_rounds_down() {
+        assert_eq!(bps_of(999, 1), Ok(0));
+        assert_eq!(bps_of(10_000, 1), Ok(1));
+        assert_eq!(bps_of(19_999, 1), Ok(1));
+        assert_eq!(bps_of(1_000, BPS_DENOMINATOR), Ok(1_000));
+        assert_eq!(bps_of(1_000, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_bps_of_rejects_out_of_range_bps() {
+        assert_eq!(bps_of(1_000, BPS_DENOMINATOR + 1), Err(Error::InvalidBps));
+    }
+
+    #[test]
+    fn test_mul_div_floor_reports_overflow() {
+        assert_eq!(
+            mul_div_floor(i128::MAX, 2, 1),
+            Err(Error::ArithmeticOverflow)
+        );
+        assert_eq!(mul_div_floor(10, 1, 0), Err(Error::InvalidAmount));
+    }
+
+    #[test]
+    fn test_tranche_last_sweeps_dust() {
+        assert_eq!(tranche(10, 3, 0), Ok(3));
+        assert_eq!(tranche(10, 3, 1), Ok(3));
+        assert_eq!(tranche(10, 3, 2), Ok(4));
+        assert_eq!(tranche(2, 3, 0), Ok(0));
+        assert_eq!(tranche(2, 3, 2), Ok(2));
+        assert_eq!(tranche(10, 3, 3), Err(Error::InvalidAmount));
+        assert_eq!(tranche(10, 0, 0), Err(Error::InvalidAmount));
+    }
+
+    #[test]
+    fn test_allocate_last_recipient_absorbs_remainder() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        let shares = allocate(
+            &env,
+            100,
+            &vec![&env, (a.clone(), 1), (b.clone(), 1), (c.clone(), 1)],
+        )
+        .unwrap();
+        assert_eq!(shares, vec![&env, (a, 33), (b, 33), (c, 34)]);
+    }
+
+    #[test]
+    fn test_allocate_rejects_empty_and_zero_weight() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        assert_eq!(
+            allocate(&env, 100, &Vec::new(&env)),
+            Err(Error::InvalidAmount)
+        );
+        assert_eq!(
+            allocate(&env, 100, &vec![&env, (a, 0)]),
+            Err(Error::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn test_grade_rounds_the_success_share_down() {
+        let env = Env::default();
+        let success = Address::generate(&env);
+        let failure = Address::generate(&env);
+        let weights = grade(&env, &vec![&env, (success.clone(), 1)], &failure, 3_333).unwrap();
+        assert_eq!(
+            allocate(&env, 100, &weights).unwrap(),
+            vec![&env, (success.clone(), 33), (failure.clone(), 67)]
+        );
+        let full = vec![&env, (success, 1)];
+        assert_eq!(
+            grade(&env, &full, &failure, BPS_DENOMINATOR),
+            Ok(full.clone())
+        );
+        assert_eq!(
+            grade(&env, &full, &failure, BPS_DENOMINATOR + 1),
+            Err(Error::InvalidBps)
+        );
+    }
+
+    #[test]
+    fn test_breakdown_parts_sum_to_gross() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let parts = breakdown(&env, 101, 0, &vec![&env, (a.clone(), 1), (b.clone(), 1)]).unwrap();
+        assert_eq!(parts.gross, 101);
+        assert_eq!(parts.parts_total(), Ok(101));
+
+        // 3.5% of 101 rounds down to 3; the 98 left splits 49/49.
+        let parts = breakdown(&env, 101, 350, &vec![&env, (a.clone(), 1), (b.clone(), 1)]).unwrap();
+        assert_eq!(parts.fee, 3);
+        assert_eq!(parts.shares, vec![&env, (a, 49), (b, 49)]);
+        assert_eq!(parts.parts_total(), Ok(101));
+    }
+
+    #[test]
+    fn test_property_fee_plus_rest_equals_amount() {
+        let mut rng = Rng(0x5eed_0001);
+        for _ in 0..2_000 {
+            let amount = rng.amount();
+            let bps = rng.below(BPS_DENOMINATOR as u64 + 1) as u32;
+            let (cut, rest) = deduct_bps(amount, bps).unwrap();
+            assert!(cut >= 0 && rest >= 0, "amount={amount} bps={bps}");
+            assert_eq!(cut + rest, amount, "amount={amount} bps={bps}");
+            assert!(cut * BPS_DENOMINATOR as i128 <= amount * bps as i128);
+        }
+    }
+
+    #[test]
+    fn test_property_tranches_sum_to_amount() {
+        let mut rng = Rng(0x5eed_0002);
+        for _ in 0..500 {
+            let amount = rng.amount();
+            let count = 1 + rng.below(52) as u32;
+            let sum: i128 = (0..count).map(|i| tranche(amount, count, i).unwrap()).sum();
+            assert_eq!(sum, amount, "amount={amount} count={count}");
+        }
+    }
+
+    #[test]
+    fn test_property_allocation_sums_to_amount() {
+        let env = Env::default();
+        let mut rng = Rng(0x5eed_0003);
+        for _ in 0..500 {
+            let amount = rng.amount();
+            let mut recipients = Vec::new(&env);
+            let n = 1 + rng.below(10);
+            for _ in 0..n {
+                let weight = rng.below(BPS_DENOMINATOR as u64) as i128;
+                recipients.push_back((Address::generate(&env), weight));
+            }
+            if recipients.iter().all(|(_, w)| w == 0) {
+                recipients.set(0, (recipients.get(0).unwrap().0, 1));
+            }
+            let shares = allocate(&env, amount, &recipients).unwrap();
+            assert_eq!(shares.len(), recipients.len());
+            assert!(shares.iter().all(|(_, s)| s >= 0));
+            assert_eq!(total(&shares), Ok(amount), "amount={amount}");
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Private vaults: redacted public reads and events.
+//!
+//! A vault created with `VaultOptions::visibility` set to `Private` keeps its status and
+//! timestamps public but hides who and how much. `get_vault_state` returns it redacted, with
+//! the amount zeroed and every address replaced by this contract's address (or `None` where
+//! optional); its creator and verifier read the real state through `get_vault_state_authed`.
+//! Amounts in its event payloads are zeroed the same way, while the topics keep their usual
+//! layout so parties can still follow their own vaults.
+//!
+//! The per-vault getters follow the same rule (see `is_private`): yield positions,
+//! installment schedules and quote targets report zero amounts; status history and
+//! attestations report this contract's address as the actor or witness; the original
+//! creator is this contract's address; and the pending creator, the witness list, the
+//! settlement callback and the emergency approvals are reported as absent.
+//!
+//! This is read-path privacy only: settlement is unchanged, ledger storage and token
+//! transfers stay visible to anyone inspecting the chain directly.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::payout::PayoutBreakdown;
+use crate::{
+    category, load_vault, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+    ProductivityVault,
+};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Visibility {
+    #[default]
+    Public = 0,
+    /// Stake and counterparties are redacted from public reads and events.
+    Private = 1,
+}
+
+/// Public view of `vault`: unchanged for public vaults, otherwise only its status,
+/// timestamps, token, visibility and mode.
+pub(crate) fn redact(env: &Env, vault: &ProductivityVault) -> ProductivityVault {
+    if vault.visibility == Visibility::Public {
+        return vault.clone();
+    }
+    let placeholder = env.current_contract_address();
+    ProductivityVault {
+        creator: placeholder.clone(),
+        amount: 0,
+        token: vault.token.clone(),
+        start_timestamp: vault.start_timestamp,
+        end_timestamp: vault.end_timestamp,
+        milestone_hash: BytesN::from_array(env, &[0; 32]),
+        verifier: None,
+        success_destination: placeholder.clone(),
+        failure_destination: placeholder,
+        status: vault.status,
+        milestone_validated: vault.milestone_validated,
+        referrer: None,
+        accountability_partner: None,
+        destinations_locked: false,
+        reveal_required: false,
+        category: category::DEFAULT_CATEGORY,
+        allow_graded: false,
+        visibility: vault.visibility,
+        auto_renew: false,
+        mode: vault.mode,
+    }
+}
+
+/// Whether `vault_id` is a private vault, whose getters redact as `redact` does. `false` for
+/// unknown vaults.
+pub(crate) fn is_private(env: &Env, vault_id: u32) -> bool {
+    load_vault(env, vault_id).is_ok_and(|vault| vault.visibility == Visibility::Private)
+}
+
+/// `amount` as published for `vault`: zero for private vaults.
+pub(crate) fn amount(vault: &ProductivityVault, amount: i128) -> i128 {
+    match vault.visibility {
+        Visibility::Public => amount,
+        Visibility::Private => 0,
+    }
+}
+
+/// `parts` as published for `vault`: the recipients are kept, every amount is zeroed for
+/// private vaults.
+pub(crate) fn breakdown(
+    env: &Env,
+    vault: &ProductivityVault,
+    parts: &PayoutBreakdown,
+) -> PayoutBreakdown {
+    if vault.visibility == Visibility::Public {
+        return parts.clone();
+    }
+    let mut shares = Vec::new(env);
+    for (recipient, _) in parts.shares.iter() {
+        shares.push_back((recipient, 0));
+    }
+    PayoutBreakdown {
+        gross: 0,
+        fee: 0,
+        penalty: 0,
+        verifier_reward: 0,
+        shares,
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Full state of a vault for its creator or verifier, whatever its visibility. `viewer`
+    /// must authorize the call; fails with `NotAuthorized` for anyone else and
+    /// `VaultNotFound` for an unknown vault.
+    pub fn get_vault_state_authed(
+        env: Env,
+        vault_id: u32,
+        viewer: Address,
+    ) -> Result<ProductivityVault, Error> {
+        viewer.require_auth();
+        let vault = load_vault(&env, vault_id)?;
+        if viewer != vault.creator && vault.verifier.as_ref() != Some(&viewer) {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(vault)
+    }
+}
@@ -0,0 +1,221 @@
+//! Proof submissions and revision requests.
+//!
+//! The creator submits a hash of their proof of work; the validator (verifier, or creator
+//! when there is none) either asks for changes with a `ReasonCode` or approves through
+//! `validate_milestone`, which approves the latest submission, or `approve_proof`, which
+//! approves a given one. Every step emits an event, so the creator has an on-chain record of
+//! what was submitted and when.
+//!
+//! Submissions form an append-only log of timestamped entries, so a goal like "write every
+//! day" keeps the whole history of the work rather than only its last proof. The log lives
+//! in persistent storage, in buckets of `PROOF_BUCKET_SIZE` entries, so a submission only
+//! rewrites the bucket it lands in and never the contract instance; every write extends the
+//! entries it touches to the maximum TTL. With `Config::timeout_min_proofs` set, a vault
+//! with a verifier released past its deadline without validation needs that many entries
+//! as evidence of effort.
+//!
+//! The log is only kept while it can matter: once the vault settles, `clear` removes its
+//! buckets and count. `proof_submitted` events keep the history for indexers, and
+//! `get_approved_proof` keeps pointing at the approved entry's index.
+
+use soroban_sdk::{contractimpl, contracttype, BytesN, Env, IntoVal, Val, Vec};
+
+use crate::{events, load_vault, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient};
+use crate::{reputation, validate, Error, VaultStatus};
+
+/// Upper bound on submissions per vault, keeping the log bounded.
+pub const MAX_PROOF_SUBMISSIONS: u32 = 100;
+
+/// Entries stored under one `DataKey::ProofBucket`.
+pub const PROOF_BUCKET_SIZE: u32 = 10;
+
+/// One submission in a vault's proof log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofEntry {
+    pub hash: BytesN<32>,
+    /// Ledger timestamp of the submission.
+    pub timestamp: u64,
+}
+
+/// Why the validator asked for changes. Stable `u32` codes apps can map to localized
+/// messages; new codes are only ever appended.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ReasonCode {
+    Unspecified = 0,
+    /// Parts of the milestone are missing.
+    Incomplete = 1,
+    /// The proof does not show the work was done.
+    InsufficientEvidence = 2,
+    /// The proof is for a different milestone.
+    WrongMilestone = 3,
+    /// The work is done but below the agreed standard.
+    QualityIssue = 4,
+}
+
+/// Number of proofs submitted for `vault_id`.
+pub(crate) fn count(env: &Env, vault_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProofCount(vault_id))
+        .unwrap_or(0)
+}
+
+fn load_bucket(env: &Env, vault_id: u32, bucket: u32) -> Vec<ProofEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProofBucket(vault_id, bucket))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Write a persistent proof log entry and keep it alive for as long as the network allows.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let storage = env.storage().persistent();
+    storage.set(key, value);
+    let max_ttl = env.storage().max_ttl();
+    storage.extend_ttl(key, max_ttl, max_ttl);
+}
+
+/// Remove the proof log of a vault that just settled. Called by `transition`.
+pub(crate) fn clear(env: &Env, vault_id: u32) {
+    let count = count(env, vault_id);
+    let storage = env.storage().persistent();
+    for bucket in 0..count.div_ceil(PROOF_BUCKET_SIZE) {
+        storage.remove(&DataKey::ProofBucket(vault_id, bucket));
+    }
+    storage.remove(&DataKey::ProofCount(vault_id));
+}
+
+fn entry(env: &Env, vault_id: u32, index: u32) -> Option<ProofEntry> {
+    if index >= count(env, vault_id) {
+        return None;
+    }
+    load_bucket(env, vault_id, index / PROOF_BUCKET_SIZE).get(index % PROOF_BUCKET_SIZE)
+}
+
+/// Most recent proof submitted for `vault_id`, if any.
+pub(crate) fn latest(env: &Env, vault_id: u32) -> Option<BytesN<32>> {
+    let index = count(env, vault_id).checked_sub(1)?;
+    entry(env, vault_id, index).map(|entry| entry.hash)
+}
+
+/// Record which entry a validation approves: `index`, or the latest when `None`. Returns its
+/// hash, or `None` when nothing was submitted. Fails with `NoProofSubmitted` for an `index`
+/// past the end of the log.
+pub(crate) fn approve(
+    env: &Env,
+    vault_id: u32,
+    index: Option<u32>,
+) -> Result<Option<BytesN<32>>, Error> {
+    let index = match index {
+        Some(index) if index >= count(env, vault_id) => return Err(Error::NoProofSubmitted),
+        Some(index) => index,
+        None => match count(env, vault_id).checked_sub(1) {
+            Some(index) => index,
+            None => return Ok(None),
+        },
+    };
+    env.storage()
+        .instance()
+        .set(&DataKey::ApprovedProof(vault_id), &index);
+    Ok(entry(env, vault_id, index).map(|entry| entry.hash))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Creator submits `proof_hash` as evidence for the milestone. Returns the zero-based
+    /// submission index.
+    ///
+    /// Allowed while the vault is Active, not yet validated and the validation window is
+    /// open. Fails with `TooManyProofs` after `MAX_PROOF_SUBMISSIONS` submissions. Earlier
+    /// submissions are kept; the new one is appended to the log.
+    pub fn submit_proof(env: Env, vault_id: u32, proof_hash: BytesN<32>) -> Result<u32, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        vault.creator.require_auth();
+        if !vault.validation_open(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        if vault.milestone_validated {
+            return Err(Error::InvalidStatus);
+        }
+
+        let index = count(&env, vault_id);
+        if index >= MAX_PROOF_SUBMISSIONS {
+            return Err(Error::TooManyProofs);
+        }
+        let bucket = index / PROOF_BUCKET_SIZE;
+        let mut entries = load_bucket(&env, vault_id, bucket);
+        entries.push_back(ProofEntry {
+            hash: proof_hash.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        store(&env, &DataKey::ProofBucket(vault_id, bucket), &entries);
+        store(&env, &DataKey::ProofCount(vault_id), &(index + 1));
+
+        events::proof_submitted(&env, vault_id, &vault.creator, &proof_hash, index);
+        Ok(index)
+    }
+
+    /// Validator rejects the latest proof, asking the creator to resubmit.
+    ///
+    /// Same preconditions as `validate_milestone`, plus `NoProofSubmitted` when there is
+    /// nothing to reject and `InvalidStatus` once the milestone has been validated.
+    pub fn request_changes(env: Env, vault_id: u32, reason: ReasonCode) -> Result<bool, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        vault.validator().require_auth();
+        if !vault.validation_open(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        if vault.milestone_validated {
+            return Err(Error::InvalidStatus);
+        }
+        let proof_hash = latest(&env, vault_id).ok_or(Error::NoProofSubmitted)?;
+        reputation::record_response(&env, vault_id, &vault);
+
+        events::changes_requested(&env, vault_id, vault.validator(), &proof_hash, reason);
+        Ok(true)
+    }
+
+    /// Validator validates the milestone like `validate_milestone`, approving the proof at
+    /// `index` rather than the latest one.
+    ///
+    /// Same preconditions as `validate_milestone`, plus `NoProofSubmitted` when the log has
+    /// no entry at `index`.
+    pub fn approve_proof(env: Env, vault_id: u32, index: u32) -> Result<bool, Error> {
+        validate(&env, vault_id, Some(index))
+    }
+
+    /// Proof log entries with an index in `[start, start + limit)`, oldest first. Empty once
+    /// the vault has settled.
+    pub fn get_proofs(env: Env, vault_id: u32, start: u32, limit: u32) -> Vec<ProofEntry> {
+        let end = start.saturating_add(limit).min(count(&env, vault_id));
+        let mut page = Vec::new(&env);
+        for index in start..end {
+            if let Some(entry) = entry(&env, vault_id, index) {
+                page.push_back(entry);
+            }
+        }
+        page
+    }
+
+    /// Number of proofs submitted for a vault, or zero once it has settled.
+    pub fn get_proof_count(env: Env, vault_id: u32) -> u32 {
+        count(&env, vault_id)
+    }
+
+    /// Index of the log entry the milestone validation approved, or `None` if the vault was
+    /// not validated or had no proofs.
+    pub fn get_approved_proof(env: Env, vault_id: u32) -> Option<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ApprovedProof(vault_id))
+    }
+}
@@ -0,0 +1,111 @@
+//! Moving a cancelled vault's stake straight into a new vault.
+//!
+//! `recommit` is `cancel_vault` followed by `create_vault` without the round trip through
+//! the creator's wallet: the old vault is cancelled (withholding a savings lock's
+//! early-withdrawal penalty, if due) and the escrow it would have refunded funds the new
+//! vault instead. The new vault is checked and indexed exactly like one from `create_vault`,
+//! with `VaultTerms` and `VaultOptions` in place of the usual arguments and the retained
+//! escrow as its `amount`; only a quote-denominated successor staking less returns the
+//! difference to the creator. No token moves otherwise, except into and out of a yield
+//! adapter.
+//!
+//! Only vaults whose refund would go to their creator alone can be recommitted: group,
+//! sponsored, wager, bounty and gift vaults cannot (`InvalidStatus`), and a vault with an
+//! accountability partner needs the partner's approval to cancel (`NotAuthorized`). The old
+//! vault's `vault_cancelled` is followed by `vault_recommitted` naming the successor, whose
+//! `vault_created` comes last; `get_successor` links the two ids afterwards.
+
+use soroban_sdk::{contractimpl, contracttype, vec, Address, BytesN, Env};
+
+use crate::{
+    bounty, callback, events, gift, group, load_vault, open_vault, payout, privacy,
+    require_unsettled, savings, settle, wager, yield_adapter, DataKey, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, VaultOptions, VaultStatus,
+};
+
+/// The `create_vault` arguments that describe a vault's milestone, besides its stake.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultTerms {
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub milestone_hash: BytesN<32>,
+    pub verifier: Option<Address>,
+    pub success_destination: Address,
+    pub failure_destination: Address,
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Creator cancels an Active vault and opens a new one with `terms` and `options`,
+    /// funded by the escrow the cancellation would have refunded. Returns the new vault's id.
+    ///
+    /// Fails where `cancel_vault` would, with `InvalidStatus` for a vault not staked by its
+    /// creator alone and `NotAuthorized` for one with an accountability partner; the new
+    /// vault is then validated like `create_vault`.
+    pub fn recommit(
+        env: Env,
+        vault_id: u32,
+        usdc_token: Address,
+        terms: VaultTerms,
+        options: VaultOptions,
+    ) -> Result<u32, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        let creator = vault.creator.clone();
+        creator.require_auth();
+        require_unsettled(&vault)?;
+        if wager::is_wager(&env, vault_id)
+            || bounty::is_bounty(&env, vault_id)
+            || gift::is_gift(&env, vault_id)
+            // Group and sponsored vaults refund someone else's money.
+            || group::refund_recipients(&env, vault_id, &vault)
+                != vec![&env, (creator.clone(), 1)]
+        {
+            return Err(Error::InvalidStatus);
+        }
+        if vault.accountability_partner.is_some() {
+            return Err(Error::NotAuthorized);
+        }
+        savings::check_cancel(&env, &vault)?;
+
+        let penalty = savings::withhold_penalty(&env, vault_id, &mut vault, &usdc_token)?;
+        // Settling to this contract keeps the refund in escrow for the successor.
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &vec![&env, (env.current_contract_address(), 1)],
+            VaultStatus::Cancelled,
+            creator.clone(),
+        )?;
+        let retained = payout::total(&parts.shares)?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        let (successor_id, successor) = open_vault(
+            &env,
+            &usdc_token,
+            creator,
+            retained,
+            terms,
+            options,
+            retained,
+        )?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Successor(vault_id), &successor_id);
+
+        savings::publish(&env, vault_id, &vault, penalty);
+        events::vault_cancelled(
+            &env,
+            vault_id,
+            &vault.creator,
+            privacy::amount(&vault, vault.amount),
+        );
+        yield_adapter::publish(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        events::vault_recommitted(&env, vault_id, &vault.creator, successor_id);
+        events::vault_created(&env, successor_id, &successor);
+        Ok(successor_id)
+    }
+}
@@ -0,0 +1,422 @@
+//! Recurring commitment vaults: one stake, checked in period by period.
+//!
+//! A recurring vault locks `amount` and splits it into `period_count` slices (see
+//! `payout::tranche`; the last slice sweeps the dust). Period `i` covers
+//! `[start + i * period_length, start + (i + 1) * period_length)` and its check-in must
+//! happen inside that window through `validate_period`. Once a period's window has closed
+//! without a check-in, anyone may call `redirect_missed_period` to send its slice to the
+//! failure destination.
+//!
+//! Validated slices go to the success destination immediately or, when the schedule does
+//! not ask for that, stay escrowed and are paid out together once the last period settles.
+//! Either way they pay the protocol fee (see `fees`): an immediate slice on its own, the
+//! deferred ones as part of the final settlement.
+//! A period is settled once it is validated or missed; when every period is settled the
+//! vault becomes `Completed`, or `Failed` if no period was ever validated.
+//!
+//! `ProductivityVault::amount` tracks what is still escrowed: every slice paid out is
+//! deducted from it, so cancelling refunds exactly the remainder. The single-milestone
+//! entrypoints (`validate_milestone`, `release_funds`, `redirect_funds`) reject recurring
+//! vaults with `InvalidStatus`.
+
+use soroban_sdk::{contractimpl, contracttype, token, vec, Address, BytesN, Env};
+
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    badge, category, check_new_vault, check_token, events, fees, load_vault, payout, reputation,
+    settle, store_new_vault, streak, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Upper bound on `period_count`, e.g. weekly check-ins for a year.
+pub const MAX_PERIODS: u32 = 52;
+
+/// How a recurring vault is divided, fixed at creation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurringSchedule {
+    /// Length of every period in seconds.
+    pub period_length: u64,
+    /// Number of periods, `1..=MAX_PERIODS`.
+    pub period_count: u32,
+    /// Pay each validated slice out as soon as it is validated instead of at the end.
+    pub release_immediately: bool,
+}
+
+/// Progress of a recurring vault.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurringState {
+    pub schedule: RecurringSchedule,
+    /// Stake at creation; slices are computed from it.
+    pub total: i128,
+    /// Periods validated or missed so far.
+    pub settled_periods: u32,
+    /// Periods validated so far.
+    pub validated_periods: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PeriodStatus {
+    /// Not checked in yet; its window may still be open.
+    Pending = 0,
+    Validated = 1,
+    /// Window closed without a check-in; the slice went to the failure destination.
+    Missed = 2,
+}
+
+pub(crate) fn is_recurring(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Recurring(vault_id))
+}
+
+/// Stake a recurring vault was created with, or `None` for other vaults.
+pub(crate) fn total_stake(env: &Env, vault_id: u32) -> Option<i128> {
+    env.storage()
+        .instance()
+        .get::<_, RecurringState>(&DataKey::Recurring(vault_id))
+        .map(|state| state.total)
+}
+
+/// Sum of a recurring vault's validated slices, or `None` for other vaults.
+pub(crate) fn validated_stake(env: &Env, vault_id: u32) -> Result<Option<i128>, Error> {
+    let Ok(state) = load_state(env, vault_id) else {
+        return Ok(None);
+    };
+    let mut validated: i128 = 0;
+    for index in 0..state.schedule.period_count {
+        if period_status(env, vault_id, index) == PeriodStatus::Validated {
+            let slice = payout::tranche(state.total, state.schedule.period_count, index)?;
+            validated = validated
+                .checked_add(slice)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+    }
+    Ok(Some(validated))
+}
+
+fn load_state(env: &Env, vault_id: u32) -> Result<RecurringState, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Recurring(vault_id))
+        .ok_or(Error::InvalidStatus)
+}
+
+fn period_status(env: &Env, vault_id: u32, index: u32) -> PeriodStatus {
+    env.storage()
+        .instance()
+        .get(&DataKey::Period(vault_id, index))
+        .unwrap_or(PeriodStatus::Pending)
+}
+
+/// `[start, end)` of period `index`.
+fn period_window(
+    vault: &ProductivityVault,
+    schedule: &RecurringSchedule,
+    index: u32,
+) -> (u64, u64) {
+    // Cannot overflow: `index < period_count` and creation checked
+    // `start + period_length * period_count`.
+    let start = vault.start_timestamp + schedule.period_length * index as u64;
+    (start, start + schedule.period_length)
+}
+
+/// Common lookup for the per-period entrypoints: the vault must be recurring and Active, and
+/// `index` must be a pending period.
+fn load_pending_period(
+    env: &Env,
+    vault_id: u32,
+    index: u32,
+) -> Result<(ProductivityVault, RecurringState), Error> {
+    let vault = load_vault(env, vault_id)?;
+    let state = load_state(env, vault_id)?;
+    if vault.status != VaultStatus::Active {
+        return Err(Error::AlreadySettled);
+    }
+    if index >= state.schedule.period_count {
+        return Err(Error::InvalidPeriod);
+    }
+    if period_status(env, vault_id, index) != PeriodStatus::Pending {
+        return Err(Error::PeriodAlreadySettled);
+    }
+    Ok((vault, state))
+}
+
+/// Record period `index` as `status`, paying its slice to `recipient` now when given one,
+/// and publish the period event attributed to `party`. Finishes the vault once every period
+/// is settled. Returns the period's slice.
+fn settle_period(
+    env: &Env,
+    vault_id: u32,
+    mut vault: ProductivityVault,
+    mut state: RecurringState,
+    index: u32,
+    status: PeriodStatus,
+    usdc_token: &Address,
+    recipient: Option<&Address>,
+    party: &Address,
+) -> Result<i128, Error> {
+    check_token(&vault, usdc_token)?;
+    let slice = payout::tranche(state.total, state.schedule.period_count, index)?;
+    state.settled_periods += 1;
+    if status == PeriodStatus::Validated {
+        state.validated_periods += 1;
+    }
+    // A slice paid out now withholds the protocol fee a deferred one pays at the final
+    // settlement; a missed slice, like any failure, withholds none.
+    let slice_parts = match recipient {
+        Some(to) => {
+            vault.amount = vault
+                .amount
+                .checked_sub(slice)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let fee_status = match status {
+                PeriodStatus::Validated => VaultStatus::Completed,
+                _ => VaultStatus::Failed,
+            };
+            let parts = payout::breakdown(
+                env,
+                slice,
+                fees::fee_bps(env, fee_status),
+                &vec![env, (to.clone(), 1)],
+            )?;
+            fees::accrue(env, usdc_token, parts.fee)?;
+            Some(parts)
+        }
+        None => None,
+    };
+    env.storage()
+        .instance()
+        .set(&DataKey::Period(vault_id, index), &status);
+    env.storage()
+        .instance()
+        .set(&DataKey::Recurring(vault_id), &state);
+    env.storage()
+        .instance()
+        .set(&DataKey::Vault(vault_id), &vault);
+
+    // Last period: what is still escrowed is the deferred validated slices, paid to the
+    // success destination. A vault that was never validated has nothing left and fails.
+    let finished = (state.settled_periods == state.schedule.period_count).then(|| {
+        if state.validated_periods > 0 {
+            (VaultStatus::Completed, vault.success_destination.clone())
+        } else {
+            (VaultStatus::Failed, vault.failure_destination.clone())
+        }
+    });
+    let settlement = match finished {
+        Some((final_status, destination)) => Some((
+            settle(
+                env,
+                vault_id,
+                &mut vault,
+                usdc_token,
+                &vec![env, (destination.clone(), 1)],
+                final_status,
+                env.current_contract_address(),
+            )?,
+            final_status,
+            destination,
+        )),
+        None => None,
+    };
+
+    if let Some(parts) = slice_parts {
+        let token_client = token::Client::new(env, usdc_token);
+        for (to, share) in parts.shares.iter() {
+            if share > 0 {
+                token_client.transfer(&env.current_contract_address(), &to, &share);
+            }
+        }
+    }
+    let badge = badge::mint(env, vault_id, &vault);
+
+    match status {
+        PeriodStatus::Missed => events::period_missed(env, vault_id, party, index, slice),
+        _ => events::period_validated(env, vault_id, party, index, slice),
+    }
+    match settlement {
+        Some((parts, VaultStatus::Completed, destination)) => {
+            events::funds_released(env, vault_id, &destination, &parts);
+            events::referral_paid(
+                env,
+                vault_id,
+                fees::referral_reward(env, &vault, parts.fee)?,
+            );
+        }
+        Some((parts, _, destination)) => {
+            events::funds_redirected(env, vault_id, &destination, &parts)
+        }
+        None => {}
+    }
+    streak::publish_update(env, vault_id, &vault);
+    badge::publish(env, vault_id, &vault, badge);
+    Ok(slice)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Create a recurring vault locking `amount` over `schedule.period_count` periods of
+    /// `schedule.period_length` seconds starting at `start_timestamp`.
+    ///
+    /// Same validation as `create_vault` with `end_timestamp` derived from the schedule,
+    /// plus `InvalidPeriod` for a zero period length or a period count outside
+    /// `1..=MAX_PERIODS`, and `InvalidAmount` when `amount` cannot give every period a
+    /// non-zero slice.
+    pub fn create_recurring_vault(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        amount: i128,
+        start_timestamp: u64,
+        schedule: RecurringSchedule,
+        milestone_hash: BytesN<32>,
+        verifier: Option<Address>,
+        success_destination: Address,
+        failure_destination: Address,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+
+        if schedule.period_length == 0
+            || schedule.period_count == 0
+            || schedule.period_count > MAX_PERIODS
+        {
+            return Err(Error::InvalidPeriod);
+        }
+        if amount < schedule.period_count as i128 {
+            return Err(Error::InvalidAmount);
+        }
+        let end_timestamp = schedule
+            .period_length
+            .checked_mul(schedule.period_count as u64)
+            .and_then(|length| start_timestamp.checked_add(length))
+            .ok_or(Error::InvalidTimestamps)?;
+
+        let vault = ProductivityVault {
+            creator,
+            amount,
+            token: usdc_token.clone(),
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier,
+            success_destination,
+            failure_destination,
+            status: VaultStatus::Active,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_new_vault(&env, &usdc_token, &vault)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        let state = RecurringState {
+            schedule,
+            total: amount,
+            settled_periods: 0,
+            validated_periods: 0,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Recurring(vault_id), &state);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &vault.creator,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::vault_created(&env, vault_id, &vault);
+        Ok(vault_id)
+    }
+
+    /// Check in for period `period_index`. Only the validator (verifier, or creator when there
+    /// is none) may call it, and only inside the period's window: `InvalidTimestamp` before
+    /// it opens, `MilestoneExpired` once it has closed.
+    ///
+    /// Returns the period's slice. It is paid to the success destination now if the schedule
+    /// releases immediately, otherwise when the last period settles.
+    pub fn validate_period(
+        env: Env,
+        vault_id: u32,
+        period_index: u32,
+        usdc_token: Address,
+    ) -> Result<i128, Error> {
+        let (vault, state) = load_pending_period(&env, vault_id, period_index)?;
+        vault.validator().require_auth();
+
+        let (opens, closes) = period_window(&vault, &state.schedule, period_index);
+        let now = env.ledger().timestamp();
+        if now < opens {
+            return Err(Error::InvalidTimestamp);
+        }
+        if now >= closes {
+            return Err(Error::MilestoneExpired);
+        }
+
+        reputation::record_response(&env, vault_id, &vault);
+        let validator = vault.validator().clone();
+        let release_to = state
+            .schedule
+            .release_immediately
+            .then(|| vault.success_destination.clone());
+        settle_period(
+            &env,
+            vault_id,
+            vault,
+            state,
+            period_index,
+            PeriodStatus::Validated,
+            &usdc_token,
+            release_to.as_ref(),
+            &validator,
+        )
+    }
+
+    /// Keeper call: send the slice of a period whose window closed without a check-in to the
+    /// failure destination. Permissionless; fails with `InvalidTimestamp` while the window
+    /// is still open. Returns the redirected slice.
+    pub fn redirect_missed_period(
+        env: Env,
+        vault_id: u32,
+        period_index: u32,
+        usdc_token: Address,
+    ) -> Result<i128, Error> {
+        let (vault, state) = load_pending_period(&env, vault_id, period_index)?;
+
+        let (_, closes) = period_window(&vault, &state.schedule, period_index);
+        if env.ledger().timestamp() < closes {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let recipient = vault.failure_destination.clone();
+        settle_period(
+            &env,
+            vault_id,
+            vault,
+            state,
+            period_index,
+            PeriodStatus::Missed,
+            &usdc_token,
+            Some(&recipient),
+            &recipient,
+        )
+    }
+
+    /// Schedule and progress of a recurring vault, or `None` for single-milestone vaults.
+    pub fn get_recurring_state(env: Env, vault_id: u32) -> Option<RecurringState> {
+        env.storage().instance().get(&DataKey::Recurring(vault_id))
+    }
+
+    /// Status of one period of a recurring vault.
+    pub fn get_period_status(env: Env, vault_id: u32, period_index: u32) -> PeriodStatus {
+        period_status(&env, vault_id, period_index)
+    }
+}
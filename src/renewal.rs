@@ -0,0 +1,133 @@
+//! Auto-renewing vaults: a completed vault rolls its escrow into the next period.
+//!
+//! A vault created with `VaultOptions::auto_renew` keeps a renewal period. When
+//! `release_funds` completes it, nothing is paid out: the vault becomes `Completed` as usual
+//! (streak, reputation, badge) and a successor opens with the same terms, its window shifted
+//! by one period and the same escrow carried over. The carry-over is pure bookkeeping, with no
+//! token or adapter call: a stake in a yield adapter stays deposited and moves to the
+//! successor, as does the settlement callback. No protocol fee is taken since nothing leaves
+//! escrow.
+//!
+//! The creator can turn renewal off (or back on) with `set_auto_renew`; it takes effect at
+//! the next settlement. A failure or cancellation ends the chain with the normal payout, and
+//! so does a release graded below full marks.
+
+use soroban_sdk::{contractimpl, Env};
+
+use crate::{
+    badge, burn, callback, claim, events, grading, hashing, load_vault, oracle, store_new_vault,
+    streak, transition, witness, yield_adapter, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+fn period(env: &Env, vault_id: u32) -> Option<u64> {
+    env.storage()
+        .instance()
+        .get(&DataKey::RenewalPeriod(vault_id))
+}
+
+/// Record the renewal period of a new `auto_renew` vault. It must be at least the vault's
+/// duration so that successive windows never overlap.
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    period: u64,
+) -> Result<(), Error> {
+    if !vault.auto_renew {
+        return Ok(());
+    }
+    if period < vault.end_timestamp - vault.start_timestamp {
+        return Err(Error::InvalidPeriod);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::RenewalPeriod(vault_id), &period);
+    Ok(())
+}
+
+/// Whether releasing `vault` renews it instead of paying out.
+pub(crate) fn renews(env: &Env, vault_id: u32, vault: &ProductivityVault) -> bool {
+    vault.auto_renew && grading::grade(env, vault_id).is_none()
+}
+
+/// Complete `vault` and open its successor with the escrow carried over. Called by
+/// `release_funds` once its checks have passed. Returns the successor's id.
+pub(crate) fn renew(env: &Env, vault_id: u32, mut vault: ProductivityVault) -> Result<u32, Error> {
+    let period = period(env, vault_id).ok_or(Error::InvalidPeriod)?;
+    let mut successor = vault.clone();
+    successor.start_timestamp = vault
+        .start_timestamp
+        .checked_add(period)
+        .ok_or(Error::InvalidTimestamps)?;
+    successor.end_timestamp = vault
+        .end_timestamp
+        .checked_add(period)
+        .ok_or(Error::InvalidTimestamps)?;
+    successor.milestone_validated = false;
+
+    transition(
+        env,
+        vault_id,
+        &mut vault,
+        VaultStatus::Completed,
+        env.current_contract_address(),
+    )?;
+    let successor_id = store_new_vault(env, &successor)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::RenewalPeriod(successor_id), &period);
+    env.storage()
+        .instance()
+        .set(&DataKey::Successor(vault_id), &successor_id);
+    yield_adapter::carry_over(env, vault_id, successor_id);
+    let notification = callback::notify(env, vault_id, &vault);
+    callback::carry_over(env, vault_id, successor_id);
+    claim::carry_over(env, vault_id, successor_id);
+    hashing::inherit(env, vault_id, successor_id);
+    oracle::carry_over(env, vault_id, successor_id);
+    burn::carry_over(env, vault_id, successor_id);
+    witness::inherit(env, vault_id, successor_id);
+    let badge = badge::mint(env, vault_id, &vault);
+
+    events::vault_renewed(env, vault_id, &vault.creator, successor_id);
+    events::vault_created(env, successor_id, &successor);
+    streak::publish_update(env, vault_id, &vault);
+    badge::publish(env, vault_id, &vault, badge);
+    callback::publish(env, vault_id, &vault, notification);
+    Ok(successor_id)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Turn renewal of an Active vault on or off; applies at its next settlement. Creator
+    /// only. Fails with `VaultNotActive` once settled and `InvalidPeriod` when enabling a
+    /// vault created without `auto_renew`, which has no renewal period.
+    pub fn set_auto_renew(env: Env, vault_id: u32, enabled: bool) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        vault.creator.require_auth();
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        if enabled && period(&env, vault_id).is_none() {
+            return Err(Error::InvalidPeriod);
+        }
+        vault.auto_renew = enabled;
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &vault);
+
+        events::auto_renew_set(&env, vault_id, &vault.creator, enabled);
+        Ok(true)
+    }
+
+    /// Vault a renewed or recommitted vault rolled into (see `recommit`), or `None`.
+    pub fn get_successor(env: Env, vault_id: u32) -> Option<u32> {
+        env.storage().instance().get(&DataKey::Successor(vault_id))
+    }
+
+    /// Renewal period of a vault created with `auto_renew`.
+    pub fn get_renewal_period(env: Env, vault_id: u32) -> Option<u64> {
+        period(&env, vault_id)
+    }
+}
@@ -0,0 +1,191 @@
+//! Per-address reputation for creators and verifiers.
+//!
+//! Settlements feed the records through `transition`: a completed vault adds stake-weighted
+//! points to its creator, a failed one takes them away, and a release that only went through
+//! because the deadline passed without the designated verifier validating counts as a
+//! verifier timeout. A completed recurring vault is weighted by its validated slices only,
+//! and a completion nobody but the creator attested to (a vault without a verifier) counts
+//! for nothing. Separately, a verifier's first response on a vault (a validation, a
+//! period check-in or a change request) earns it points once.
+//!
+//! Weights come from `Config::reputation` so they can be tuned without an upgrade. Stake is
+//! counted in whole `stake_unit`s capped at `max_units`, so dust vaults earn nothing and a
+//! single large vault cannot dominate; vaults staking less than `Config::min_amount` are
+//! ignored altogether. Scores never drop below zero and saturate at `MAX_SCORE`.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::{
+    config, creator_transfer, recurring, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Ceiling for either score.
+pub const MAX_SCORE: u64 = 1_000_000_000;
+
+/// Scoring weights. All point values are per counted stake unit except the verifier ones,
+/// which are flat per event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationWeights {
+    /// Stake that counts as one unit. Must be positive.
+    pub stake_unit: i128,
+    /// Most units a single vault counts for.
+    pub max_units: u32,
+    /// Creator points per unit on completion.
+    pub completion: u32,
+    /// Creator points per unit lost on failure.
+    pub failure: u32,
+    /// Verifier points for its first response on a vault.
+    pub verifier_response: u32,
+    /// Verifier points lost when a vault is released past its deadline without it validating.
+    pub verifier_timeout: u32,
+}
+
+impl Default for ReputationWeights {
+    fn default() -> Self {
+        ReputationWeights {
+            stake_unit: 1_000_000,
+            max_units: 100,
+            completion: 10,
+            failure: 10,
+            verifier_response: 5,
+            verifier_timeout: 20,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reputation {
+    pub creator_score: u64,
+    pub verifier_score: u64,
+    /// Vaults this address created that completed.
+    pub completed: u32,
+    /// Vaults this address created that failed.
+    pub failed: u32,
+    /// Vaults this address responded to as verifier.
+    pub responses: u32,
+    /// Vaults released past their deadline while this address was the silent verifier.
+    pub timeouts: u32,
+}
+
+fn load(env: &Env, address: &Address) -> Reputation {
+    env.storage()
+        .instance()
+        .get(&DataKey::Reputation(address.clone()))
+        .unwrap_or_default()
+}
+
+fn store(env: &Env, address: &Address, reputation: &Reputation) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Reputation(address.clone()), reputation);
+}
+
+/// Stake the vault counts for: the full recurring stake, since paid-out slices are deducted
+/// from `vault.amount`.
+fn stake(env: &Env, vault_id: u32, vault: &ProductivityVault) -> i128 {
+    recurring::total_stake(env, vault_id).unwrap_or(vault.amount)
+}
+
+/// Stake a settlement is weighted by: for a completed recurring vault only the slices that
+/// were validated, otherwise `stake`.
+fn settled_stake(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Result<i128, Error> {
+    match recurring::validated_stake(env, vault_id)? {
+        Some(validated) if vault.status == VaultStatus::Completed => Ok(validated),
+        _ => Ok(stake(env, vault_id, vault)),
+    }
+}
+
+/// Whether `vault` counts toward its creator's standing: it staked at least
+/// `Config::min_amount`, and a completion was attested by a verifier rather than
+/// self-attested by the creator.
+pub(crate) fn counts(env: &Env, vault_id: u32, vault: &ProductivityVault) -> bool {
+    if vault.status == VaultStatus::Completed && vault.verifier.is_none() {
+        return false;
+    }
+    stake(env, vault_id, vault) >= config::load(env).min_amount
+}
+
+/// Points for `weight` per unit, `weight * min(stake / stake_unit, max_units)`.
+fn weighted(weights: &ReputationWeights, stake: i128, weight: u32) -> Result<u64, Error> {
+    let units = stake
+        .checked_div(weights.stake_unit)
+        .ok_or(Error::ArithmeticOverflow)?
+        .clamp(0, weights.max_units as i128) as u64;
+    units
+        .checked_mul(weight as u64)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+fn gain(score: u64, points: u64) -> u64 {
+    score.saturating_add(points).min(MAX_SCORE)
+}
+
+/// Apply `vault`'s new terminal status to its creator's and verifier's records. Called by
+/// `transition`. The creator record is the original creator's, even after a transfer.
+pub(crate) fn record(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Result<(), Error> {
+    if !counts(env, vault_id, vault) {
+        return Ok(());
+    }
+    let weights = config::load(env).reputation;
+    let stake = settled_stake(env, vault_id, vault)?;
+    let original = creator_transfer::original_creator(env, vault_id, vault);
+    let mut creator = load(env, &original);
+    match vault.status {
+        VaultStatus::Completed => {
+            let points = weighted(&weights, stake, weights.completion)?;
+            creator.creator_score = gain(creator.creator_score, points);
+            creator.completed = creator.completed.saturating_add(1);
+        }
+        VaultStatus::Failed => {
+            let points = weighted(&weights, stake, weights.failure)?;
+            creator.creator_score = creator.creator_score.saturating_sub(points);
+            creator.failed = creator.failed.saturating_add(1);
+        }
+        _ => return Ok(()),
+    }
+    store(env, &original, &creator);
+
+    // Recurring vaults are checked in per period and never set `milestone_validated`.
+    let timed_out = vault.status == VaultStatus::Completed
+        && !vault.milestone_validated
+        && !recurring::is_recurring(env, vault_id);
+    if let (true, Some(verifier)) = (timed_out, &vault.verifier) {
+        let mut record = load(env, verifier);
+        record.verifier_score = record
+            .verifier_score
+            .saturating_sub(weights.verifier_timeout as u64);
+        record.timeouts = record.timeouts.saturating_add(1);
+        store(env, verifier, &record);
+    }
+    Ok(())
+}
+
+/// Credit the designated verifier for responding on `vault_id`, at most once per vault.
+/// Creators validating their own vault earn nothing.
+pub(crate) fn record_response(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    let Some(verifier) = &vault.verifier else {
+        return;
+    };
+    let key = DataKey::VerifierResponded(vault_id);
+    if env.storage().instance().has(&key) || !counts(env, vault_id, vault) {
+        return;
+    }
+    env.storage().instance().set(&key, &true);
+
+    let weights = config::load(env).reputation;
+    let mut record = load(env, verifier);
+    record.verifier_score = gain(record.verifier_score, weights.verifier_response as u64);
+    record.responses = record.responses.saturating_add(1);
+    store(env, verifier, &record);
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Reputation of `address`; all zero for addresses with no history.
+    pub fn get_reputation(env: Env, address: Address) -> Reputation {
+        load(&env, &address)
+    }
+}
@@ -0,0 +1,69 @@
+//! Commit-reveal of the milestone description.
+//!
+//! A creator who wants to keep their goal private commits to it at creation:
+//! `milestone_hash` is `hash(description || salt)` for a secret 32-byte salt, where `hash` is
+//! the vault's `HashAlg` (see `hashing`). Revealing later hands the contract the description
+//! and salt; if they hash to the commitment, the plaintext is published in
+//! `milestone_revealed` as a permanent public record, and `hash(description)` is stored so
+//! the vault stays linked to what was revealed.
+//!
+//! The creator may reveal at any time, including after settlement. A vault created with
+//! `VaultOptions::reveal_required` and no verifier is self-attested, so its release waits
+//! for the reveal and observers can judge the claim first.
+
+use soroban_sdk::{contractimpl, Bytes, BytesN, Env};
+
+use crate::hashing::{self, HashAlg};
+use crate::{
+    events, load_vault, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error,
+};
+
+pub(crate) fn is_revealed(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Revealed(vault_id))
+}
+
+/// The commitment to `description` under `salt`: `hash(description || salt)` with `alg`.
+pub fn commitment(env: &Env, alg: HashAlg, description: &Bytes, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = description.clone();
+    preimage.extend_from_array(&salt.to_array());
+    hashing::digest(env, alg, &preimage)
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Creator reveals the milestone `description` behind the vault's `milestone_hash`.
+    ///
+    /// Fails with `RevealMismatch` unless `hash(description || salt)` under the vault's
+    /// `HashAlg` equals the stored hash, and `AlreadyRevealed` on a second reveal.
+    pub fn reveal_milestone(
+        env: Env,
+        vault_id: u32,
+        description: Bytes,
+        salt: BytesN<32>,
+    ) -> Result<bool, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        vault.creator.require_auth();
+
+        if is_revealed(&env, vault_id) {
+            return Err(Error::AlreadyRevealed);
+        }
+        let alg = hashing::alg(&env, vault_id);
+        if commitment(&env, alg, &description, &salt) != vault.milestone_hash {
+            return Err(Error::RevealMismatch);
+        }
+
+        let description_hash = hashing::digest(&env, alg, &description);
+        env.storage()
+            .instance()
+            .set(&DataKey::Revealed(vault_id), &description_hash);
+
+        events::milestone_revealed(&env, vault_id, &vault.creator, &description);
+        Ok(true)
+    }
+
+    /// `hash(description)` of a revealed milestone under the vault's `HashAlg`, or `None`
+    /// while it is still private.
+    pub fn get_revealed_description_hash(env: Env, vault_id: u32) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::Revealed(vault_id))
+    }
+}
@@ -0,0 +1,94 @@
+//! Savings locks: a pure time lock that can only pay the creator back.
+//!
+//! A vault created with `VaultOptions::mode` set to `SavingsLock` has no verifier and both
+//! destinations set to its creator (`InvalidSavingsLock` otherwise, and also with
+//! `allow_graded` or `reveal_required`, which need a milestone). There is nothing to
+//! validate: `validate_milestone` and `redirect_funds` reject it with `InvalidStatus`, and
+//! once `end_timestamp` is reached anyone can `release_funds` it back to the creator.
+//!
+//! Before the deadline `cancel_vault` fails with `SavingsLocked`, unless the admin sets
+//! `Config::early_withdrawal_bps`. Then the creator may withdraw early and that share of the
+//! escrow is withheld as a penalty, which accrues to the protocol next to the fees (see
+//! `fees::withdraw_fees`). Cancelling at or after the deadline costs nothing.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::{
+    config, events, fees, payout, privacy, yield_adapter, DataKey, Error, ProductivityVault,
+};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum VaultMode {
+    /// A milestone commitment settled by validation or the deadline.
+    #[default]
+    Standard = 0,
+    /// Time lock paying only the creator (see `savings`).
+    SavingsLock = 1,
+}
+
+pub(crate) fn is_savings_lock(vault: &ProductivityVault) -> bool {
+    vault.mode == VaultMode::SavingsLock
+}
+
+/// Creation check for a savings lock's fixed terms.
+pub(crate) fn check(vault: &ProductivityVault) -> Result<(), Error> {
+    if is_savings_lock(vault)
+        && (vault.verifier.is_some()
+            || vault.success_destination != vault.creator
+            || vault.failure_destination != vault.creator
+            || vault.allow_graded
+            || vault.reveal_required)
+    {
+        return Err(Error::InvalidSavingsLock);
+    }
+    Ok(())
+}
+
+/// Whether cancelling `vault` now is allowed: always for other vaults, and for savings locks
+/// once the deadline is reached or when early withdrawal is enabled.
+pub(crate) fn check_cancel(env: &Env, vault: &ProductivityVault) -> Result<(), Error> {
+    if is_savings_lock(vault)
+        && !vault.deadline_reached(env.ledger().timestamp())
+        && config::load(env).early_withdrawal_bps == 0
+    {
+        return Err(Error::SavingsLocked);
+    }
+    Ok(())
+}
+
+/// Withhold the early-withdrawal penalty from a savings lock cancelled before its deadline,
+/// deducting it from the escrow and accruing it to the protocol. Returns the penalty, zero
+/// when none applies.
+pub(crate) fn withhold_penalty(
+    env: &Env,
+    vault_id: u32,
+    vault: &mut ProductivityVault,
+    usdc_token: &Address,
+) -> Result<i128, Error> {
+    if !is_savings_lock(vault) || vault.deadline_reached(env.ledger().timestamp()) {
+        return Ok(0);
+    }
+    let penalty = payout::bps_of(vault.amount, config::load(env).early_withdrawal_bps)?;
+    if penalty > 0 {
+        yield_adapter::withdraw(env, vault_id, usdc_token, penalty)?;
+        vault.amount -= penalty;
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &*vault);
+        fees::accrue(env, usdc_token, penalty)?;
+    }
+    Ok(penalty)
+}
+
+/// Publish `early_withdrawal` for a penalty just withheld.
+pub(crate) fn publish(env: &Env, vault_id: u32, vault: &ProductivityVault, penalty: i128) {
+    if penalty > 0 {
+        events::early_withdrawal(
+            env,
+            vault_id,
+            &vault.creator,
+            privacy::amount(vault, penalty),
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! One-time snooze: the creator pushes the deadline back once, for a fee.
+//!
+//! `snooze` moves `end_timestamp` by up to `Config::max_snooze_seconds` (zero, the default,
+//! disables snoozing). It costs `Config::snooze_fee_bps` of the current escrow, which is
+//! deducted from the vault and paid to the failure destination right away, or burned for a
+//! vault that burns on failure (see `burn`), so putting off a commitment always costs
+//! something. A vault can be snoozed once, only before its original deadline and only while
+//! the milestone is still open: not once validated or graded.
+//!
+//! Only a deadline the creator alone committed to can be snoozed. Group and joint vaults,
+//! wagers, bounties, gifts and sponsored vaults have other parties who staked or agreed to
+//! it, and recurring vaults run on a fixed schedule; all of them are rejected. So are savings
+//! locks (see `savings`), whose deadline is the lock itself rather than a milestone's.
+
+use soroban_sdk::{contractimpl, token, Address, Env};
+
+use crate::{
+    bounty, burn, check_terms, check_token, config, events, gift, grading, group, load_vault,
+    payout, privacy, recurring, savings, sponsor, wager, yield_adapter, DataKey, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, VaultStatus,
+};
+
+fn snoozed(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Snoozed(vault_id))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Push the deadline of an Active vault back by `extra_seconds`, paying the snooze fee
+    /// to its failure destination (or burning it). Creator only. Returns the new deadline.
+    ///
+    /// Fails with `AlreadySnoozed` on a second snooze, `InvalidSnooze` for zero or more than
+    /// `Config::max_snooze_seconds`, `MilestoneExpired` at or past the deadline,
+    /// `InvalidStatus` for validated and graded vaults and the kinds listed in the module
+    /// doc, `VaultNotActive` unless Active and `DurationTooLong` when the new window
+    /// exceeds the duration cap.
+    pub fn snooze(
+        env: Env,
+        vault_id: u32,
+        extra_seconds: u64,
+        usdc_token: Address,
+    ) -> Result<u64, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        vault.creator.require_auth();
+        check_token(&vault, &usdc_token)?;
+        if recurring::is_recurring(&env, vault_id)
+            || group::is_group(&env, vault_id)
+            || savings::is_savings_lock(&vault)
+        {
+            return Err(Error::InvalidStatus);
+        }
+        if wager::is_wager(&env, vault_id)
+            || bounty::is_bounty(&env, vault_id)
+            || gift::is_gift(&env, vault_id)
+            || sponsor::funder(&env, vault_id).is_some()
+        {
+            return Err(Error::InvalidStatus);
+        }
+        if vault.status != VaultStatus::Active {
+            return Err(Error::VaultNotActive);
+        }
+        if vault.milestone_validated || grading::grade(&env, vault_id).is_some() {
+            return Err(Error::InvalidStatus);
+        }
+        if snoozed(&env, vault_id) {
+            return Err(Error::AlreadySnoozed);
+        }
+        if vault.deadline_reached(env.ledger().timestamp()) {
+            return Err(Error::MilestoneExpired);
+        }
+        let config = config::load(&env);
+        if extra_seconds == 0 || extra_seconds > config.max_snooze_seconds {
+            return Err(Error::InvalidSnooze);
+        }
+
+        let old_deadline = vault.end_timestamp;
+        vault.end_timestamp = old_deadline
+            .checked_add(extra_seconds)
+            .ok_or(Error::InvalidTimestamps)?;
+        check_terms(&env, &vault)?;
+        let fee = payout::bps_of(vault.amount, config.snooze_fee_bps)?;
+        vault.amount -= fee;
+        env.storage()
+            .instance()
+            .set(&DataKey::Vault(vault_id), &vault);
+        env.storage()
+            .instance()
+            .set(&DataKey::Snoozed(vault_id), &true);
+
+        let recipient = burn::failure_recipient(&env, vault_id, &vault);
+        let mut forfeited = None;
+        if fee > 0 {
+            yield_adapter::withdraw(&env, vault_id, &usdc_token, fee)?;
+            if recipient == env.current_contract_address() {
+                forfeited = Some((fee, burn::burn(&env, &usdc_token, fee)?));
+            } else {
+                token::Client::new(&env, &usdc_token).transfer(
+                    &env.current_contract_address(),
+                    &recipient,
+                    &fee,
+                );
+            }
+        }
+
+        events::deadline_extended(
+            &env,
+            vault_id,
+            &vault.creator,
+            old_deadline,
+            vault.end_timestamp,
+            privacy::amount(&vault, fee),
+        );
+        burn::publish(&env, vault_id, &vault, forfeited);
+        Ok(vault.end_timestamp)
+    }
+
+    /// Whether the vault has used its snooze.
+    pub fn is_snoozed(env: Env, vault_id: u32) -> bool {
+        snoozed(&env, vault_id)
+    }
+}
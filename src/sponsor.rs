@@ -0,0 +1,117 @@
+//! Sponsored vaults: someone other than the creator puts up the stake.
+//!
+//! The funder authorizes and pays; the creator is the one whose milestone and deadlines
+//! apply, so completions count toward the creator's streak and reputation as usual. The
+//! funder's money never becomes the creator's to take back: on failure it goes to the
+//! failure destination, which defaults to the funder, and any refund goes to the funder.
+//!
+//! Cancelling is the funder's call before `start_timestamp`. Once the commitment has started
+//! it needs both the funder and the creator, so neither can pull out alone.
+
+use soroban_sdk::{contractimpl, token, Address, BytesN, Env};
+
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    category, check_amount, check_balance, check_terms, events, store_new_vault, DataKey,
+    DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Funder of a sponsored vault, or `None` for vaults the creator paid for.
+pub(crate) fn funder(env: &Env, vault_id: u32) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Funder(vault_id))
+}
+
+/// Address that put up the stake: the funder, or the creator.
+pub(crate) fn payer(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Address {
+    funder(env, vault_id).unwrap_or_else(|| vault.creator.clone())
+}
+
+/// Require the authorizations `cancel_vault` needs and return the party the cancellation is
+/// attributed to: the creator, or for sponsored vaults the funder (plus the creator once
+/// the vault has started).
+pub(crate) fn authorize_cancel(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Address {
+    match funder(env, vault_id) {
+        Some(funder) => {
+            funder.require_auth();
+            if env.ledger().timestamp() >= vault.start_timestamp {
+                vault.creator.require_auth();
+            }
+            funder
+        }
+        None => {
+            vault.creator.require_auth();
+            vault.creator.clone()
+        }
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Create a vault for `creator` staked by `funder`. Both must authorize.
+    ///
+    /// `failure_destination` defaults to the funder, so a failed commitment returns the
+    /// stake instead of forfeiting it. Validation matches `create_vault`, with the balance
+    /// check applied to the funder. Sponsored vaults take no `VaultOptions`.
+    pub fn create_sponsored_vault(
+        env: Env,
+        usdc_token: Address,
+        funder: Address,
+        creator: Address,
+        amount: i128,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        verifier: Option<Address>,
+        success_destination: Address,
+        failure_destination: Option<Address>,
+    ) -> Result<u32, Error> {
+        funder.require_auth();
+        creator.require_auth();
+
+        let vault = ProductivityVault {
+            creator,
+            amount,
+            token: usdc_token.clone(),
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier,
+            success_destination,
+            failure_destination: failure_destination.unwrap_or_else(|| funder.clone()),
+            status: VaultStatus::Active,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: false,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_amount(amount)?;
+        check_terms(&env, &vault)?;
+        check_balance(&env, &usdc_token, &funder, amount)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Funder(vault_id), &funder);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::vault_created(&env, vault_id, &vault);
+        events::vault_sponsored(&env, vault_id, &funder, amount);
+        Ok(vault_id)
+    }
+
+    /// Funder of a sponsored vault, or `None` if the creator staked it.
+    pub fn get_funder(env: Env, vault_id: u32) -> Option<Address> {
+        funder(&env, vault_id)
+    }
+}
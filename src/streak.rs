@@ -0,0 +1,88 @@
+//! Per-creator streaks of consecutively completed vaults.
+//!
+//! Every terminal transition updates the creator's `StreakInfo`: completion extends the
+//! current streak, failure resets it to zero and cancellation leaves it alone. When
+//! `Config::streak_window` is non-zero, a completion only extends the streak if the vault
+//! started no later than `streak_window` seconds after the previous completion; otherwise it
+//! starts a new streak of one.
+//!
+//! Only vaults that count toward reputation move the streak (see `reputation::counts`):
+//! vaults staking less than `Config::min_amount` are ignored, and so are completions of vaults
+//! without a verifier, which would otherwise let anyone build a streak for free.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::{
+    config, events, reputation, DataKey, DisciplrVault, DisciplrVaultArgs, DisciplrVaultClient,
+    Error, ProductivityVault, VaultStatus,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StreakInfo {
+    /// Consecutive completions since the last failure (or window break).
+    pub current: u32,
+    /// Highest `current` ever reached.
+    pub longest: u32,
+    /// Ledger timestamp of the most recent completion.
+    pub last_completed_at: Option<u64>,
+}
+
+pub(crate) fn load(env: &Env, creator: &Address) -> StreakInfo {
+    env.storage()
+        .instance()
+        .get(&DataKey::Streak(creator.clone()))
+        .unwrap_or_default()
+}
+
+/// Apply `vault`'s new terminal status to its creator's streak. Called by `transition`.
+pub(crate) fn record(env: &Env, vault_id: u32, vault: &ProductivityVault) -> Result<(), Error> {
+    if !reputation::counts(env, vault_id, vault) {
+        return Ok(());
+    }
+    let mut streak = load(env, &vault.creator);
+    match vault.status {
+        VaultStatus::Completed => {
+            let window = config::load(env).streak_window;
+            let continues = match streak.last_completed_at {
+                Some(previous) if window > 0 => {
+                    vault.start_timestamp <= previous.saturating_add(window)
+                }
+                _ => true,
+            };
+            streak.current = if continues {
+                streak
+                    .current
+                    .checked_add(1)
+                    .ok_or(Error::ArithmeticOverflow)?
+            } else {
+                1
+            };
+            streak.longest = streak.longest.max(streak.current);
+            streak.last_completed_at = Some(env.ledger().timestamp());
+        }
+        VaultStatus::Failed => streak.current = 0,
+        _ => return Ok(()),
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Streak(vault.creator.clone()), &streak);
+    Ok(())
+}
+
+/// Publish `streak_updated` for `vault`'s creator if its last transition touched the streak.
+pub(crate) fn publish_update(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    if matches!(vault.status, VaultStatus::Completed | VaultStatus::Failed)
+        && reputation::counts(env, vault_id, vault)
+    {
+        events::streak_updated(env, &vault.creator, &load(env, &vault.creator));
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Streak of `creator`; all zero for addresses that never settled a vault.
+    pub fn get_streak(env: Env, creator: Address) -> StreakInfo {
+        load(&env, &creator)
+    }
+}
@@ -0,0 +1,165 @@
+//! Reusable vault templates.
+//!
+//! A template stores everything about a commitment except its window: token, default stake,
+//! milestone, verifier, destinations and `VaultOptions`. `create_from_template` then opens a
+//! vault from it for a given `[start, end)`, optionally with a different stake.
+//!
+//! Saving only checks what cannot go stale (the stake and destinations). Instantiation goes
+//! through `create_vault` itself, so a vault made from a template is validated by exactly the
+//! rules in force when it is created, not the ones from when the template was saved.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::{
+    check_amount, events, validate_destination, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, VaultOptions,
+};
+
+/// Upper bound on templates per owner, keeping `list_templates` bounded.
+pub const MAX_TEMPLATES_PER_OWNER: u32 = 20;
+
+/// Settings a template fills in for `create_vault`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateParams {
+    pub usdc_token: Address,
+    /// Stake used unless `create_from_template` overrides it.
+    pub amount: i128,
+    pub milestone_hash: BytesN<32>,
+    pub verifier: Option<Address>,
+    pub success_destination: Address,
+    pub failure_destination: Address,
+    pub options: VaultOptions,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultTemplate {
+    /// Creator of every vault made from this template; the only one who may use or delete it.
+    pub owner: Address,
+    pub params: TemplateParams,
+}
+
+fn load(env: &Env, template_id: u32) -> Result<VaultTemplate, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Template(template_id))
+        .ok_or(Error::TemplateNotFound)
+}
+
+fn owner_templates(env: &Env, owner: &Address) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::OwnerTemplates(owner.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Save `params` as a template owned by `creator`. Returns its id.
+    ///
+    /// Fails with `InvalidAmount` or `InvalidDestination` like `create_vault`, and with
+    /// `TooManyTemplates` once `creator` owns `MAX_TEMPLATES_PER_OWNER`.
+    pub fn create_template(
+        env: Env,
+        creator: Address,
+        params: TemplateParams,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+        check_amount(params.amount)?;
+        validate_destination(&env, &params.success_destination)?;
+        validate_destination(&env, &params.failure_destination)?;
+        let mut owned = owner_templates(&env, &creator);
+        if owned.len() >= MAX_TEMPLATES_PER_OWNER {
+            return Err(Error::TooManyTemplates);
+        }
+
+        let template_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TemplateCount)
+            .unwrap_or(0);
+        let next = template_id
+            .checked_add(1)
+            .ok_or(Error::ArithmeticOverflow)?;
+        owned.push_back(template_id);
+        let template = VaultTemplate {
+            owner: creator.clone(),
+            params,
+        };
+        env.storage().instance().set(&DataKey::TemplateCount, &next);
+        env.storage()
+            .instance()
+            .set(&DataKey::Template(template_id), &template);
+        env.storage()
+            .instance()
+            .set(&DataKey::OwnerTemplates(creator.clone()), &owned);
+
+        events::template_created(&env, &creator, template_id);
+        Ok(template_id)
+    }
+
+    /// Create a vault from a template for the window `[start_timestamp, end_timestamp)`,
+    /// staking `amount_override` if given and the template's amount otherwise. Owner only.
+    ///
+    /// Fails with `TemplateNotFound` for unknown or deleted templates, and otherwise exactly
+    /// like `create_vault` with the template's settings.
+    pub fn create_from_template(
+        env: Env,
+        template_id: u32,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        amount_override: Option<i128>,
+    ) -> Result<u32, Error> {
+        let VaultTemplate { owner, params } = load(&env, template_id)?;
+        Self::create_vault(
+            env,
+            params.usdc_token,
+            owner,
+            amount_override.unwrap_or(params.amount),
+            start_timestamp,
+            end_timestamp,
+            params.milestone_hash,
+            params.verifier,
+            params.success_destination,
+            params.failure_destination,
+            params.options,
+        )
+    }
+
+    /// Delete a template. Owner only; vaults already created from it are unaffected.
+    pub fn delete_template(env: Env, template_id: u32) -> Result<(), Error> {
+        let template = load(&env, template_id)?;
+        template.owner.require_auth();
+
+        let mut owned = owner_templates(&env, &template.owner);
+        if let Some(position) = owned.first_index_of(template_id) {
+            owned.remove(position);
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::Template(template_id));
+        env.storage()
+            .instance()
+            .set(&DataKey::OwnerTemplates(template.owner.clone()), &owned);
+
+        events::template_deleted(&env, &template.owner, template_id);
+        Ok(())
+    }
+
+    /// Templates owned by `owner` as `(template_id, template)`, oldest first.
+    pub fn list_templates(env: Env, owner: Address) -> Vec<(u32, VaultTemplate)> {
+        let mut templates = Vec::new(&env);
+        for template_id in owner_templates(&env, &owner).iter() {
+            if let Ok(template) = load(&env, template_id) {
+                templates.push_back((template_id, template));
+            }
+        }
+        templates
+    }
+
+    /// Template `template_id`, or `None` if it does not exist.
+    pub fn get_template(env: Env, template_id: u32) -> Option<VaultTemplate> {
+        load(&env, template_id).ok()
+    }
+}
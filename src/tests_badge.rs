@@ -0,0 +1,196 @@
+//! Best-effort badge minting on completion, against mock badge contracts.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, testutils::Address as _, Address, Env,
+    IntoVal, Symbol, Vec,
+};
+
+use crate::badge::tier;
+use crate::config::Config;
+use crate::events::{BADGE_MINTED, BADGE_MINT_FAILED};
+use crate::testutils::VaultTestContext;
+use crate::VaultStatus;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct MintCall {
+    to: Address,
+    vault_id: u64,
+    tier: u32,
+}
+
+const CALLS: Symbol = symbol_short!("calls");
+
+/// Records every `mint` it receives.
+#[contract]
+struct MockBadge;
+
+#[contractimpl]
+impl MockBadge {
+    pub fn mint(env: Env, to: Address, vault_id: u64, tier: u32) {
+        let mut calls: Vec<MintCall> = env
+            .storage()
+            .instance()
+            .get(&CALLS)
+            .unwrap_or(Vec::new(&env));
+        calls.push_back(MintCall { to, vault_id, tier });
+        env.storage().instance().set(&CALLS, &calls);
+    }
+
+    pub fn calls(env: Env) -> Vec<MintCall> {
+        env.storage()
+            .instance()
+            .get(&CALLS)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+/// Traps on every `mint`. In its own module because two contracts in one module would
+/// clash on the generated `mint` symbols.
+mod trapping {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct TrappingBadge;
+
+    #[contractimpl]
+    impl TrappingBadge {
+        pub fn mint(_env: Env, _to: Address, _vault_id: u64, _tier: u32) {
+            panic!("badge contract is broken");
+        }
+    }
+}
+use trapping::TrappingBadge;
+
+fn use_badge_contract(ctx: &VaultTestContext, badge_contract: &Address) {
+    ctx.client().set_config(&Config {
+        badge_contract: Some(badge_contract.clone()),
+        ..Config::default()
+    });
+}
+
+/// Validate and release a fresh default vault; returns its id.
+fn complete(ctx: &VaultTestContext) -> u32 {
+    ctx.mint(&ctx.creator, ctx.amount);
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+    id
+}
+
+fn last_event_name(ctx: &VaultTestContext) -> Symbol {
+    let events = ctx.vault_events();
+    let (topics, _) = events.get(events.len() - 1).unwrap();
+    topics.get(0).unwrap().into_val(&ctx.env)
+}
+
+#[test]
+fn test_tier_steps_up_with_streak() {
+    assert_eq!(
+        [0, 1, 2, 3, 6, 7, 29, 30, 1_000].map(tier),
+        [0, 1, 1, 2, 2, 3, 3, 4, 4]
+    );
+}
+
+#[test]
+fn test_completion_mints_badge_for_creator() {
+    let ctx = VaultTestContext::new();
+    let badge = ctx.env.register(MockBadge, ());
+    use_badge_contract(&ctx, &badge);
+    let badge_client = MockBadgeClient::new(&ctx.env, &badge);
+
+    let first = complete(&ctx);
+    let (topics, data) = ctx.vault_events().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, BADGE_MINTED),
+            first,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let minted_tier: u32 = data.into_val(&ctx.env);
+    assert_eq!(minted_tier, 1);
+
+    complete(&ctx);
+    let third = complete(&ctx);
+    let calls = badge_client.calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(
+        calls.last().unwrap(),
+        MintCall {
+            to: ctx.creator.clone(),
+            vault_id: third as u64,
+            tier: 2,
+        }
+    );
+}
+
+#[test]
+fn test_failed_vault_mints_nothing() {
+    let ctx = VaultTestContext::new();
+    let badge = ctx.env.register(MockBadge, ());
+    use_badge_contract(&ctx, &badge);
+
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+    assert!(MockBadgeClient::new(&ctx.env, &badge).calls().is_empty());
+}
+
+#[test]
+fn test_trapping_badge_contract_does_not_block_release() {
+    let ctx = VaultTestContext::new();
+    let badge = ctx.env.register(TrappingBadge, ());
+    use_badge_contract(&ctx, &badge);
+
+    let id = complete(&ctx);
+    assert_eq!(
+        last_event_name(&ctx),
+        Symbol::new(&ctx.env, BADGE_MINT_FAILED)
+    );
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+}
+
+#[test]
+fn test_undeployed_badge_contract_does_not_block_release() {
+    let ctx = VaultTestContext::new();
+    let nowhere = Address::generate(&ctx.env);
+    use_badge_contract(&ctx, &nowhere);
+
+    complete(&ctx);
+    assert_eq!(
+        last_event_name(&ctx),
+        Symbol::new(&ctx.env, BADGE_MINT_FAILED)
+    );
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+}
+
+#[test]
+fn test_no_badge_contract_emits_no_badge_event() {
+    let ctx = VaultTestContext::new();
+    complete(&ctx);
+    let name = last_event_name(&ctx);
+    assert_ne!(name, Symbol::new(&ctx.env, BADGE_MINTED));
+    assert_ne!(name, Symbol::new(&ctx.env, BADGE_MINT_FAILED));
+}
+
+#[test]
+fn test_self_attested_completion_mints_nothing() {
+    let ctx = VaultTestContext::new();
+    let badge = ctx.env.register(MockBadge, ());
+    use_badge_contract(&ctx, &badge);
+
+    ctx.advance_to_start();
+    let id = ctx.create_vault_no_verifier();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+    assert!(MockBadgeClient::new(&ctx.env, &badge).calls().is_empty());
+}
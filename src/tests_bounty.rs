@@ -0,0 +1,245 @@
+//! Bounty vaults: preimage claims, commit-reveal ordering and expiry.
+
+use soroban_sdk::testutils::{storage::Temporary as _, Address as _, Ledger as _};
+use soroban_sdk::{Address, Bytes, BytesN, IntoVal, Symbol, TryIntoVal};
+
+use crate::bounty::{claim_commitment, Bounty, COMMITMENT_TTL};
+use crate::events::{BOUNTY_CLAIMED, FUNDS_RELEASED};
+use crate::hashing::HashAlg;
+use crate::testutils::VaultTestContext;
+use crate::{DataKey, Error, VaultStatus};
+
+fn preimage(ctx: &VaultTestContext) -> Bytes {
+    Bytes::from_slice(&ctx.env, b"the answer is 42")
+}
+
+fn create(ctx: &VaultTestContext, commit_reveal: bool) -> u32 {
+    let hash: BytesN<32> = ctx.env.crypto().sha256(&preimage(ctx)).into();
+    ctx.client().create_bounty(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &hash,
+        &commit_reveal,
+        &HashAlg::Sha256,
+    )
+}
+
+fn next_ledger(ctx: &VaultTestContext) {
+    let sequence = ctx.env.ledger().sequence();
+    ctx.env.ledger().set_sequence_number(sequence + 1);
+}
+
+#[test]
+fn test_anyone_with_the_preimage_claims_the_pot() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let id = create(&ctx, false);
+    let solver = Address::generate(env);
+
+    ctx.advance_to_start();
+    assert!(client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token));
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, BOUNTY_CLAIMED), id, solver.clone()).into_val(env)
+    );
+    let revealed: Bytes = data.try_into_val(env).unwrap();
+    assert_eq!(revealed, preimage(&ctx));
+    assert_eq!(
+        events.get(1).unwrap().0,
+        (Symbol::new(env, FUNDS_RELEASED), id, solver.clone()).into_val(env)
+    );
+    // Nobody verified the poster's work, so the streak does not move.
+    assert_eq!(events.len(), 2);
+    assert_eq!(client.get_streak(&ctx.creator).current, 0);
+
+    ctx.assert_balances(&[(&solver, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+    assert_eq!(
+        client.try_claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_wrong_preimage_is_rejected() {
+    let ctx = VaultTestContext::new();
+    let id = create(&ctx, false);
+    let solver = Address::generate(&ctx.env);
+
+    let guess = Bytes::from_slice(&ctx.env, b"the answer is 41");
+    assert_eq!(
+        ctx.client()
+            .try_claim_bounty(&id, &solver, &guess, &ctx.usdc_token),
+        Err(Ok(Error::InvalidPreimage))
+    );
+    ctx.assert_balances(&[(&solver, 0), (&ctx.contract_id, ctx.amount)]);
+}
+
+#[test]
+fn test_claims_close_at_the_deadline() {
+    let ctx = VaultTestContext::new();
+    let id = create(&ctx, false);
+    let solver = Address::generate(&ctx.env);
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        ctx.client()
+            .try_claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token),
+        Err(Ok(Error::MilestoneExpired))
+    );
+}
+
+#[test]
+fn test_commit_reveal_claim_needs_an_earlier_commitment() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+    let solver = Address::generate(&ctx.env);
+
+    assert_eq!(
+        client.try_claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token),
+        Err(Ok(Error::ClaimNotCommitted))
+    );
+
+    let commitment = claim_commitment(&ctx.env, HashAlg::Sha256, &preimage(&ctx), &solver);
+    client.commit_bounty_claim(&id, &solver, &commitment);
+    assert_eq!(
+        client.try_claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token),
+        Err(Ok(Error::ClaimNotCommitted))
+    );
+
+    next_ledger(&ctx);
+    client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token);
+    ctx.assert_balances(&[(&solver, ctx.amount)]);
+}
+
+#[test]
+fn test_commitments_are_temporary_and_consumed_by_the_claim() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+    let solver = Address::generate(&ctx.env);
+    let key = DataKey::BountyCommitment(id, solver.clone());
+
+    let commitment = claim_commitment(&ctx.env, HashAlg::Sha256, &preimage(&ctx), &solver);
+    client.commit_bounty_claim(&id, &solver, &commitment);
+    ctx.env.as_contract(&ctx.contract_id, || {
+        assert!(!ctx.env.storage().instance().has(&key));
+        assert_eq!(ctx.env.storage().temporary().get_ttl(&key), COMMITMENT_TTL);
+    });
+
+    next_ledger(&ctx);
+    client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token);
+    ctx.env.as_contract(&ctx.contract_id, || {
+        assert!(!ctx.env.storage().temporary().has(&key));
+    });
+}
+
+#[test]
+fn test_copied_commitment_does_not_let_a_front_runner_claim() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+    let solver = Address::generate(&ctx.env);
+    let copycat = Address::generate(&ctx.env);
+
+    let commitment = claim_commitment(&ctx.env, HashAlg::Sha256, &preimage(&ctx), &solver);
+    client.commit_bounty_claim(&id, &solver, &commitment);
+    client.commit_bounty_claim(&id, &copycat, &commitment);
+    next_ledger(&ctx);
+
+    assert_eq!(
+        client.try_claim_bounty(&id, &copycat, &preimage(&ctx), &ctx.usdc_token),
+        Err(Ok(Error::InvalidPreimage))
+    );
+    client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token);
+    ctx.assert_balances(&[(&solver, ctx.amount), (&copycat, 0)]);
+}
+
+#[test]
+fn test_commitments_are_only_for_commit_reveal_bounties() {
+    let ctx = VaultTestContext::new();
+    let id = create(&ctx, false);
+    let solver = Address::generate(&ctx.env);
+
+    let commitment = claim_commitment(&ctx.env, HashAlg::Sha256, &preimage(&ctx), &solver);
+    assert_eq!(
+        ctx.client()
+            .try_commit_bounty_claim(&id, &solver, &commitment),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        ctx.client().get_bounty(&id),
+        Some(Bounty {
+            commit_reveal: false
+        })
+    );
+}
+
+#[test]
+fn test_unclaimed_bounty_expires_back_to_the_creator() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+
+    ctx.advance_to(ctx.end_timestamp - 1);
+    assert_eq!(
+        client.try_expire_bounty(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    ctx.advance_to_deadline();
+    client.expire_bounty(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+    assert_eq!(
+        client.try_expire_bounty(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_creator_cannot_settle_a_bounty_early() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+
+    ctx.advance_to_start();
+    for result in [
+        client.try_validate_milestone(&id).map(|_| ()),
+        client.try_cancel_vault(&id, &ctx.usdc_token).map(|_| ()),
+    ] {
+        assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+    }
+    ctx.advance_to_deadline();
+    for result in [
+        client.try_release_funds(&id, &ctx.usdc_token).map(|_| ()),
+        client.try_redirect_funds(&id, &ctx.usdc_token).map(|_| ()),
+    ] {
+        assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+    }
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount)]);
+}
+
+#[test]
+fn test_expire_rejects_other_vaults() {
+    let ctx = VaultTestContext::new();
+    let id = ctx.create_default_vault();
+    ctx.advance_to_deadline();
+    assert_eq!(
+        ctx.client().try_expire_bounty(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(ctx.client().get_bounty(&id), None);
+}
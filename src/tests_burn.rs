@@ -0,0 +1,158 @@
+//! Burning the stake of failed vaults, including tokens that refuse to burn.
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, IntoVal, Symbol};
+
+use crate::events::{FUNDS_REDIRECTED, VAULT_FORFEITED};
+use crate::savings::VaultMode;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+#[contracttype]
+enum Key {
+    Balance(Address),
+}
+
+/// Token with balances and transfers but no `burn`, like an asset whose issuer forbids it.
+#[contract]
+struct UnburnableToken;
+
+#[contractimpl]
+impl UnburnableToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .instance()
+            .set(&Key::Balance(to), &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&Key::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        Self::mint(env.clone(), from, -amount);
+        Self::mint(env, to, amount);
+    }
+}
+
+fn burn_options() -> VaultOptions {
+    VaultOptions {
+        burn_on_failure: true,
+        ..VaultOptions::default()
+    }
+}
+
+fn create_burn_vault(ctx: &VaultTestContext, token: &Address) -> u32 {
+    ctx.client().create_vault(
+        token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &burn_options(),
+    )
+}
+
+fn assert_forfeit_events(ctx: &VaultTestContext, id: u32, burned: bool) {
+    let events = ctx.vault_events();
+    let (topics, _) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, FUNDS_REDIRECTED),
+            id,
+            ctx.contract_id.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let (topics, data) = events.get(1).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, VAULT_FORFEITED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let data: (i128, bool) = data.into_val(&ctx.env);
+    assert_eq!(data, (ctx.amount, burned));
+}
+
+#[test]
+fn test_failed_vault_burns_its_stake() {
+    let ctx = VaultTestContext::new();
+    let id = create_burn_vault(&ctx, &ctx.usdc_token);
+    ctx.advance_to_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+
+    assert_forfeit_events(&ctx, id, true);
+    ctx.assert_balances(&[
+        (&ctx.contract_id, 0),
+        (&ctx.failure_dest, 0),
+        (&ctx.creator, 0),
+    ]);
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), 0);
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().status,
+        VaultStatus::Failed
+    );
+}
+
+#[test]
+fn test_unburnable_token_falls_back_to_the_treasury() {
+    let ctx = VaultTestContext::new();
+    let token = ctx.env.register(UnburnableToken, ());
+    let token_client = UnburnableTokenClient::new(&ctx.env, &token);
+    token_client.mint(&ctx.creator, &ctx.amount);
+    let id = create_burn_vault(&ctx, &token);
+    ctx.advance_to_deadline();
+    ctx.client().redirect_funds(&id, &token);
+
+    assert_forfeit_events(&ctx, id, false);
+    assert_eq!(token_client.balance(&ctx.contract_id), ctx.amount);
+    assert_eq!(token_client.balance(&ctx.failure_dest), 0);
+    assert_eq!(ctx.client().get_accrued_fees(&token), ctx.amount);
+}
+
+#[test]
+fn test_completion_pays_out_as_usual() {
+    let ctx = VaultTestContext::new();
+    let id = create_burn_vault(&ctx, &ctx.usdc_token);
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[
+        (&ctx.success_dest, ctx.amount),
+        (&ctx.failure_dest, 0),
+        (&ctx.contract_id, 0),
+    ]);
+}
+
+#[test]
+fn test_savings_locks_cannot_burn() {
+    let ctx = VaultTestContext::new();
+    ctx.advance_to_start();
+    let result = ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.creator,
+        &ctx.creator,
+        &VaultOptions {
+            mode: VaultMode::SavingsLock,
+            ..burn_options()
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSavingsLock)));
+}
@@ -0,0 +1,171 @@
+//! Best-effort settlement callbacks, against mock listener contracts.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, testutils::Address as _, Address, Env,
+    IntoVal, Symbol, Vec,
+};
+
+use crate::events::CALLBACK_FAILED;
+use crate::testutils::VaultTestContext;
+use crate::{VaultOptions, VaultStatus};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Settled {
+    vault_id: u64,
+    status: VaultStatus,
+    amount: i128,
+}
+
+const CALLS: Symbol = symbol_short!("calls");
+
+/// Records every notification it receives.
+#[contract]
+struct MockListener;
+
+#[contractimpl]
+impl MockListener {
+    pub fn on_vault_settled(env: Env, vault_id: u64, status: VaultStatus, amount: i128) {
+        let mut calls = Self::calls(env.clone());
+        calls.push_back(Settled {
+            vault_id,
+            status,
+            amount,
+        });
+        env.storage().instance().set(&CALLS, &calls);
+    }
+
+    pub fn calls(env: Env) -> Vec<Settled> {
+        env.storage()
+            .instance()
+            .get(&CALLS)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+/// Traps on every notification. In its own module because two contracts in one module
+/// would clash on the generated `on_vault_settled` symbols.
+mod trapping {
+    use soroban_sdk::{contract, contractimpl, Env};
+
+    use crate::VaultStatus;
+
+    #[contract]
+    pub struct TrappingListener;
+
+    #[contractimpl]
+    impl TrappingListener {
+        pub fn on_vault_settled(_env: Env, _vault_id: u64, _status: VaultStatus, _amount: i128) {
+            panic!("listener is broken");
+        }
+    }
+}
+use trapping::TrappingListener;
+
+fn create_with_callback(ctx: &VaultTestContext, callback: &Address) -> u32 {
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            callback: Some(callback.clone()),
+            ..VaultOptions::default()
+        },
+    )
+}
+
+#[test]
+fn test_listener_is_notified_after_release() {
+    let ctx = VaultTestContext::new();
+    let listener = ctx.env.register(MockListener, ());
+    let id = create_with_callback(&ctx, &listener);
+    assert_eq!(ctx.client().get_callback(&id), Some(listener.clone()));
+
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+    assert_eq!(
+        MockListenerClient::new(&ctx.env, &listener).calls(),
+        soroban_sdk::vec![
+            &ctx.env,
+            Settled {
+                vault_id: id as u64,
+                status: VaultStatus::Completed,
+                amount: ctx.amount,
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_listener_is_notified_of_redirects_and_cancellations() {
+    let ctx = VaultTestContext::new();
+    let listener = ctx.env.register(MockListener, ());
+    let cancelled = create_with_callback(&ctx, &listener);
+    ctx.client().cancel_vault(&cancelled, &ctx.usdc_token);
+    let redirected = create_with_callback(&ctx, &listener);
+    ctx.advance_to_deadline();
+    ctx.client().redirect_funds(&redirected, &ctx.usdc_token);
+
+    let calls = MockListenerClient::new(&ctx.env, &listener).calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls.get(0).unwrap().status, VaultStatus::Cancelled);
+    assert_eq!(calls.get(1).unwrap().status, VaultStatus::Failed);
+}
+
+#[test]
+fn test_trapping_listener_never_blocks_the_payout() {
+    let ctx = VaultTestContext::new();
+    let listener = ctx.env.register(TrappingListener, ());
+    let id = create_with_callback(&ctx, &listener);
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(events.len() - 1).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&ctx.env, CALLBACK_FAILED), id, listener).into_val(&ctx.env)
+    );
+    let status: VaultStatus = data.into_val(&ctx.env);
+    assert_eq!(status, VaultStatus::Completed);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_missing_listener_contract_is_reported_too() {
+    let ctx = VaultTestContext::new();
+    let id = create_with_callback(&ctx, &Address::generate(&ctx.env));
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    let name: Symbol = events
+        .get(events.len() - 1)
+        .unwrap()
+        .0
+        .get(0)
+        .unwrap()
+        .into_val(&ctx.env);
+    assert_eq!(name, Symbol::new(&ctx.env, CALLBACK_FAILED));
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount)]);
+}
+
+#[test]
+fn test_admin_can_clear_a_callback() {
+    let ctx = VaultTestContext::new();
+    let listener = ctx.env.register(MockListener, ());
+    let id = create_with_callback(&ctx, &listener);
+    ctx.client().clear_callback(&id);
+    assert_eq!(ctx.env.auths()[0].0, ctx.admin);
+    assert_eq!(ctx.client().get_callback(&id), None);
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    assert!(MockListenerClient::new(&ctx.env, &listener)
+        .calls()
+        .is_empty());
+}
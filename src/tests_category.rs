@@ -0,0 +1,184 @@
+//! Vault categories: the admin list, creation checks and per-category statistics.
+
+extern crate std;
+
+use soroban_sdk::{symbol_short, vec, IntoVal, Symbol};
+
+use crate::category::{CategoryStats, DEFAULT_CATEGORY, MAX_CATEGORIES};
+use crate::events::VAULT_CREATED;
+use crate::recurring::RecurringSchedule;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+const FITNESS: Symbol = symbol_short!("fitness");
+const WRITING: Symbol = symbol_short!("writing");
+
+fn setup() -> VaultTestContext {
+    let ctx = VaultTestContext::new();
+    ctx.client().add_category(&FITNESS);
+    ctx.client().add_category(&WRITING);
+    ctx
+}
+
+fn try_create(ctx: &VaultTestContext, category: Option<Symbol>) -> Result<u32, Error> {
+    ctx.mint(&ctx.creator, ctx.amount);
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                category,
+                ..VaultOptions::default()
+            },
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext, category: Symbol) -> u32 {
+    try_create(ctx, Some(category)).unwrap()
+}
+
+#[test]
+fn test_category_is_stored_and_in_the_created_topics() {
+    let ctx = setup();
+    let id = create(&ctx, FITNESS);
+    let (topics, _) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, VAULT_CREATED),
+            id,
+            ctx.creator.clone(),
+            FITNESS
+        )
+            .into_val(&ctx.env)
+    );
+    assert_eq!(ctx.client().get_vault_state(&id).unwrap().category, FITNESS);
+}
+
+#[test]
+fn test_uncategorized_vaults_use_the_default() {
+    let ctx = setup();
+    let id = try_create(&ctx, None).unwrap();
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().category,
+        DEFAULT_CATEGORY
+    );
+    assert_eq!(try_create(&ctx, Some(DEFAULT_CATEGORY)), Ok(id + 1));
+
+    ctx.client().create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &RecurringSchedule {
+            period_length: 100,
+            period_count: 2,
+            release_immediately: false,
+        },
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+    let stats = ctx.client().get_category_stats(&DEFAULT_CATEGORY);
+    assert_eq!((stats.created, stats.total_staked), (3, 3 * ctx.amount));
+}
+
+#[test]
+fn test_stats_across_mixed_category_lifecycles() {
+    let ctx = setup();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let fit_done = create(&ctx, FITNESS);
+    let fit_failed = create(&ctx, FITNESS);
+    let fit_cancelled = create(&ctx, FITNESS);
+    let write_done = create(&ctx, WRITING);
+    let write_failed = create(&ctx, WRITING);
+
+    for id in [fit_done, write_done] {
+        client.validate_milestone(&id);
+        client.release_funds(&id, &ctx.usdc_token);
+    }
+    client.cancel_vault(&fit_cancelled, &ctx.usdc_token);
+    ctx.advance_to_deadline();
+    for id in [fit_failed, write_failed] {
+        client.redirect_funds(&id, &ctx.usdc_token);
+    }
+
+    assert_eq!(
+        client.get_category_stats(&FITNESS),
+        CategoryStats {
+            created: 3,
+            completed: 1,
+            failed: 1,
+            total_staked: 3 * ctx.amount,
+        }
+    );
+    assert_eq!(
+        client.get_category_stats(&WRITING),
+        CategoryStats {
+            created: 2,
+            completed: 1,
+            failed: 1,
+            total_staked: 2 * ctx.amount,
+        }
+    );
+    assert_eq!(
+        client.get_category_stats(&symbol_short!("reading")),
+        CategoryStats::default()
+    );
+}
+
+#[test]
+fn test_unlisted_categories_are_rejected() {
+    let ctx = setup();
+    assert_eq!(
+        try_create(&ctx, Some(symbol_short!("reading"))),
+        Err(Error::UnknownCategory)
+    );
+
+    let id = create(&ctx, WRITING);
+    ctx.client().remove_category(&WRITING);
+    assert_eq!(try_create(&ctx, Some(WRITING)), Err(Error::UnknownCategory));
+    // Existing vaults and their statistics are untouched.
+    assert_eq!(ctx.client().get_vault_state(&id).unwrap().category, WRITING);
+    assert_eq!(ctx.client().get_category_stats(&WRITING).created, 1);
+}
+
+#[test]
+fn test_admin_list_management() {
+    let ctx = setup();
+    let client = ctx.client();
+    assert_eq!(client.list_categories(), vec![&ctx.env, FITNESS, WRITING]);
+    assert_eq!(
+        client.try_add_category(&FITNESS),
+        Err(Ok(Error::CategoryAlreadyListed))
+    );
+    assert_eq!(
+        client.try_add_category(&DEFAULT_CATEGORY),
+        Err(Ok(Error::CategoryAlreadyListed))
+    );
+    assert_eq!(
+        client.try_remove_category(&DEFAULT_CATEGORY),
+        Err(Ok(Error::UnknownCategory))
+    );
+
+    client.remove_category(&FITNESS);
+    assert_eq!(client.list_categories(), vec![&ctx.env, WRITING]);
+    for i in 1..MAX_CATEGORIES {
+        client.add_category(&Symbol::new(&ctx.env, &std::format!("c{i}")));
+    }
+    assert_eq!(
+        client.try_add_category(&symbol_short!("overflow")),
+        Err(Ok(Error::TooManyCategories))
+    );
+}
@@ -0,0 +1,164 @@
+//! Charity registry and registered failure destinations.
+
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Vec};
+
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+fn registered(ctx: &VaultTestContext, count: u32) -> Vec<Address> {
+    let mut charities = Vec::new(&ctx.env);
+    for i in 0..count {
+        let charity = Address::generate(&ctx.env);
+        let index = ctx
+            .client()
+            .add_charity(&charity, &BytesN::from_array(&ctx.env, &[i as u8; 32]));
+        assert_eq!(index, i);
+        charities.push_back(charity);
+    }
+    charities
+}
+
+fn require_charity() -> VaultOptions {
+    VaultOptions {
+        require_registered_charity: true,
+        ..VaultOptions::default()
+    }
+}
+
+fn try_create(
+    ctx: &VaultTestContext,
+    failure_destination: &Address,
+    options: &VaultOptions,
+) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &None,
+            &ctx.success_dest,
+            failure_destination,
+            options,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+#[test]
+fn test_registry_lists_pages_in_index_order() {
+    let ctx = VaultTestContext::new();
+    let charities = registered(&ctx, 4);
+    let client = ctx.client();
+
+    client.remove_charity(&charities.get(1).unwrap());
+    let page = client.list_charities(&0, &3);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().0, 0);
+    assert_eq!(page.get(1).unwrap().0, 2);
+    assert_eq!(page.get(1).unwrap().1.address, charities.get(2).unwrap());
+
+    assert_eq!(client.list_charities(&3, &10).len(), 1);
+    assert!(client.list_charities(&4, &10).is_empty());
+    assert!(client.list_charities(&u32::MAX, &u32::MAX).is_empty());
+    assert_eq!(client.get_charity(&1), None);
+}
+
+#[test]
+fn test_registry_rejects_duplicates_and_unknown_removals() {
+    let ctx = VaultTestContext::new();
+    let charities = registered(&ctx, 1);
+    let client = ctx.client();
+    let hash = BytesN::from_array(&ctx.env, &[9u8; 32]);
+
+    assert_eq!(
+        client.try_add_charity(&charities.get(0).unwrap(), &hash),
+        Err(Ok(Error::CharityAlreadyRegistered))
+    );
+    assert_eq!(
+        client.try_remove_charity(&Address::generate(&ctx.env)),
+        Err(Ok(Error::CharityNotFound))
+    );
+
+    // A removed charity can be registered again, under a new index.
+    client.remove_charity(&charities.get(0).unwrap());
+    assert_eq!(client.add_charity(&charities.get(0).unwrap(), &hash), 1);
+}
+
+#[test]
+fn test_registry_is_admin_only() {
+    let ctx = VaultTestContext::new();
+    let hash = BytesN::from_array(&ctx.env, &[0u8; 32]);
+    ctx.client()
+        .add_charity(&Address::generate(&ctx.env), &hash);
+    assert_eq!(ctx.env.auths()[0].0, ctx.admin);
+}
+
+#[test]
+fn test_required_registration_is_enforced_at_creation() {
+    let ctx = VaultTestContext::new();
+    let charities = registered(&ctx, 1);
+
+    assert_eq!(
+        try_create(&ctx, &ctx.failure_dest, &require_charity()),
+        Err(Error::UnregisteredDestination)
+    );
+    assert!(try_create(&ctx, &ctx.failure_dest, &VaultOptions::default()).is_ok());
+    ctx.mint(&ctx.creator, ctx.amount);
+    assert!(try_create(&ctx, &charities.get(0).unwrap(), &require_charity()).is_ok());
+}
+
+#[test]
+fn test_removed_charity_still_receives_existing_failures() {
+    let ctx = VaultTestContext::new();
+    let charity = registered(&ctx, 1).get(0).unwrap();
+    let id = try_create(&ctx, &charity, &require_charity()).unwrap();
+
+    ctx.client().remove_charity(&charity);
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&charity, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_create_with_charity_resolves_the_destination() {
+    let ctx = VaultTestContext::new();
+    let charities = registered(&ctx, 2);
+    let client = ctx.client();
+
+    let id = client.create_vault_with_charity(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &1,
+        &require_charity(),
+    );
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().failure_destination,
+        charities.get(1).unwrap()
+    );
+
+    client.remove_charity(&charities.get(0).unwrap());
+    assert_eq!(
+        client.try_create_vault_with_charity(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &None,
+            &ctx.success_dest,
+            &0,
+            &VaultOptions::default(),
+        ),
+        Err(Ok(Error::CharityNotFound))
+    );
+}
@@ -0,0 +1,149 @@
+//! Transferable success claims: ownership, settlement and the status-aware getter.
+
+use soroban_sdk::{testutils::Address as _, Address, IntoVal, Symbol};
+
+use crate::claim::Claim;
+use crate::events::CLAIM_TRANSFERRED;
+use crate::savings::VaultMode;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+fn try_create(ctx: &VaultTestContext, options: &VaultOptions) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            options,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext) -> u32 {
+    ctx.advance_to_start();
+    try_create(
+        ctx,
+        &VaultOptions {
+            transferable_claim: true,
+            ..VaultOptions::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_release_pays_the_owner_at_release_time() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+    let buyer = Address::generate(&ctx.env);
+    let last = Address::generate(&ctx.env);
+
+    client.transfer_claim(&id, &buyer);
+    assert_eq!(ctx.env.auths()[0].0, ctx.success_dest);
+    client.transfer_claim(&id, &last);
+    assert_eq!(ctx.env.auths()[0].0, buyer);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&ctx.env, CLAIM_TRANSFERRED), id, buyer.clone()).into_val(&ctx.env)
+    );
+    let new_owner: Address = data.into_val(&ctx.env);
+    assert_eq!(new_owner, last);
+
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&last, ctx.amount), (&buyer, 0), (&ctx.success_dest, 0)]);
+}
+
+#[test]
+fn test_failed_claims_still_transfer_and_report_status() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+    assert_eq!(
+        client.get_claim(&id),
+        Some(Claim {
+            owner: ctx.success_dest.clone(),
+            status: VaultStatus::Active,
+        })
+    );
+
+    ctx.advance_past_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+    let buyer = Address::generate(&ctx.env);
+    client.transfer_claim(&id, &buyer);
+    assert_eq!(
+        client.get_claim(&id),
+        Some(Claim {
+            owner: buyer,
+            status: VaultStatus::Failed,
+        })
+    );
+    // The settled vault keeps the destination it settled with.
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().success_destination,
+        ctx.success_dest
+    );
+}
+
+#[test]
+fn test_creator_cannot_redirect_a_claim() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    assert_eq!(
+        client.try_update_success_destination(&id, &Address::generate(&ctx.env)),
+        Err(Ok(Error::DestinationsLocked))
+    );
+    assert_eq!(
+        client.try_transfer_claim(&id, &ctx.contract_id),
+        Err(Ok(Error::InvalidDestination))
+    );
+}
+
+#[test]
+fn test_vaults_without_a_claim_cannot_transfer() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.try_transfer_claim(&id, &Address::generate(&ctx.env)),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(client.get_claim(&id), None);
+    assert_eq!(client.try_get_claim(&7), Err(Ok(Error::VaultNotFound)));
+}
+
+#[test]
+fn test_savings_locks_have_no_claim() {
+    let ctx = VaultTestContext::new();
+    ctx.advance_to_start();
+    let result = ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.creator,
+        &ctx.creator,
+        &VaultOptions {
+            mode: VaultMode::SavingsLock,
+            transferable_claim: true,
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSavingsLock)));
+}
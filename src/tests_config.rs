@@ -0,0 +1,55 @@
+//! Deployment admin and configuration updates.
+
+use crate::config::Config;
+use crate::reputation::ReputationWeights;
+use crate::testutils::VaultTestContext;
+use crate::Error;
+
+#[test]
+fn test_defaults_before_any_config() {
+    let ctx = VaultTestContext::new();
+    assert_eq!(ctx.client().get_config(), Config::default());
+    assert_eq!(ctx.client().get_admin(), Some(ctx.admin.clone()));
+}
+
+#[test]
+fn test_set_config_requires_admin_auth() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let config = Config {
+        streak_window: 86_400,
+        ..Config::default()
+    };
+    client.set_config(&config);
+    let auths = ctx.env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, ctx.admin);
+    assert_eq!(client.get_config(), config);
+}
+
+#[test]
+fn test_set_config_rejects_unusable_values() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+
+    let negative_min = Config {
+        min_amount: -1,
+        ..Config::default()
+    };
+    assert_eq!(
+        client.try_set_config(&negative_min),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    let zero_unit = Config {
+        reputation: ReputationWeights {
+            stake_unit: 0,
+            ..ReputationWeights::default()
+        },
+        ..Config::default()
+    };
+    assert_eq!(
+        client.try_set_config(&zero_unit),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
@@ -0,0 +1,135 @@
+//! Two-step transfer of the creator role, and how history is attributed afterwards.
+
+use soroban_sdk::{testutils::Address as _, Address, BytesN, IntoVal, Symbol};
+
+use crate::events::CREATOR_TRANSFERRED;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+/// Default vault whose creator role has been handed to a fresh address.
+fn transferred(ctx: &VaultTestContext) -> (u32, Address) {
+    let id = ctx.create_default_vault();
+    let new_creator = Address::generate(&ctx.env);
+    ctx.client().transfer_creator(&id, &new_creator);
+    ctx.client().accept_creator(&id);
+    (id, new_creator)
+}
+
+#[test]
+fn test_transfer_takes_effect_on_acceptance() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    let new_creator = Address::generate(&ctx.env);
+
+    client.transfer_creator(&id, &new_creator);
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    assert_eq!(client.get_pending_creator(&id), Some(new_creator.clone()));
+    assert_eq!(client.get_vault_state(&id).unwrap().creator, ctx.creator);
+
+    client.accept_creator(&id);
+    assert_eq!(ctx.env.auths()[0].0, new_creator);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, CREATOR_TRANSFERRED),
+            id,
+            ctx.verifier.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let (old, new): (Address, Address) = data.into_val(&ctx.env);
+    assert_eq!((old, new), (ctx.creator.clone(), new_creator.clone()));
+
+    assert_eq!(client.get_vault_state(&id).unwrap().creator, new_creator);
+    assert_eq!(client.get_pending_creator(&id), None);
+    assert_eq!(client.get_original_creator(&id), ctx.creator);
+}
+
+#[test]
+fn test_new_creator_submits_proofs_and_receives_refunds() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let (id, new_creator) = transferred(&ctx);
+
+    client.submit_proof(&id, &BytesN::from_array(&ctx.env, &[2u8; 32]));
+    assert_eq!(ctx.env.auths()[0].0, new_creator);
+
+    client.cancel_vault(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&new_creator, ctx.amount), (&ctx.creator, 0)]);
+}
+
+#[test]
+fn test_streak_follows_the_vault_and_reputation_stays_with_the_original() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let (id, new_creator) = transferred(&ctx);
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+
+    assert_eq!(client.get_streak(&new_creator).current, 1);
+    assert_eq!(client.get_streak(&ctx.creator).current, 0);
+    assert_eq!(client.get_reputation(&ctx.creator).completed, 1);
+    assert_eq!(client.get_reputation(&new_creator).completed, 0);
+}
+
+#[test]
+fn test_transfer_rejects_bad_targets() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let partner = Address::generate(&ctx.env);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            accountability_partner: Some(partner.clone()),
+            ..VaultOptions::default()
+        },
+    );
+
+    assert_eq!(
+        client.try_accept_creator(&id),
+        Err(Ok(Error::NoPendingTransfer))
+    );
+    assert_eq!(
+        client.try_transfer_creator(&id, &ctx.creator),
+        Err(Ok(Error::InvalidCreator))
+    );
+    assert_eq!(
+        client.try_transfer_creator(&id, &ctx.contract_id),
+        Err(Ok(Error::InvalidCreator))
+    );
+    assert_eq!(
+        client.try_transfer_creator(&id, &partner),
+        Err(Ok(Error::InvalidPartner))
+    );
+}
+
+#[test]
+fn test_settled_vaults_cannot_change_hands() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    let new_creator = Address::generate(&ctx.env);
+    client.transfer_creator(&id, &new_creator);
+    client.cancel_vault(&id, &ctx.usdc_token);
+
+    assert_eq!(
+        client.try_accept_creator(&id),
+        Err(Ok(Error::VaultNotActive))
+    );
+    assert_eq!(
+        client.try_transfer_creator(&id, &new_creator),
+        Err(Ok(Error::VaultNotActive))
+    );
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount)]);
+}
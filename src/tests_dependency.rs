@@ -0,0 +1,177 @@
+//! Vault dependencies: locked validation, cascading failures and creation checks.
+
+use soroban_sdk::{testutils::Address as _, Address, Vec};
+
+use crate::dependency::{UnlockStatus, MAX_DEPENDENCIES};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+/// Dependents in the cascade tests run this much longer than their dependency.
+const LATER: u64 = 1_000;
+
+fn try_create(
+    ctx: &VaultTestContext,
+    creator: &Address,
+    end_timestamp: u64,
+    depends_on: &[u32],
+    cascade_failure: bool,
+) -> Result<u32, Error> {
+    let mut ids = Vec::new(&ctx.env);
+    for id in depends_on {
+        ids.push_back(*id);
+    }
+    ctx.mint(creator, ctx.amount);
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                depends_on: (!depends_on.is_empty()).then_some(ids),
+                cascade_failure,
+                ..VaultOptions::default()
+            },
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext, depends_on: &[u32]) -> u32 {
+    try_create(ctx, &ctx.creator, ctx.end_timestamp, depends_on, false).unwrap()
+}
+
+#[test]
+fn test_dependent_unlocks_once_every_dependency_completes() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = ctx.create_default_vault();
+    let second = create(&ctx, &[]);
+    let third = create(&ctx, &[first, second]);
+    assert_eq!(client.get_unlock_status(&third), UnlockStatus::Locked);
+    assert_eq!(client.get_unlock_status(&first), UnlockStatus::Unlocked);
+
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_validate_milestone(&third),
+        Err(Ok(Error::DependencyNotMet))
+    );
+    client.validate_milestone(&first);
+    client.release_funds(&first, &ctx.usdc_token);
+    assert_eq!(client.get_unlock_status(&third), UnlockStatus::Locked);
+    assert_eq!(
+        client.try_validate_milestone(&third),
+        Err(Ok(Error::DependencyNotMet))
+    );
+
+    client.validate_milestone(&second);
+    client.release_funds(&second, &ctx.usdc_token);
+    assert_eq!(client.get_unlock_status(&third), UnlockStatus::Unlocked);
+    client.validate_milestone(&third);
+    client.release_funds(&third, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, 3 * ctx.amount)]);
+}
+
+#[test]
+fn test_locked_vault_is_not_released_at_its_deadline() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = ctx.create_default_vault();
+    let second = create(&ctx, &[first]);
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_release_funds(&second, &ctx.usdc_token),
+        Err(Ok(Error::DependencyNotMet))
+    );
+    client.redirect_funds(&second, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.failure_dest, ctx.amount), (&ctx.success_dest, 0)]);
+}
+
+#[test]
+fn test_failed_dependency_cascades_only_when_asked() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = ctx.create_default_vault();
+    let later = ctx.end_timestamp + LATER;
+    let cascading = try_create(&ctx, &ctx.creator, later, &[first], true).unwrap();
+    let waiting = try_create(&ctx, &ctx.creator, later, &[first], false).unwrap();
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_redirect_funds(&cascading, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    client.redirect_funds(&first, &ctx.usdc_token);
+    assert_eq!(client.get_unlock_status(&cascading), UnlockStatus::Blocked);
+
+    client.redirect_funds(&cascading, &ctx.usdc_token);
+    assert_eq!(
+        client.get_vault_state(&cascading).unwrap().status,
+        VaultStatus::Failed
+    );
+    assert_eq!(
+        client.try_redirect_funds(&waiting, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_validate_milestone(&waiting),
+        Err(Ok(Error::DependencyNotMet))
+    );
+    ctx.advance_to(later);
+    client.redirect_funds(&waiting, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.failure_dest, 3 * ctx.amount)]);
+}
+
+#[test]
+fn test_cancelled_dependency_blocks_the_dependent() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = ctx.create_default_vault();
+    let second = create(&ctx, &[first]);
+
+    client.cancel_vault(&first, &ctx.usdc_token);
+    assert_eq!(client.get_unlock_status(&second), UnlockStatus::Blocked);
+    assert_eq!(
+        try_create(&ctx, &ctx.creator, ctx.end_timestamp, &[first], false),
+        Err(Error::DependencyNotMet)
+    );
+}
+
+#[test]
+fn test_create_rejects_malformed_dependencies() {
+    let ctx = VaultTestContext::new();
+    let first = ctx.create_default_vault();
+    let end = ctx.end_timestamp;
+
+    assert_eq!(
+        try_create(&ctx, &ctx.creator, end, &[first, first], false),
+        Err(Error::InvalidParticipants)
+    );
+    let too_many: [u32; MAX_DEPENDENCIES as usize + 1] = core::array::from_fn(|_| first);
+    assert_eq!(
+        try_create(&ctx, &ctx.creator, end, &too_many, false),
+        Err(Error::InvalidParticipants)
+    );
+    // Vault 1 does not exist yet; it would be the new vault itself.
+    assert_eq!(
+        try_create(&ctx, &ctx.creator, end, &[first + 1], false),
+        Err(Error::VaultNotFound)
+    );
+    let stranger = Address::generate(&ctx.env);
+    assert_eq!(
+        try_create(&ctx, &stranger, end, &[first], false),
+        Err(Error::NotAuthorized)
+    );
+    assert_eq!(ctx.client().get_dependencies(&first), None);
+
+    let second = create(&ctx, &[first]);
+    let dependencies = ctx.client().get_dependencies(&second).unwrap();
+    assert_eq!(dependencies.depends_on.len(), 1);
+    assert!(!dependencies.cascade_failure);
+}
@@ -0,0 +1,127 @@
+//! Retargeting the success destination, and vaults that lock it.
+
+use soroban_sdk::{testutils::Address as _, Address, IntoVal, Symbol};
+
+use crate::events::SUCCESS_DESTINATION_UPDATED;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+#[test]
+fn test_creator_retargets_success_destination() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    let new_destination = Address::generate(&ctx.env);
+
+    assert!(client.update_success_destination(&id, &new_destination));
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, SUCCESS_DESTINATION_UPDATED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let (old, new): (Address, Address) = data.into_val(&ctx.env);
+    assert_eq!(
+        (old, new),
+        (ctx.success_dest.clone(), new_destination.clone())
+    );
+
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&new_destination, ctx.amount), (&ctx.success_dest, 0)]);
+}
+
+#[test]
+fn test_retarget_window_closes_at_validation_or_deadline() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let destination = Address::generate(&ctx.env);
+    let validated = ctx.create_default_vault();
+    ctx.mint(&ctx.creator, ctx.amount);
+    let expired = ctx.create_default_vault();
+
+    ctx.advance_to_start();
+    client.validate_milestone(&validated);
+    assert_eq!(
+        client.try_update_success_destination(&validated, &destination),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_update_success_destination(&expired, &destination),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    client.redirect_funds(&expired, &ctx.usdc_token);
+    assert_eq!(
+        client.try_update_success_destination(&expired, &destination),
+        Err(Ok(Error::VaultNotActive))
+    );
+}
+
+#[test]
+fn test_retarget_rejects_the_contract_itself() {
+    let ctx = VaultTestContext::new();
+    let id = ctx.create_default_vault();
+    assert_eq!(
+        ctx.client()
+            .try_update_success_destination(&id, &ctx.contract_id),
+        Err(Ok(Error::InvalidDestination))
+    );
+}
+
+#[test]
+fn test_locked_destinations_cannot_change() {
+    let ctx = VaultTestContext::new();
+    let id = ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            destinations_locked: true,
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(
+        ctx.client()
+            .try_update_success_destination(&id, &Address::generate(&ctx.env)),
+        Err(Ok(Error::DestinationsLocked))
+    );
+}
+
+#[test]
+fn test_sponsored_retarget_needs_the_funder_too() {
+    let ctx = VaultTestContext::new();
+    let funder = Address::generate(&ctx.env);
+    ctx.mint(&funder, ctx.amount);
+    let id = ctx.client().create_sponsored_vault(
+        &ctx.usdc_token,
+        &funder,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &None,
+    );
+
+    ctx.client()
+        .update_success_destination(&id, &Address::generate(&ctx.env));
+    let auths = ctx.env.auths();
+    assert_eq!(auths.len(), 2);
+    assert_eq!((&auths[0].0, &auths[1].0), (&ctx.creator, &funder));
+}
@@ -0,0 +1,226 @@
+//! Emergency unlock: the verifier's approval, revocation, expiry and the refunds.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, IntoVal, Symbol, TryIntoVal, Vec};
+
+use crate::config::Config;
+use crate::events::{EMERGENCY_UNLOCK, VAULT_CANCELLED};
+use crate::group::GroupPayout;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+const WINDOW: u64 = 300;
+
+fn configure(ctx: &VaultTestContext, quorum_bps: u32) {
+    ctx.client().set_config(&Config {
+        emergency_quorum_bps: quorum_bps,
+        emergency_approval_window: WINDOW,
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_verifier_approval_unlocks_a_validated_vault_for_the_creator() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    configure(&ctx, 6_666);
+    let id = ctx.create_default_vault();
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+
+    assert_eq!(
+        client.try_emergency_unlock(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(client.approve_emergency(&id, &ctx.verifier), 1);
+    assert_eq!(env.auths()[0].0, ctx.verifier);
+
+    client.emergency_unlock(&id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, EMERGENCY_UNLOCK), id, ctx.creator.clone()).into_val(env)
+    );
+    let approvers: Vec<Address> = data.try_into_val(env).unwrap();
+    assert_eq!(approvers, vec![env, ctx.verifier.clone()]);
+    let name: Symbol = events
+        .get(1)
+        .unwrap()
+        .0
+        .get(0)
+        .unwrap()
+        .try_into_val(env)
+        .unwrap();
+    assert_eq!(name, Symbol::new(env, VAULT_CANCELLED));
+
+    ctx.assert_balances(&[
+        (&ctx.creator, ctx.amount),
+        (&ctx.success_dest, 0),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+    assert_eq!(
+        client.try_emergency_unlock(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_approvals_expire_after_the_window() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx, 10_000);
+    let id = ctx.create_default_vault();
+
+    client.approve_emergency(&id, &ctx.verifier);
+    assert_eq!(
+        client.try_approve_emergency(&id, &ctx.verifier),
+        Err(Ok(Error::AlreadyJoined))
+    );
+    ctx.advance_to(WINDOW);
+    assert_eq!(client.get_emergency_approvals(&id).len(), 0);
+    assert_eq!(
+        client.try_emergency_unlock(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    // A lapsed approval can be given again.
+    client.approve_emergency(&id, &ctx.verifier);
+    let approvals = client.get_emergency_approvals(&id);
+    assert_eq!(approvals, vec![&ctx.env, (ctx.verifier.clone(), WINDOW)]);
+    client.emergency_unlock(&id, &ctx.usdc_token);
+}
+
+#[test]
+fn test_approval_is_revocable_only_before_the_quorum() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx, 10_000);
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.try_revoke_emergency(&id, &ctx.verifier),
+        Err(Ok(Error::NoPendingCancellation))
+    );
+    client.approve_emergency(&id, &ctx.verifier);
+    // A single verifier's approval is the whole quorum.
+    assert_eq!(
+        client.try_revoke_emergency(&id, &ctx.verifier),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_only_verifiers_approve_and_only_when_enabled() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx, 0);
+    let id = ctx.create_default_vault();
+    assert_eq!(
+        client.try_approve_emergency(&id, &ctx.verifier),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    client.set_config(&Config {
+        emergency_quorum_bps: 5_000,
+        ..Config::default()
+    });
+    assert_eq!(
+        client.try_approve_emergency(&id, &ctx.creator),
+        Err(Ok(Error::NotAuthorized))
+    );
+    let stranger = Address::generate(&ctx.env);
+    assert_eq!(
+        client.try_approve_emergency(&id, &stranger),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    // Without a verifier there is no one to reach a quorum.
+    ctx.mint(&ctx.creator, ctx.amount);
+    let unverified = ctx.create_vault_no_verifier();
+    assert_eq!(
+        client.try_emergency_unlock(&unverified, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_unlocked_wager_refunds_each_side_its_stake() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx, 6_666);
+    let challenger = Address::generate(&ctx.env);
+    ctx.mint(&challenger, ctx.amount);
+    let id = client.create_wager(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &challenger,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &ctx.verifier,
+    );
+    client.accept_wager(&id, &ctx.usdc_token);
+
+    client.approve_emergency(&id, &ctx.verifier);
+    client.emergency_unlock(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.creator, ctx.amount),
+        (&challenger, ctx.amount),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_a_verifier_cannot_unlock_their_own_stake() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx, 10_000);
+    let self_verified = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.creator.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    );
+    let member = Address::generate(&ctx.env);
+    ctx.mint(&member, ctx.amount);
+    let group = client.create_group_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &vec![&ctx.env, member.clone()],
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(member.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &GroupPayout::Proportional,
+    );
+    client.join_vault(&group, &member, &ctx.amount, &ctx.usdc_token);
+
+    for (id, verifier) in [(self_verified, &ctx.creator), (group, &member)] {
+        assert_eq!(
+            client.try_approve_emergency(&id, verifier),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(
+            client.try_emergency_unlock(&id, &ctx.usdc_token),
+            Err(Ok(Error::InvalidStatus))
+        );
+    }
+}
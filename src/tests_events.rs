@@ -0,0 +1,298 @@
+//! The event stream seen by an indexer across complete vault lifecycles.
+//!
+//! `env.events().all()` only covers the last top-level invocation, so each test collects
+//! the vault's events after every call and compares the concatenated stream.
+
+use soroban_sdk::{vec, Address, BytesN, IntoVal, Symbol, TryIntoVal, Val, Vec};
+
+use crate::events::{
+    CHANGES_REQUESTED, FUNDS_REDIRECTED, FUNDS_RELEASED, MILESTONE_VALIDATED, PROOF_APPROVED,
+    PROOF_SUBMITTED, STREAK_UPDATED, VAULT_CANCELLED, VAULT_CREATED,
+};
+use crate::payout::PayoutBreakdown;
+use crate::proofs::ReasonCode;
+use crate::streak::StreakInfo;
+use crate::testutils::VaultTestContext;
+use crate::ProductivityVault;
+
+fn event(
+    ctx: &VaultTestContext,
+    name: &str,
+    vault_id: u32,
+    party: &Address,
+    data: impl IntoVal<soroban_sdk::Env, Val>,
+) -> (Vec<Val>, Val) {
+    let env = &ctx.env;
+    (
+        (Symbol::new(env, name), vault_id, party.clone()).into_val(env),
+        data.into_val(env),
+    )
+}
+
+/// `vault_created` for `vault`, whose topics also carry its category.
+fn created_event(
+    ctx: &VaultTestContext,
+    vault_id: u32,
+    vault: ProductivityVault,
+) -> (Vec<Val>, Val) {
+    let env = &ctx.env;
+    (
+        (
+            Symbol::new(env, VAULT_CREATED),
+            vault_id,
+            vault.creator.clone(),
+            vault.category.clone(),
+        )
+            .into_val(env),
+        vault.into_val(env),
+    )
+}
+
+/// Breakdown of the context's stake paid in full to `recipient`.
+fn sole_recipient(ctx: &VaultTestContext, recipient: &Address) -> PayoutBreakdown {
+    PayoutBreakdown {
+        gross: ctx.amount,
+        fee: 0,
+        penalty: 0,
+        verifier_reward: 0,
+        shares: vec![&ctx.env, (recipient.clone(), ctx.amount)],
+    }
+}
+
+/// `streak_updated` for the context's creator.
+fn streak_event(ctx: &VaultTestContext, streak: StreakInfo) -> (Vec<Val>, Val) {
+    let env = &ctx.env;
+    (
+        (Symbol::new(env, STREAK_UPDATED), ctx.creator.clone()).into_val(env),
+        streak.into_val(env),
+    )
+}
+
+/// Decode the `PayoutBreakdown` of the settlement event, the first event of the last
+/// invocation.
+fn settlement_payload(ctx: &VaultTestContext, name: &str) -> PayoutBreakdown {
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    let topic: Symbol = topics.get(0).unwrap().try_into_val(&ctx.env).unwrap();
+    assert_eq!(topic, Symbol::new(&ctx.env, name));
+    data.try_into_val(&ctx.env).unwrap()
+}
+
+/// Run `call` and append the vault events it published to `stream`.
+fn record<T>(
+    ctx: &VaultTestContext,
+    stream: &mut Vec<(Vec<Val>, Val)>,
+    call: impl FnOnce() -> T,
+) -> T {
+    let result = call();
+    stream.append(&ctx.vault_events());
+    result
+}
+
+#[test]
+fn test_release_lifecycle_event_stream() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let mut stream = Vec::new(&ctx.env);
+    ctx.advance_to_start();
+
+    let id = record(&ctx, &mut stream, || ctx.create_default_vault());
+    let created = client.get_vault_state(&id).unwrap();
+    record(&ctx, &mut stream, || client.validate_milestone(&id));
+    record(&ctx, &mut stream, || {
+        client.release_funds(&id, &ctx.usdc_token)
+    });
+
+    let mut expected = Vec::new(&ctx.env);
+    expected.push_back(created_event(&ctx, id, created));
+    expected.push_back(event(&ctx, MILESTONE_VALIDATED, id, &ctx.verifier, ()));
+    expected.push_back(event(
+        &ctx,
+        FUNDS_RELEASED,
+        id,
+        &ctx.success_dest,
+        sole_recipient(&ctx, &ctx.success_dest),
+    ));
+    expected.push_back(streak_event(
+        &ctx,
+        StreakInfo {
+            current: 1,
+            longest: 1,
+            last_completed_at: Some(ctx.start_timestamp),
+        },
+    ));
+    assert_eq!(stream, expected);
+}
+
+#[test]
+fn test_redirect_lifecycle_event_stream() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let mut stream = Vec::new(&ctx.env);
+    ctx.advance_to_start();
+
+    let id = record(&ctx, &mut stream, || ctx.create_default_vault());
+    let created = client.get_vault_state(&id).unwrap();
+    ctx.advance_to_deadline();
+    record(&ctx, &mut stream, || {
+        client.redirect_funds(&id, &ctx.usdc_token)
+    });
+
+    let mut expected = Vec::new(&ctx.env);
+    expected.push_back(created_event(&ctx, id, created));
+    expected.push_back(event(
+        &ctx,
+        FUNDS_REDIRECTED,
+        id,
+        &ctx.failure_dest,
+        sole_recipient(&ctx, &ctx.failure_dest),
+    ));
+    expected.push_back(streak_event(&ctx, StreakInfo::default()));
+    assert_eq!(stream, expected);
+}
+
+#[test]
+fn test_cancel_lifecycle_event_stream() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let mut stream = Vec::new(&ctx.env);
+    ctx.advance_to_start();
+
+    let id = record(&ctx, &mut stream, || ctx.create_vault_no_verifier());
+    let created = client.get_vault_state(&id).unwrap();
+    record(&ctx, &mut stream, || client.validate_milestone(&id));
+    record(&ctx, &mut stream, || {
+        client.cancel_vault(&id, &ctx.usdc_token)
+    });
+
+    // Without a verifier the creator is the validator.
+    let mut expected = Vec::new(&ctx.env);
+    expected.push_back(created_event(&ctx, id, created));
+    expected.push_back(event(&ctx, MILESTONE_VALIDATED, id, &ctx.creator, ()));
+    expected.push_back(event(&ctx, VAULT_CANCELLED, id, &ctx.creator, ctx.amount));
+    assert_eq!(stream, expected);
+}
+
+#[test]
+fn test_read_only_calls_emit_no_events() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    client.get_vault_state(&id);
+    ctx.assert_no_events();
+    client.get_vault_history(&id);
+    ctx.assert_no_events();
+}
+
+#[test]
+fn test_release_payload_reconstructs_transfers() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+
+    let parts = settlement_payload(&ctx, FUNDS_RELEASED);
+    assert_eq!(parts.gross, ctx.amount);
+    assert_eq!(parts.parts_total(), Ok(parts.gross));
+    for (recipient, net) in parts.shares.iter() {
+        ctx.assert_balances(&[(&recipient, net)]);
+    }
+    ctx.assert_balances(&[(&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_redirect_payload_reconstructs_transfers() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    ctx.advance_past_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+
+    let parts = settlement_payload(&ctx, FUNDS_REDIRECTED);
+    assert_eq!(parts.gross, ctx.amount);
+    assert_eq!(parts.parts_total(), Ok(parts.gross));
+    for (recipient, net) in parts.shares.iter() {
+        ctx.assert_balances(&[(&recipient, net)]);
+    }
+    ctx.assert_balances(&[(&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_proof_revision_cycle_event_stream() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let mut stream = Vec::new(&ctx.env);
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    let first = BytesN::from_array(&ctx.env, &[7u8; 32]);
+    let second = BytesN::from_array(&ctx.env, &[8u8; 32]);
+
+    record(&ctx, &mut stream, || client.submit_proof(&id, &first));
+    record(&ctx, &mut stream, || {
+        client.request_changes(&id, &ReasonCode::InsufficientEvidence)
+    });
+    record(&ctx, &mut stream, || client.submit_proof(&id, &second));
+    record(&ctx, &mut stream, || client.validate_milestone(&id));
+
+    let mut expected = Vec::new(&ctx.env);
+    let (creator, verifier) = (&ctx.creator, &ctx.verifier);
+    expected.push_back(event(
+        &ctx,
+        PROOF_SUBMITTED,
+        id,
+        creator,
+        (first.clone(), 0u32),
+    ));
+    expected.push_back(event(
+        &ctx,
+        CHANGES_REQUESTED,
+        id,
+        verifier,
+        (first, ReasonCode::InsufficientEvidence),
+    ));
+    expected.push_back(event(
+        &ctx,
+        PROOF_SUBMITTED,
+        id,
+        creator,
+        (second.clone(), 1u32),
+    ));
+    expected.push_back(event(&ctx, MILESTONE_VALIDATED, id, verifier, ()));
+    expected.push_back(event(&ctx, PROOF_APPROVED, id, verifier, second));
+    assert_eq!(stream, expected);
+}
+
+#[test]
+fn test_reason_code_is_a_u32_on_the_wire() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    client.submit_proof(&id, &BytesN::from_array(&ctx.env, &[7u8; 32]));
+    client.request_changes(&id, &ReasonCode::QualityIssue);
+
+    let (_, data) = ctx.vault_events().get(0).unwrap();
+    let (_, code): (BytesN<32>, u32) = data.try_into_val(&ctx.env).unwrap();
+    assert_eq!(code, ReasonCode::QualityIssue as u32);
+}
+
+#[test]
+fn test_party_topic_lets_a_wallet_filter_its_events() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    ctx.advance_past_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+
+    // The failure destination finds the payout by its address in topic position 2.
+    let (topics, _) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(topics.len(), 3);
+    let party: Address = topics.get(2).unwrap().try_into_val(&ctx.env).unwrap();
+    assert_eq!(party, ctx.failure_dest);
+}
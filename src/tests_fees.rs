@@ -0,0 +1,268 @@
+//! Protocol fee withholding, referral rewards and fee withdrawal.
+
+use soroban_sdk::{testutils::Address as _, Address, IntoVal, Symbol};
+
+use crate::config::Config;
+use crate::events::REFERRAL_PAID;
+use crate::fees::ReferralStats;
+use crate::recurring::RecurringSchedule;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+/// 5% protocol fee, a fifth of which goes to the referrer.
+const FEE_BPS: u32 = 500;
+const REFERRAL_BPS: u32 = 2_000;
+/// Fee and referral reward on the default 1 USDC stake.
+const FEE: i128 = 50_000;
+const REWARD: i128 = 10_000;
+
+struct Setup {
+    ctx: VaultTestContext,
+    admin: Address,
+    referrer: Address,
+}
+
+impl Setup {
+    fn new(fee_bps: u32) -> Self {
+        let ctx = VaultTestContext::new();
+        let admin = ctx.admin.clone();
+        ctx.client().set_config(&Config {
+            fee_bps,
+            referral_bps: REFERRAL_BPS,
+            ..Config::default()
+        });
+        let referrer = Address::generate(&ctx.env);
+        Setup {
+            ctx,
+            admin,
+            referrer,
+        }
+    }
+
+    fn referred_vault(&self) -> u32 {
+        let ctx = &self.ctx;
+        ctx.client().create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &None,
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                referrer: Some(self.referrer.clone()),
+                ..VaultOptions::default()
+            },
+        )
+    }
+
+    fn complete(&self, id: u32) {
+        let ctx = &self.ctx;
+        ctx.advance_to_start();
+        ctx.client().validate_milestone(&id);
+        ctx.client().release_funds(&id, &ctx.usdc_token);
+    }
+}
+
+#[test]
+fn test_completion_pays_referrer_a_share_of_the_fee() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    let id = setup.referred_vault();
+    setup.complete(id);
+
+    let (topics, data) = ctx.vault_events().get(1).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, REFERRAL_PAID),
+            id,
+            setup.referrer.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let reward: i128 = data.into_val(&ctx.env);
+    assert_eq!(reward, REWARD);
+
+    ctx.assert_balances(&[
+        (&ctx.success_dest, ctx.amount - FEE),
+        (&setup.referrer, REWARD),
+        (&ctx.contract_id, FEE - REWARD),
+    ]);
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), FEE - REWARD);
+    assert_eq!(
+        ctx.client().get_referral_stats(&setup.referrer),
+        ReferralStats {
+            completed_referrals: 1,
+            earned: REWARD,
+        }
+    );
+}
+
+#[test]
+fn test_unreferred_vault_accrues_the_whole_fee() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    setup.complete(id);
+
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount - FEE)]);
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), FEE);
+}
+
+#[test]
+fn test_failed_and_cancelled_referrals_pay_nothing() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+
+    let cancelled = setup.referred_vault();
+    client.cancel_vault(&cancelled, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount)]);
+
+    let failed = setup.referred_vault();
+    ctx.advance_past_deadline();
+    client.redirect_funds(&failed, &ctx.usdc_token);
+    assert_eq!(
+        client.get_vault_state(&failed).unwrap().status,
+        VaultStatus::Failed
+    );
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, ctx.amount),
+        (&setup.referrer, 0),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_referral_stats(&setup.referrer),
+        ReferralStats::default()
+    );
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), 0);
+}
+
+#[test]
+fn test_referral_without_protocol_fee_earns_zero() {
+    let setup = Setup::new(0);
+    let ctx = &setup.ctx;
+    let id = setup.referred_vault();
+    setup.complete(id);
+
+    let (_, data) = ctx.vault_events().get(1).unwrap();
+    let reward: i128 = data.into_val(&ctx.env);
+    assert_eq!(reward, 0);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&setup.referrer, 0)]);
+    assert_eq!(
+        ctx.client().get_referral_stats(&setup.referrer),
+        ReferralStats {
+            completed_referrals: 1,
+            earned: 0,
+        }
+    );
+}
+
+#[test]
+fn test_self_referral_rejected() {
+    let ctx = VaultTestContext::new();
+    let result = ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            referrer: Some(ctx.creator.clone()),
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::SelfReferral)));
+}
+
+#[test]
+fn test_admin_withdraws_accrued_fees() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let id = setup.referred_vault();
+    setup.complete(id);
+
+    let treasury = Address::generate(&ctx.env);
+    assert_eq!(
+        client.withdraw_fees(&ctx.usdc_token, &treasury),
+        FEE - REWARD
+    );
+    let auths = ctx.env.auths();
+    assert_eq!(auths[0].0, setup.admin);
+    ctx.assert_balances(&[(&treasury, FEE - REWARD), (&ctx.contract_id, 0)]);
+    assert_eq!(client.withdraw_fees(&ctx.usdc_token, &treasury), 0);
+}
+
+#[test]
+fn test_fees_cannot_be_withdrawn_to_the_contract() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let id = setup.referred_vault();
+    setup.complete(id);
+
+    assert_eq!(
+        client.try_withdraw_fees(&ctx.usdc_token, &ctx.contract_id),
+        Err(Ok(Error::InvalidDestination))
+    );
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), FEE - REWARD);
+}
+
+#[test]
+fn test_immediately_released_slices_pay_the_fee() {
+    let setup = Setup::new(FEE_BPS);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let id = client.create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &RecurringSchedule {
+            period_length: ctx.end_timestamp - ctx.start_timestamp,
+            period_count: 1,
+            release_immediately: true,
+        },
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+    ctx.advance_to_start();
+    assert_eq!(client.validate_period(&id, &0, &ctx.usdc_token), ctx.amount);
+
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+    ctx.assert_balances(&[
+        (&ctx.success_dest, ctx.amount - FEE),
+        (&ctx.contract_id, FEE),
+    ]);
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), FEE);
+}
+
+#[test]
+fn test_config_rejects_rates_above_100_percent() {
+    let setup = Setup::new(FEE_BPS);
+    let client = setup.ctx.client();
+    for (fee_bps, referral_bps) in [(10_001, 0), (0, 10_001)] {
+        assert_eq!(
+            client.try_set_config(&Config {
+                fee_bps,
+                referral_bps,
+                ..Config::default()
+            }),
+            Err(Ok(Error::InvalidBps))
+        );
+    }
+}
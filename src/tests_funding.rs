@@ -0,0 +1,173 @@
+//! Funding deadlines: the closing window and `expire_unfunded` for every Pending kind.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, IntoVal, Symbol};
+
+use crate::events::{VAULT_CANCELLED, VAULT_EXPIRED};
+use crate::group::GroupPayout;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+const DEADLINE: u64 = 50;
+
+/// A group of three where only the first two, staking 100 and 300, will join.
+fn group(ctx: &VaultTestContext) -> (u32, [Address; 3]) {
+    let members: [Address; 3] = core::array::from_fn(|_| Address::generate(&ctx.env));
+    for member in &members {
+        ctx.mint(member, 300);
+    }
+    let id = ctx.client().create_group_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &vec![
+            &ctx.env,
+            members[0].clone(),
+            members[1].clone(),
+            members[2].clone(),
+        ],
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &GroupPayout::Proportional,
+    );
+    (id, members)
+}
+
+fn status(ctx: &VaultTestContext, id: u32) -> VaultStatus {
+    ctx.client().get_vault_state(&id).unwrap().status
+}
+
+#[test]
+fn test_partial_group_refunds_each_depositor_exactly() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let (id, members) = group(&ctx);
+    assert_eq!(client.get_funding_deadline(&id), ctx.start_timestamp);
+    client.set_funding_deadline(&id, &DEADLINE);
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+
+    client.join_vault(&id, &members[0], &100, &ctx.usdc_token);
+    client.join_vault(&id, &members[1], &300, &ctx.usdc_token);
+    ctx.advance_to(DEADLINE);
+    assert_eq!(
+        client.try_join_vault(&id, &members[2], &300, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+
+    client.expire_unfunded(&id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    for (i, name) in [VAULT_EXPIRED, VAULT_CANCELLED].iter().enumerate() {
+        let (topics, _) = events.get(i as u32).unwrap();
+        assert_eq!(
+            topics,
+            (Symbol::new(&ctx.env, name), id, ctx.creator.clone()).into_val(&ctx.env)
+        );
+    }
+    let (_, data) = events.get(0).unwrap();
+    let expired_at: u64 = data.into_val(&ctx.env);
+    assert_eq!(expired_at, DEADLINE);
+    ctx.assert_balances(&[
+        (&members[0], 300),
+        (&members[1], 300),
+        (&members[2], 300),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(status(&ctx, id), VaultStatus::Cancelled);
+    assert_eq!(client.get_status_count(&VaultStatus::Pending), 0);
+    assert_eq!(client.get_status_count(&VaultStatus::Cancelled), 1);
+    assert_eq!(
+        client.try_expire_unfunded(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_unaccepted_wager_and_gift_refund_their_payer() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let gifter = Address::generate(&ctx.env);
+    ctx.mint(&gifter, ctx.amount);
+    let wager = client.create_wager(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &Address::generate(&ctx.env),
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &ctx.verifier,
+    );
+    let gift = client.create_gift(
+        &ctx.usdc_token,
+        &gifter,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &None,
+    );
+    client.set_funding_deadline(&gift, &DEADLINE);
+    assert_eq!(ctx.env.auths()[0].0, gifter);
+
+    ctx.advance_to(DEADLINE);
+    assert_eq!(
+        client.try_accept_gift(&gift),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_expire_unfunded(&wager, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    client.expire_unfunded(&gift, &ctx.usdc_token);
+
+    ctx.advance_to_start();
+    client.expire_unfunded(&wager, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.creator, ctx.amount),
+        (&gifter, ctx.amount),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(status(&ctx, wager), VaultStatus::Cancelled);
+    assert_eq!(status(&ctx, gift), VaultStatus::Cancelled);
+}
+
+#[test]
+fn test_funded_vaults_cannot_expire() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    ctx.advance_past_deadline();
+
+    assert_eq!(
+        client.try_expire_unfunded(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_set_funding_deadline(&id, &DEADLINE),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_expire_unfunded(&7, &ctx.usdc_token),
+        Err(Ok(Error::VaultNotFound))
+    );
+}
+
+#[test]
+fn test_funding_deadline_must_fall_before_start() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let (id, _) = group(&ctx);
+    ctx.advance_to(DEADLINE);
+
+    for deadline in [DEADLINE, ctx.start_timestamp + 1] {
+        assert_eq!(
+            client.try_set_funding_deadline(&id, &deadline),
+            Err(Ok(Error::InvalidTimestamp))
+        );
+    }
+    client.set_funding_deadline(&id, &ctx.start_timestamp);
+}
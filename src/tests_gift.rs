@@ -0,0 +1,188 @@
+//! Gifted vaults: acceptance, reclaim and who is paid, refunded and authorized.
+
+use soroban_sdk::{testutils::Address as _, Address, IntoVal, Symbol, TryIntoVal};
+
+use crate::events::{GIFT_ACCEPTED, VAULT_ACTIVATED};
+use crate::gift::Gift;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+/// A gifter funded with the stake; the context's creator is the recipient.
+fn funded_gifter(ctx: &VaultTestContext) -> Address {
+    let gifter = Address::generate(&ctx.env);
+    ctx.mint(&gifter, ctx.amount);
+    gifter
+}
+
+fn try_create(
+    ctx: &VaultTestContext,
+    gifter: &Address,
+    verifier: Option<Address>,
+    success_destination: Option<Address>,
+) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_gift(
+            &ctx.usdc_token,
+            gifter,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &verifier,
+            &success_destination,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn accepted(ctx: &VaultTestContext, gifter: &Address) -> u32 {
+    let id = try_create(ctx, gifter, None, None).unwrap();
+    ctx.client().accept_gift(&id);
+    id
+}
+
+#[test]
+fn test_recipient_accepts_and_is_paid_on_success() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let gifter = funded_gifter(&ctx);
+    let id = try_create(&ctx, &gifter, None, None).unwrap();
+    let vault = client.get_vault_state(&id).unwrap();
+    assert_eq!(vault.status, VaultStatus::Pending);
+    assert_eq!(vault.creator, ctx.creator);
+    assert_eq!(vault.failure_destination, gifter);
+    assert_eq!(client.get_funder(&id), Some(gifter.clone()));
+
+    client.accept_gift(&id);
+    assert_eq!(env.auths()[0].0, ctx.creator);
+    let events = ctx.vault_events();
+    assert_eq!(
+        events.get(0).unwrap().0,
+        (Symbol::new(env, GIFT_ACCEPTED), id, ctx.creator.clone()).into_val(env)
+    );
+    let name: Symbol = events
+        .get(1)
+        .unwrap()
+        .0
+        .get(0)
+        .unwrap()
+        .try_into_val(env)
+        .unwrap();
+    assert_eq!(name, Symbol::new(env, VAULT_ACTIVATED));
+
+    // Without a verifier the recipient self-attests.
+    ctx.advance_to_start();
+    client.submit_proof(&id, &ctx.milestone_hash());
+    client.validate_milestone(&id);
+    assert_eq!(env.auths()[0].0, ctx.creator);
+    client.release_funds(&id, &ctx.usdc_token);
+    // On top of the context's own funding of the creator.
+    ctx.assert_balances(&[(&ctx.creator, 2 * ctx.amount), (&gifter, 0)]);
+}
+
+#[test]
+fn test_failed_gift_returns_to_the_gifter() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let gifter = funded_gifter(&ctx);
+    let id = accepted(&ctx, &gifter);
+
+    ctx.advance_to_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&gifter, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_gifter_can_choose_the_success_destination() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let gifter = funded_gifter(&ctx);
+    let id = try_create(
+        &ctx,
+        &gifter,
+        Some(ctx.verifier.clone()),
+        Some(ctx.success_dest.clone()),
+    )
+    .unwrap();
+    assert_eq!(
+        client.get_gift(&id),
+        Some(Gift {
+            custom_destination: true
+        })
+    );
+
+    client.accept_gift(&id);
+    client.validate_milestone(&id);
+    assert_eq!(ctx.env.auths()[0].0, ctx.verifier);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.creator, ctx.amount)]);
+}
+
+#[test]
+fn test_gifter_reclaims_an_unaccepted_gift() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let gifter = funded_gifter(&ctx);
+    let id = try_create(&ctx, &gifter, None, None).unwrap();
+
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_accept_gift(&id),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    client.reclaim_gift(&id, &ctx.usdc_token);
+    assert_eq!(ctx.env.auths()[0].0, gifter);
+    ctx.assert_balances(&[(&gifter, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+    assert_eq!(
+        client.try_reclaim_gift(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_accepted_gift_cannot_be_cancelled() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let gifter = funded_gifter(&ctx);
+    let id = accepted(&ctx, &gifter);
+
+    assert_eq!(
+        client.try_reclaim_gift(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_cancel_vault(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(client.try_accept_gift(&id), Err(Ok(Error::InvalidStatus)));
+}
+
+#[test]
+fn test_create_gift_rejects_bad_terms() {
+    let ctx = VaultTestContext::new();
+    let gifter = funded_gifter(&ctx);
+    let result = ctx.client().try_create_gift(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidCreator)));
+
+    ctx.advance_to_start();
+    assert_eq!(
+        try_create(&ctx, &gifter, None, None),
+        Err(Error::InvalidTimestamp)
+    );
+}
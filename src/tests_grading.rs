@@ -0,0 +1,159 @@
+//! Graded completion: `validate_graded` and the proportional payout it leads to.
+
+use soroban_sdk::{vec, IntoVal, Symbol, TryIntoVal};
+
+use crate::events::{FUNDS_RELEASED, MILESTONE_GRADED};
+use crate::payout::PayoutBreakdown;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+fn create_graded_vault(ctx: &VaultTestContext) -> u32 {
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            allow_graded: true,
+            ..VaultOptions::default()
+        },
+    )
+}
+
+#[test]
+fn test_partial_grade_splits_one_settlement() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    ctx.advance_to_start();
+    let id = create_graded_vault(&ctx);
+
+    assert!(client.validate_graded(&id, &8_000));
+    assert_eq!(env.auths()[0].0, ctx.verifier);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, MILESTONE_GRADED), id, ctx.verifier.clone()).into_val(env)
+    );
+    let score: u32 = data.into_val(env);
+    assert_eq!(score, 8_000);
+    assert_eq!(client.get_grade(&id), Some(8_000));
+
+    client.release_funds(&id, &ctx.usdc_token);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    let name: Symbol = topics.get(0).unwrap().try_into_val(env).unwrap();
+    assert_eq!(name, Symbol::new(env, FUNDS_RELEASED));
+    let parts: PayoutBreakdown = data.try_into_val(env).unwrap();
+    assert_eq!(
+        parts.shares,
+        vec![
+            env,
+            (ctx.success_dest.clone(), 800_000),
+            (ctx.failure_dest.clone(), 200_000)
+        ]
+    );
+    ctx.assert_balances(&[
+        (&ctx.success_dest, 800_000),
+        (&ctx.failure_dest, 200_000),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+}
+
+#[test]
+fn test_full_grade_is_a_plain_validation() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = create_graded_vault(&ctx);
+
+    client.validate_graded(&id, &10_000);
+    assert_eq!(client.get_grade(&id), None);
+    assert!(client.get_vault_state(&id).unwrap().milestone_validated);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.failure_dest, 0)]);
+}
+
+#[test]
+fn test_zero_grade_only_allows_redirect() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = create_graded_vault(&ctx);
+
+    client.validate_graded(&id, &0);
+    assert!(!client.get_vault_state(&id).unwrap().milestone_validated);
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    client.redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, 0), (&ctx.failure_dest, ctx.amount)]);
+}
+
+#[test]
+fn test_grading_requires_opt_in() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.try_validate_graded(&id, &5_000),
+        Err(Ok(Error::GradingNotAllowed))
+    );
+    assert_eq!(
+        client.try_validate_graded(&id, &10_000),
+        Err(Ok(Error::GradingNotAllowed))
+    );
+}
+
+#[test]
+fn test_grade_is_bounded_and_final() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = create_graded_vault(&ctx);
+
+    assert_eq!(
+        client.try_validate_graded(&id, &10_001),
+        Err(Ok(Error::InvalidBps))
+    );
+    client.validate_graded(&id, &5_000);
+    assert_eq!(
+        client.try_validate_graded(&id, &6_000),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_validate_milestone(&id),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(client.get_grade(&id), Some(5_000));
+}
+
+#[test]
+fn test_grading_closes_at_the_deadline() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = create_graded_vault(&ctx);
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_validate_graded(&id, &5_000),
+        Err(Ok(Error::MilestoneExpired))
+    );
+}
@@ -0,0 +1,323 @@
+//! Group vaults: joining, activation, payouts and the unfilled-group refund path.
+
+extern crate std;
+
+use soroban_sdk::{
+    testutils::Address as _, token::StellarAssetClient, Address, Symbol, TryIntoVal, Vec,
+};
+
+use crate::events::{VAULT_CANCELLED, VAULT_EXPIRED};
+use crate::group::{GroupPayout, MAX_GROUP_SIZE};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+struct Group {
+    ctx: VaultTestContext,
+    id: u32,
+    members: std::vec::Vec<(Address, i128)>,
+}
+
+impl Group {
+    /// Group of three with stakes 100, 200 and 700; the ledger stays before the start.
+    fn new(payout: GroupPayout) -> Self {
+        let ctx = VaultTestContext::new();
+        let members: std::vec::Vec<(Address, i128)> = [100, 200, 700]
+            .into_iter()
+            .map(|stake| (Address::generate(&ctx.env), stake))
+            .collect();
+        for (member, stake) in &members {
+            ctx.mint(member, *stake);
+        }
+        let mut participants = Vec::new(&ctx.env);
+        for (member, _) in &members {
+            participants.push_back(member.clone());
+        }
+        let id = ctx.client().create_group_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &participants,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &payout,
+        );
+        Group { ctx, id, members }
+    }
+
+    fn join(&self, index: usize) -> bool {
+        let (member, stake) = &self.members[index];
+        self.ctx
+            .client()
+            .join_vault(&self.id, member, stake, &self.ctx.usdc_token)
+    }
+
+    fn join_all(&self) {
+        for index in 0..self.members.len() {
+            self.join(index);
+        }
+    }
+
+    fn status(&self) -> VaultStatus {
+        self.ctx.client().get_vault_state(&self.id).unwrap().status
+    }
+}
+
+#[test]
+fn test_vault_activates_when_last_participant_joins() {
+    let group = Group::new(GroupPayout::Proportional);
+    let client = group.ctx.client();
+    assert_eq!(group.status(), VaultStatus::Pending);
+    assert_eq!(client.get_status_count(&VaultStatus::Pending), 1);
+
+    assert!(!group.join(0));
+    assert!(!group.join(1));
+    assert_eq!(group.status(), VaultStatus::Pending);
+    assert!(group.join(2));
+
+    assert_eq!(group.status(), VaultStatus::Active);
+    assert_eq!(client.get_status_count(&VaultStatus::Pending), 0);
+    assert_eq!(client.get_vault_state(&group.id).unwrap().amount, 1_000);
+    group
+        .ctx
+        .assert_balances(&[(&group.ctx.contract_id, 1_000)]);
+}
+
+#[test]
+fn test_proportional_success_returns_each_contribution() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    group.join_all();
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&group.id);
+    ctx.client().release_funds(&group.id, &ctx.usdc_token);
+
+    for (member, stake) in &group.members {
+        ctx.assert_balances(&[(member, *stake)]);
+    }
+    ctx.assert_balances(&[(&ctx.success_dest, 0), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_shared_destination_success_takes_the_pot() {
+    let group = Group::new(GroupPayout::SharedDestination);
+    let ctx = &group.ctx;
+    group.join_all();
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&group.id);
+    ctx.client().release_funds(&group.id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[(&ctx.success_dest, 1_000), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_failed_group_pot_goes_to_failure_destination() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    group.join_all();
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&group.id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[(&ctx.failure_dest, 1_000), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_cancelled_group_refunds_participants() {
+    let group = Group::new(GroupPayout::SharedDestination);
+    let ctx = &group.ctx;
+    group.join_all();
+    ctx.client().cancel_vault(&group.id, &ctx.usdc_token);
+
+    for (member, stake) in &group.members {
+        ctx.assert_balances(&[(member, *stake)]);
+    }
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_unfilled_group_refunds_joiners_after_start() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    let client = ctx.client();
+    group.join(0);
+    group.join(2);
+
+    assert_eq!(
+        client.try_reclaim_group_stakes(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    ctx.advance_to_start();
+    let (late, stake) = &group.members[1];
+    assert_eq!(
+        client.try_join_vault(&group.id, late, stake, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+
+    client.reclaim_group_stakes(&group.id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    for (i, name) in [VAULT_EXPIRED, VAULT_CANCELLED].iter().enumerate() {
+        let topic: Symbol = events
+            .get(i as u32)
+            .unwrap()
+            .0
+            .get(0)
+            .unwrap()
+            .try_into_val(&ctx.env)
+            .unwrap();
+        assert_eq!(topic, Symbol::new(&ctx.env, name));
+    }
+    for (member, stake) in &group.members {
+        ctx.assert_balances(&[(member, *stake)]);
+    }
+    ctx.assert_balances(&[(&ctx.contract_id, 0)]);
+    assert_eq!(group.status(), VaultStatus::Cancelled);
+    assert_eq!(
+        client.try_reclaim_group_stakes(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_group_nobody_joined_can_still_be_closed() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    ctx.advance_to_start();
+    ctx.client()
+        .reclaim_group_stakes(&group.id, &ctx.usdc_token);
+    assert_eq!(group.status(), VaultStatus::Cancelled);
+}
+
+#[test]
+fn test_active_group_cannot_be_reclaimed() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    group.join_all();
+    ctx.advance_to_start();
+    assert_eq!(
+        ctx.client()
+            .try_reclaim_group_stakes(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_join_rejects_outsiders_and_second_joins() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    let client = ctx.client();
+    let outsider = Address::generate(&ctx.env);
+    ctx.mint(&outsider, 100);
+    assert_eq!(
+        client.try_join_vault(&group.id, &outsider, &100, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    ctx.mint(&group.members[0].0, 100);
+    group.join(0);
+    assert_eq!(
+        client.try_join_vault(&group.id, &group.members[0].0, &100, &ctx.usdc_token),
+        Err(Ok(Error::AlreadyJoined))
+    );
+    assert_eq!(
+        client.try_join_vault(&group.id, &group.members[1].0, &0, &ctx.usdc_token),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_join_vault(&group.id, &group.members[1].0, &201, &ctx.usdc_token),
+        Err(Ok(Error::InsufficientBalance))
+    );
+}
+
+#[test]
+fn test_join_must_stake_the_vault_token() {
+    let group = Group::new(GroupPayout::SharedDestination);
+    let ctx = &group.ctx;
+    let (member, stake) = &group.members[0];
+    let junk = ctx
+        .env
+        .register_stellar_asset_contract_v2(member.clone())
+        .address();
+    StellarAssetClient::new(&ctx.env, &junk).mint(member, stake);
+
+    assert_eq!(
+        ctx.client().try_join_vault(&group.id, member, stake, &junk),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert!(ctx
+        .client()
+        .get_group(&group.id)
+        .unwrap()
+        .contributions
+        .is_empty());
+}
+
+#[test]
+fn test_pending_group_cannot_be_validated_or_settled() {
+    let group = Group::new(GroupPayout::Proportional);
+    let ctx = &group.ctx;
+    let client = ctx.client();
+    group.join(0);
+
+    assert_eq!(
+        client.try_validate_milestone(&group.id),
+        Err(Ok(Error::VaultNotActive))
+    );
+    assert_eq!(
+        client.try_cancel_vault(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::VaultNotActive))
+    );
+    ctx.advance_past_deadline();
+    assert_eq!(
+        client.try_release_funds(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::VaultNotActive))
+    );
+    assert_eq!(
+        client.try_redirect_funds(&group.id, &ctx.usdc_token),
+        Err(Ok(Error::VaultNotActive))
+    );
+}
+
+#[test]
+fn test_create_group_rejects_bad_participant_lists() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let try_create = |participants: &Vec<Address>| {
+        client.try_create_group_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            participants,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &None,
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &GroupPayout::Proportional,
+        )
+    };
+
+    assert_eq!(
+        try_create(&Vec::new(&ctx.env)),
+        Err(Ok(Error::InvalidParticipants))
+    );
+
+    let member = Address::generate(&ctx.env);
+    let mut duplicated = Vec::new(&ctx.env);
+    duplicated.push_back(member.clone());
+    duplicated.push_back(Address::generate(&ctx.env));
+    duplicated.push_back(member);
+    assert_eq!(try_create(&duplicated), Err(Ok(Error::InvalidParticipants)));
+
+    let mut oversized = Vec::new(&ctx.env);
+    for _ in 0..=MAX_GROUP_SIZE {
+        oversized.push_back(Address::generate(&ctx.env));
+    }
+    assert_eq!(try_create(&oversized), Err(Ok(Error::InvalidParticipants)));
+
+    ctx.advance_to_start();
+    let mut one = Vec::new(&ctx.env);
+    one.push_back(Address::generate(&ctx.env));
+    assert_eq!(try_create(&one), Err(Ok(Error::InvalidTimestamp)));
+}
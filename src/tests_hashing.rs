@@ -0,0 +1,221 @@
+//! Hash algorithm selection: known vectors and the reveal and bounty flows under both.
+
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{vec, Address, Bytes, BytesN, IntoVal, Symbol, Val};
+
+use crate::bounty::claim_commitment;
+use crate::hashing::{digest, HashAlg};
+use crate::reveal::commitment;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+const PREIMAGE: &[u8] = b"abc";
+
+/// `sha256("abc")`.
+const SHA256_ABC: [u8; 32] = [
+    0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+    0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+];
+
+/// `keccak256("abc")`.
+const KECCAK256_ABC: [u8; 32] = [
+    0x4e, 0x03, 0x65, 0x7a, 0xea, 0x45, 0xa9, 0x4f, 0xc7, 0xd4, 0x7b, 0xa8, 0x26, 0xc8, 0xd6, 0x67,
+    0xc0, 0xd1, 0xe6, 0xe3, 0x3a, 0x64, 0xa0, 0x36, 0xec, 0x44, 0xf5, 0x8f, 0xa1, 0x2d, 0x6c, 0x45,
+];
+
+const ALGS: [HashAlg; 2] = [HashAlg::Sha256, HashAlg::Keccak256];
+
+fn preimage(ctx: &VaultTestContext) -> Bytes {
+    Bytes::from_slice(&ctx.env, PREIMAGE)
+}
+
+fn known_digest(ctx: &VaultTestContext, alg: HashAlg) -> BytesN<32> {
+    match alg {
+        HashAlg::Sha256 => BytesN::from_array(&ctx.env, &SHA256_ABC),
+        HashAlg::Keccak256 => BytesN::from_array(&ctx.env, &KECCAK256_ABC),
+    }
+}
+
+fn create_bounty(
+    ctx: &VaultTestContext,
+    hash: &BytesN<32>,
+    commit_reveal: bool,
+    alg: HashAlg,
+) -> u32 {
+    ctx.client().create_bounty(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        hash,
+        &commit_reveal,
+        &alg,
+    )
+}
+
+#[test]
+fn test_digest_matches_known_vectors() {
+    let ctx = VaultTestContext::new();
+    for alg in ALGS {
+        assert_eq!(
+            digest(&ctx.env, alg, &preimage(&ctx)),
+            known_digest(&ctx, alg)
+        );
+    }
+}
+
+#[test]
+fn test_bounty_claims_under_both_algorithms() {
+    for alg in ALGS {
+        let ctx = VaultTestContext::new();
+        let client = ctx.client();
+        let id = create_bounty(&ctx, &known_digest(&ctx, alg), false, alg);
+        assert_eq!(client.get_hash_alg(&id), alg);
+        let solver = Address::generate(&ctx.env);
+
+        ctx.advance_to_start();
+        client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token);
+        assert_eq!(
+            client.get_vault_state(&id).unwrap().status,
+            VaultStatus::Completed
+        );
+    }
+}
+
+#[test]
+fn test_bounty_rejects_a_digest_of_the_other_algorithm() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_bounty(
+        &ctx,
+        &known_digest(&ctx, HashAlg::Sha256),
+        false,
+        HashAlg::Keccak256,
+    );
+
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_claim_bounty(
+            &id,
+            &Address::generate(&ctx.env),
+            &preimage(&ctx),
+            &ctx.usdc_token
+        ),
+        Err(Ok(Error::InvalidPreimage))
+    );
+}
+
+#[test]
+fn test_commit_reveal_bounty_under_both_algorithms() {
+    for alg in ALGS {
+        let ctx = VaultTestContext::new();
+        let client = ctx.client();
+        let id = create_bounty(&ctx, &known_digest(&ctx, alg), true, alg);
+        let solver = Address::generate(&ctx.env);
+        ctx.advance_to_start();
+
+        let other = match alg {
+            HashAlg::Sha256 => HashAlg::Keccak256,
+            HashAlg::Keccak256 => HashAlg::Sha256,
+        };
+        let wrong = claim_commitment(&ctx.env, other, &preimage(&ctx), &solver);
+        client.commit_bounty_claim(&id, &solver, &wrong);
+        let sequence = ctx.env.ledger().sequence();
+        ctx.env.ledger().set_sequence_number(sequence + 1);
+        assert_eq!(
+            client.try_claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token),
+            Err(Ok(Error::InvalidPreimage))
+        );
+
+        let right = claim_commitment(&ctx.env, alg, &preimage(&ctx), &solver);
+        client.commit_bounty_claim(&id, &solver, &right);
+        ctx.env.ledger().set_sequence_number(sequence + 2);
+        client.claim_bounty(&id, &solver, &preimage(&ctx), &ctx.usdc_token);
+        ctx.assert_balances(&[(&solver, ctx.amount)]);
+    }
+}
+
+#[test]
+fn test_reveal_under_both_algorithms() {
+    for alg in ALGS {
+        let ctx = VaultTestContext::new();
+        let client = ctx.client();
+        let salt = BytesN::from_array(&ctx.env, &[7u8; 32]);
+        let id = client.create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &commitment(&ctx.env, alg, &preimage(&ctx), &salt),
+            &None,
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                hash_alg: alg,
+                ..VaultOptions::default()
+            },
+        );
+
+        // The vault stays linked to `hash(description)`, here the known vector.
+        client.reveal_milestone(&id, &preimage(&ctx), &salt);
+        assert_eq!(
+            client.get_revealed_description_hash(&id),
+            Some(known_digest(&ctx, alg))
+        );
+    }
+}
+
+#[test]
+fn test_reveal_with_the_wrong_algorithm_mismatches() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let salt = BytesN::from_array(&ctx.env, &[7u8; 32]);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &commitment(&ctx.env, HashAlg::Keccak256, &preimage(&ctx), &salt),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    );
+    assert_eq!(client.get_hash_alg(&id), HashAlg::Sha256);
+    assert_eq!(
+        client.try_reveal_milestone(&id, &preimage(&ctx), &salt),
+        Err(Ok(Error::RevealMismatch))
+    );
+}
+
+#[test]
+fn test_unknown_discriminant_is_rejected() {
+    let ctx = VaultTestContext::new();
+    let env = &ctx.env;
+    let invoke = |discriminant: u32| {
+        let args: soroban_sdk::Vec<Val> = vec![
+            env,
+            ctx.usdc_token.into_val(env),
+            ctx.creator.into_val(env),
+            ctx.amount.into_val(env),
+            ctx.start_timestamp.into_val(env),
+            ctx.end_timestamp.into_val(env),
+            known_digest(&ctx, HashAlg::Keccak256).into_val(env),
+            false.into_val(env),
+            discriminant.into_val(env),
+        ];
+        env.try_invoke_contract::<u32, Error>(
+            &ctx.contract_id,
+            &Symbol::new(env, "create_bounty"),
+            args,
+        )
+    };
+
+    assert!(invoke(2).is_err());
+    // The same call with a known discriminant goes through.
+    let id = invoke(HashAlg::Keccak256 as u32).unwrap().unwrap();
+    assert_eq!(ctx.client().get_hash_alg(&id), HashAlg::Keccak256);
+}
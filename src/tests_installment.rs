@@ -0,0 +1,226 @@
+//! Installment vaults: scheduled deposits, the overdue check and settlement of partial
+//! balances.
+
+use soroban_sdk::{IntoVal, Symbol, TryIntoVal};
+
+use crate::events::{FUNDS_REDIRECTED, INSTALLMENT_MISSED, INSTALLMENT_PAID};
+use crate::installment::{InstallmentSchedule, MAX_INSTALLMENTS};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+const DEPOSIT: i128 = 250_000;
+const COUNT: u32 = 4;
+const INTERVAL: u64 = 200;
+const GRACE: u64 = 50;
+
+fn schedule() -> InstallmentSchedule {
+    InstallmentSchedule {
+        count: COUNT,
+        amount: DEPOSIT,
+        interval: INTERVAL,
+        grace_period: GRACE,
+    }
+}
+
+fn try_create(ctx: &VaultTestContext, installments: InstallmentSchedule) -> Result<u32, Error> {
+    let first_deposit = installments.amount;
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &first_deposit,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                installments,
+                ..VaultOptions::default()
+            },
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext) -> u32 {
+    try_create(ctx, schedule()).unwrap()
+}
+
+/// When installment `index` falls due.
+fn due(ctx: &VaultTestContext, index: u32) -> u64 {
+    ctx.start_timestamp + INTERVAL * index as u64
+}
+
+#[test]
+fn test_paying_every_installment_then_validating_releases_everything() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let id = create(&ctx);
+    ctx.assert_balances(&[(&ctx.contract_id, DEPOSIT)]);
+
+    for index in 1..COUNT {
+        ctx.advance_to(due(&ctx, index) + GRACE - 1);
+        assert_eq!(client.pay_installment(&id, &ctx.usdc_token), index);
+        let (topics, data) = ctx.vault_events().get(0).unwrap();
+        assert_eq!(
+            topics,
+            (Symbol::new(env, INSTALLMENT_PAID), id, ctx.creator.clone()).into_val(env)
+        );
+        let payload: (u32, i128) = data.try_into_val(env).unwrap();
+        assert_eq!(payload, (index, DEPOSIT));
+    }
+    let state = client.get_installments(&id).unwrap();
+    assert_eq!(state.paid, COUNT);
+    assert_eq!(client.get_next_installment_deadline(&id), None);
+    assert_eq!(client.get_vault_state(&id).unwrap().amount, ctx.amount);
+    assert_eq!(
+        client.try_pay_installment(&id, &ctx.usdc_token),
+        Err(Ok(Error::PeriodAlreadySettled))
+    );
+
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_release_waits_for_the_last_installment() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    client.validate_milestone(&id);
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    for _ in 1..COUNT {
+        client.pay_installment(&id, &ctx.usdc_token);
+    }
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+}
+
+#[test]
+fn test_overdue_installment_fails_the_vault_with_what_accumulated() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let id = create(&ctx);
+    client.pay_installment(&id, &ctx.usdc_token);
+
+    let closes = due(&ctx, 2) + GRACE;
+    assert_eq!(client.get_next_installment_deadline(&id), Some(closes - 1));
+    ctx.advance_to(closes - 1);
+    assert_eq!(
+        client.try_check_installment(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    ctx.advance_to(closes);
+    assert_eq!(
+        client.try_pay_installment(&id, &ctx.usdc_token),
+        Err(Ok(Error::MilestoneExpired))
+    );
+
+    client.check_installment(&id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(env, INSTALLMENT_MISSED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(env)
+    );
+    let index: u32 = data.try_into_val(env).unwrap();
+    assert_eq!(index, 2);
+    let name: Symbol = events
+        .get(1)
+        .unwrap()
+        .0
+        .get(0)
+        .unwrap()
+        .try_into_val(env)
+        .unwrap();
+    assert_eq!(name, Symbol::new(env, FUNDS_REDIRECTED));
+
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, 2 * DEPOSIT),
+        (&ctx.creator, ctx.amount - 2 * DEPOSIT),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Failed
+    );
+}
+
+#[test]
+fn test_cancel_refunds_the_installments_paid_so_far() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+    client.pay_installment(&id, &ctx.usdc_token);
+
+    client.cancel_vault(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        client.try_pay_installment(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_create_rejects_unworkable_schedules() {
+    let ctx = VaultTestContext::new();
+    for bad in [
+        InstallmentSchedule {
+            count: MAX_INSTALLMENTS + 1,
+            ..schedule()
+        },
+        InstallmentSchedule {
+            interval: 0,
+            ..schedule()
+        },
+        // The last window would close after the vault's deadline.
+        InstallmentSchedule {
+            grace_period: ctx.end_timestamp,
+            ..schedule()
+        },
+    ] {
+        assert_eq!(try_create(&ctx, bad), Err(Error::InvalidPeriod));
+    }
+
+    let result = ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            installments: schedule(),
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_installment_entrypoints_reject_other_vaults() {
+    let ctx = VaultTestContext::new();
+    let id = ctx.create_default_vault();
+    assert_eq!(
+        ctx.client().try_pay_installment(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(ctx.client().get_installments(&id), None);
+}
@@ -0,0 +1,341 @@
+//! Randomized lifecycle tests checked against a simple model.
+//!
+//! Each run drives a seeded sequence of operations (create, validate, release, redirect,
+//! cancel, clock warps) against the contract while a plain-Rust model predicts the outcome
+//! of every call. After every step we assert that:
+//!
+//! - token conservation holds: the contract's balance equals the escrow of all Active
+//!   vaults;
+//! - no vault is ever paid out twice: every destination holds exactly what the model says;
+//! - status transitions follow the allowed graph; and
+//! - the vault counter, per-status counters and raw vault records match a recomputation
+//!   from storage.
+//!
+//! Failures report the seed and step. Set `DISCIPLR_INVARIANT_SEED=<seed>` to replay a
+//! single seed.
+
+extern crate std;
+
+use std::vec::Vec;
+
+use soroban_sdk::{
+    testutils::{Address as _, EnvTestConfig},
+    Address, Env,
+};
+
+use crate::testutils::VaultTestContext;
+use crate::{is_allowed_transition, DataKey, Error, ProductivityVault, VaultOptions, VaultStatus};
+
+const SEEDS: u64 = 8;
+const STEPS: u32 = 80;
+
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Avoid the all-zero xorshift state.
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+/// Model of a single vault plus the parties that are unique to it, so every balance can be
+/// attributed to exactly one vault.
+struct ModelVault {
+    amount: i128,
+    end_timestamp: u64,
+    status: VaultStatus,
+    validated: bool,
+    creator: Address,
+    success_dest: Address,
+    failure_dest: Address,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Create,
+    Validate,
+    Release,
+    Redirect,
+    Cancel,
+    Warp,
+}
+
+struct Harness {
+    ctx: VaultTestContext,
+    rng: Rng,
+    vaults: Vec<ModelVault>,
+    now: u64,
+    seed: u64,
+    step: u32,
+}
+
+impl Harness {
+    fn new(seed: u64) -> Self {
+        // Hundreds of ledger entries per run; skip the snapshot file.
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        let ctx = VaultTestContext::with_env(env);
+        ctx.env.cost_estimate().budget().reset_unlimited();
+        ctx.advance_to(0);
+        Harness {
+            ctx,
+            rng: Rng::new(seed),
+            vaults: Vec::new(),
+            now: 0,
+            seed,
+            step: 0,
+        }
+    }
+
+    fn check_result(
+        &self,
+        op: Op,
+        id: u32,
+        result: Result<(), Error>,
+        expected: Result<(), Error>,
+    ) {
+        assert_eq!(
+            result, expected,
+            "seed={} step={} op={:?} vault={}",
+            self.seed, self.step, op, id
+        );
+    }
+
+    fn expected_for(&self, op: Op, id: u32) -> Result<(), Error> {
+        let Some(v) = self.vaults.get(id as usize) else {
+            return Err(Error::VaultNotFound);
+        };
+        let active = v.status == VaultStatus::Active;
+        let deadline_reached = self.now >= v.end_timestamp;
+        match op {
+            Op::Validate if !active => Err(Error::VaultNotActive),
+            Op::Validate if deadline_reached => Err(Error::MilestoneExpired),
+            Op::Release | Op::Redirect | Op::Cancel if !active => Err(Error::AlreadySettled),
+            Op::Release if !v.validated && !deadline_reached => Err(Error::NotAuthorized),
+            Op::Redirect if !deadline_reached => Err(Error::InvalidTimestamp),
+            Op::Redirect if v.validated => Err(Error::NotAuthorized),
+            _ => Ok(()),
+        }
+    }
+
+    fn apply(&mut self, op: Op) {
+        let client = self.ctx.client();
+        let token = self.ctx.usdc_token.clone();
+        match op {
+            Op::Create => {
+                let env = &self.ctx.env;
+                let amount = 1 + self.rng.below(5_000_000) as i128;
+                let end_timestamp = self.now + 1 + self.rng.below(500);
+                let verifier = (self.rng.below(2) == 0).then(|| Address::generate(env));
+                let model = ModelVault {
+                    amount,
+                    end_timestamp,
+                    status: VaultStatus::Active,
+                    validated: false,
+                    creator: Address::generate(env),
+                    success_dest: Address::generate(env),
+                    failure_dest: Address::generate(env),
+                };
+                self.ctx.mint(&model.creator, amount);
+                let id = client.create_vault(
+                    &token,
+                    &model.creator,
+                    &amount,
+                    &self.now,
+                    &end_timestamp,
+                    &self.ctx.milestone_hash(),
+                    &verifier,
+                    &model.success_dest,
+                    &model.failure_dest,
+                    &VaultOptions::default(),
+                );
+                assert_eq!(id as usize, self.vaults.len(), "seed={}", self.seed);
+                self.vaults.push(model);
+            }
+            Op::Warp => {
+                self.now += self.rng.below(300);
+                self.ctx.advance_to(self.now);
+            }
+            Op::Validate | Op::Release | Op::Redirect | Op::Cancel => {
+                // Occasionally target an id that does not exist yet.
+                let id = self.rng.below(self.vaults.len() as u64 + 1) as u32;
+                let expected = self.expected_for(op, id);
+                let result = match op {
+                    Op::Validate => client.try_validate_milestone(&id).map(|_| ()),
+                    Op::Release => client.try_release_funds(&id, &token).map(|_| ()),
+                    Op::Redirect => client.try_redirect_funds(&id, &token).map(|_| ()),
+                    _ => client.try_cancel_vault(&id, &token).map(|_| ()),
+                }
+                .map_err(|e| e.expect("contract error"));
+                self.check_result(op, id, result, expected);
+                if result.is_ok() {
+                    let v = &mut self.vaults[id as usize];
+                    match op {
+                        Op::Validate => v.validated = true,
+                        Op::Release => v.status = VaultStatus::Completed,
+                        Op::Redirect => v.status = VaultStatus::Failed,
+                        _ => v.status = VaultStatus::Cancelled,
+                    }
+                }
+            }
+        }
+    }
+
+    fn random_op(&mut self) -> Op {
+        match self.rng.below(10) {
+            0 | 1 => Op::Create,
+            2 => Op::Validate,
+            3 | 4 => Op::Release,
+            5 | 6 => Op::Redirect,
+            7 => Op::Cancel,
+            _ => Op::Warp,
+        }
+    }
+
+    fn raw_vault(&self, id: u32) -> Option<ProductivityVault> {
+        let env = &self.ctx.env;
+        env.as_contract(&self.ctx.contract_id, || {
+            env.storage().instance().get(&DataKey::Vault(id))
+        })
+    }
+
+    fn raw_status_count(&self, status: VaultStatus) -> u32 {
+        let env = &self.ctx.env;
+        env.as_contract(&self.ctx.contract_id, || {
+            env.storage()
+                .instance()
+                .get(&DataKey::StatusCount(status))
+                .unwrap_or(0)
+        })
+    }
+
+    fn raw_vault_count(&self) -> u32 {
+        let env = &self.ctx.env;
+        env.as_contract(&self.ctx.contract_id, || {
+            env.storage()
+                .instance()
+                .get(&DataKey::VaultCount)
+                .unwrap_or(0)
+        })
+    }
+
+    fn check_invariants(&self, previous: &[VaultStatus]) {
+        let ctx = &self.ctx;
+        let tag = || std::format!("seed={} step={}", self.seed, self.step);
+
+        // Counter and raw records match the model.
+        assert_eq!(
+            self.raw_vault_count() as usize,
+            self.vaults.len(),
+            "{}",
+            tag()
+        );
+        assert!(
+            self.raw_vault(self.vaults.len() as u32).is_none(),
+            "{}",
+            tag()
+        );
+
+        let mut escrowed: i128 = 0;
+        let mut stored_statuses = Vec::new();
+        for (id, model) in self.vaults.iter().enumerate() {
+            let stored = self.raw_vault(id as u32).expect("vault record missing");
+            stored_statuses.push(stored.status);
+            assert_eq!(stored.status, model.status, "{} vault={}", tag(), id);
+            assert_eq!(stored.amount, model.amount, "{} vault={}", tag(), id);
+            assert_eq!(
+                stored.milestone_validated,
+                model.validated,
+                "{} vault={}",
+                tag(),
+                id
+            );
+
+            if let Some(prev) = previous.get(id) {
+                assert!(
+                    *prev == stored.status || is_allowed_transition(*prev, stored.status),
+                    "{} vault={} illegal transition {:?} -> {:?}",
+                    tag(),
+                    id,
+                    prev,
+                    stored.status
+                );
+            }
+
+            // Exactly one payout (or none while Active) per vault.
+            let paid = |status| {
+                if model.status == status {
+                    model.amount
+                } else {
+                    0
+                }
+            };
+            ctx.assert_balances(&[
+                (&model.success_dest, paid(VaultStatus::Completed)),
+                (&model.failure_dest, paid(VaultStatus::Failed)),
+                (&model.creator, paid(VaultStatus::Cancelled)),
+            ]);
+
+            if model.status == VaultStatus::Active {
+                escrowed += model.amount;
+            }
+        }
+
+        // Per-status counters match a recount of the raw records.
+        for status in [
+            VaultStatus::Pending,
+            VaultStatus::Active,
+            VaultStatus::Completed,
+            VaultStatus::Failed,
+            VaultStatus::Cancelled,
+        ] {
+            let recount = stored_statuses.iter().filter(|s| **s == status).count() as u32;
+            assert_eq!(
+                self.raw_status_count(status),
+                recount,
+                "{} {:?}",
+                tag(),
+                status
+            );
+        }
+
+        // Token conservation.
+        ctx.assert_balances(&[(&ctx.contract_id, escrowed)]);
+    }
+
+    fn run(mut self) {
+        for step in 0..STEPS {
+            self.step = step;
+            let previous: Vec<VaultStatus> = self.vaults.iter().map(|v| v.status).collect();
+            let op = self.random_op();
+            self.apply(op);
+            self.check_invariants(&previous);
+        }
+    }
+}
+
+fn seeds() -> Vec<u64> {
+    match std::env::var("DISCIPLR_INVARIANT_SEED") {
+        Ok(seed) => std::vec![seed.parse().expect("DISCIPLR_INVARIANT_SEED must be a u64")],
+        Err(_) => (1..=SEEDS).collect(),
+    }
+}
+
+#[test]
+fn test_lifecycle_invariants_hold_for_random_sequences() {
+    for seed in seeds() {
+        Harness::new(seed).run();
+    }
+}
@@ -0,0 +1,236 @@
+//! Joint vaults: per-participant milestones and all-or-nothing settlement.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, IntoVal, Symbol, Vec};
+
+use crate::events::{FUNDS_REDIRECTED, JOINT_FAILED, PARTICIPANT_VALIDATED};
+use crate::group::GroupPayout;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+const STAKES: [i128; 2] = [300, 700];
+
+struct Joint {
+    ctx: VaultTestContext,
+    id: u32,
+    members: [Address; 2],
+}
+
+impl Joint {
+    /// Couple staking 300 and 700, each with their own milestone; funded and started.
+    fn new() -> Self {
+        let ctx = VaultTestContext::new();
+        let members: [Address; 2] = core::array::from_fn(|_| Address::generate(&ctx.env));
+        let mut list = Vec::new(&ctx.env);
+        for (i, member) in members.iter().enumerate() {
+            ctx.mint(member, STAKES[i]);
+            list.push_back((
+                member.clone(),
+                STAKES[i],
+                BytesN::from_array(&ctx.env, &[i as u8 + 1; 32]),
+            ));
+        }
+        let id = ctx.client().create_joint_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &list,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &GroupPayout::Proportional,
+        );
+        let joint = Joint { ctx, id, members };
+        for (i, member) in joint.members.iter().enumerate() {
+            joint
+                .ctx
+                .client()
+                .join_vault(&id, member, &STAKES[i], &joint.ctx.usdc_token);
+        }
+        joint.ctx.advance_to_start();
+        joint
+    }
+
+    fn validate(&self, index: usize) -> bool {
+        self.ctx
+            .client()
+            .validate_participant(&self.id, &self.members[index])
+    }
+}
+
+#[test]
+fn test_release_needs_every_participant() {
+    let joint = Joint::new();
+    let ctx = &joint.ctx;
+    let client = ctx.client();
+    assert_eq!(
+        client.get_vault_state(&joint.id).unwrap().status,
+        VaultStatus::Active
+    );
+
+    assert!(!joint.validate(1));
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, PARTICIPANT_VALIDATED),
+            joint.id,
+            ctx.verifier.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let participant: Address = data.into_val(&ctx.env);
+    assert_eq!(participant, joint.members[1]);
+    assert_eq!(
+        client.try_release_funds(&joint.id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_validate_participant(&joint.id, &joint.members[1]),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    assert!(joint.validate(0));
+    assert!(
+        client
+            .get_vault_state(&joint.id)
+            .unwrap()
+            .milestone_validated
+    );
+    assert!(client
+        .get_joint(&joint.id)
+        .unwrap()
+        .iter()
+        .all(|m| m.validated));
+    client.release_funds(&joint.id, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&joint.members[0], STAKES[0]),
+        (&joint.members[1], STAKES[1]),
+        (&ctx.contract_id, 0),
+    ]);
+}
+
+#[test]
+fn test_partial_validation_loses_everything_at_expiry() {
+    let joint = Joint::new();
+    let ctx = &joint.ctx;
+    let client = ctx.client();
+    joint.validate(0);
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_validate_participant(&joint.id, &joint.members[1]),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    // A plain vault would now release on a verifier timeout; a joint vault cannot.
+    assert_eq!(
+        client.try_release_funds(&joint.id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    client.redirect_funds(&joint.id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, JOINT_FAILED),
+            joint.id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let data: (Vec<Address>, Vec<Address>) = data.into_val(&ctx.env);
+    assert_eq!(
+        data,
+        (
+            vec![&ctx.env, joint.members[0].clone()],
+            vec![&ctx.env, joint.members[1].clone()]
+        )
+    );
+    let (topics, _) = events.get(1).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, FUNDS_REDIRECTED),
+            joint.id,
+            ctx.failure_dest.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, STAKES[0] + STAKES[1]),
+        (&joint.members[0], 0),
+        (&joint.members[1], 0),
+    ]);
+}
+
+#[test]
+fn test_participants_join_with_their_own_stake() {
+    let ctx = VaultTestContext::new();
+    let member = Address::generate(&ctx.env);
+    ctx.mint(&member, 1_000);
+    let list = vec![&ctx.env, (member.clone(), 500_i128, ctx.milestone_hash())];
+    let id = ctx.client().create_joint_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &list,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &GroupPayout::SharedDestination,
+    );
+    assert_eq!(
+        ctx.client()
+            .try_join_vault(&id, &member, &400, &ctx.usdc_token),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert!(ctx.client().join_vault(&id, &member, &500, &ctx.usdc_token));
+
+    // Without a verifier each participant validates themselves, not the shared milestone.
+    ctx.advance_to_start();
+    assert_eq!(
+        ctx.client().try_validate_milestone(&id),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert!(ctx.client().validate_participant(&id, &member));
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, 500), (&member, 500)]);
+}
+
+#[test]
+fn test_create_joint_rejects_bad_members() {
+    let ctx = VaultTestContext::new();
+    let member = Address::generate(&ctx.env);
+    for (list, error) in [
+        (
+            vec![&ctx.env, (member.clone(), 0_i128, ctx.milestone_hash())],
+            Error::InvalidAmount,
+        ),
+        (
+            vec![
+                &ctx.env,
+                (member.clone(), 1_i128, ctx.milestone_hash()),
+                (member.clone(), 1_i128, ctx.milestone_hash()),
+            ],
+            Error::InvalidParticipants,
+        ),
+    ] {
+        assert_eq!(
+            ctx.client().try_create_joint_vault(
+                &ctx.usdc_token,
+                &ctx.creator,
+                &list,
+                &ctx.start_timestamp,
+                &ctx.end_timestamp,
+                &None,
+                &ctx.success_dest,
+                &ctx.failure_dest,
+                &GroupPayout::Proportional,
+            ),
+            Err(Ok(error))
+        );
+    }
+    assert_eq!(ctx.client().get_joint(&0), None);
+}
@@ -0,0 +1,259 @@
+//! Leaderboards: ranking rules of `place` and the boards kept by settlements.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Vec};
+
+use crate::config::Config;
+use crate::group::GroupPayout;
+use crate::leaderboard::{place, LeaderboardKind, MAX_LEADERBOARD};
+use crate::recurring::RecurringSchedule;
+use crate::testutils::VaultTestContext;
+use crate::VaultOptions;
+
+/// Settle a fresh verified vault of `creator` staking `amount`: released when
+/// `complete`, redirected otherwise.
+fn settle(ctx: &VaultTestContext, creator: &Address, amount: i128, complete: bool) {
+    let client = ctx.client();
+    ctx.advance_to_start();
+    ctx.mint(creator, amount);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        creator,
+        &amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    );
+    if complete {
+        client.validate_milestone(&id);
+        client.release_funds(&id, &ctx.usdc_token);
+    } else {
+        ctx.advance_to_deadline();
+        client.redirect_funds(&id, &ctx.usdc_token);
+    }
+}
+
+#[test]
+fn test_settlements_rank_creators() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let other = Address::generate(&ctx.env);
+
+    settle(&ctx, &ctx.creator, 1_000, true);
+    settle(&ctx, &other, 5_000, true);
+    settle(&ctx, &ctx.creator, 1_000, true);
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::CompletedStake, &10),
+        vec![
+            &ctx.env,
+            (other.clone(), 5_000),
+            (ctx.creator.clone(), 2_000)
+        ]
+    );
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::Streak, &10),
+        vec![&ctx.env, (ctx.creator.clone(), 2), (other.clone(), 1)]
+    );
+    assert_eq!(client.get_completed_stake(&ctx.creator), 2_000);
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::Streak, &1),
+        vec![&ctx.env, (ctx.creator.clone(), 2)]
+    );
+}
+
+#[test]
+fn test_failure_drops_the_streak_entry_only() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+
+    settle(&ctx, &ctx.creator, 1_000, true);
+    settle(&ctx, &ctx.creator, 3_000, false);
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::Streak, &10),
+        Vec::new(&ctx.env)
+    );
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::CompletedStake, &10),
+        vec![&ctx.env, (ctx.creator.clone(), 1_000)]
+    );
+}
+
+#[test]
+fn test_self_attested_and_sub_minimum_vaults_are_not_ranked() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_vault_no_verifier();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+
+    ctx.mint(&ctx.creator, ctx.amount);
+    let small = ctx.create_default_vault();
+    client.set_config(&Config {
+        min_amount: ctx.amount + 1,
+        ..Config::default()
+    });
+    client.validate_milestone(&small);
+    client.release_funds(&small, &ctx.usdc_token);
+    assert_eq!(client.get_completed_stake(&ctx.creator), 0);
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::Streak, &10),
+        Vec::new(&ctx.env)
+    );
+}
+
+#[test]
+fn test_only_the_creators_own_stake_is_credited() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+
+    // A won wager: the challenger's matching stake is not the creator's.
+    let challenger = Address::generate(env);
+    ctx.mint(&challenger, ctx.amount);
+    let wager = client.create_wager(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &challenger,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &ctx.verifier,
+    );
+    client.accept_wager(&wager, &ctx.usdc_token);
+    ctx.advance_to_start();
+    client.validate_milestone(&wager);
+    client.release_funds(&wager, &ctx.usdc_token);
+    assert_eq!(client.get_completed_stake(&ctx.creator), ctx.amount);
+
+    // A group vault: only the creator's own contribution.
+    let other = Address::generate(env);
+    ctx.mint(&ctx.creator, 300);
+    ctx.mint(&other, 700);
+    let group = client.create_group_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &vec![env, ctx.creator.clone(), other.clone()],
+        &(ctx.start_timestamp + 1),
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &GroupPayout::Proportional,
+    );
+    client.join_vault(&group, &ctx.creator, &300, &ctx.usdc_token);
+    client.join_vault(&group, &other, &700, &ctx.usdc_token);
+    ctx.advance_to(ctx.start_timestamp + 1);
+    client.validate_milestone(&group);
+    client.release_funds(&group, &ctx.usdc_token);
+    assert_eq!(client.get_completed_stake(&ctx.creator), ctx.amount + 300);
+    assert_eq!(client.get_completed_stake(&other), 0);
+}
+
+#[test]
+fn test_recurring_vault_credits_its_validated_slices() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = client.create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &RecurringSchedule {
+            period_length: 100,
+            period_count: 4,
+            release_immediately: false,
+        },
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+    ctx.advance_to_start();
+    client.validate_period(&id, &0, &ctx.usdc_token);
+    ctx.advance_to(ctx.start_timestamp + 400);
+    for index in 1..4 {
+        client.redirect_missed_period(&id, &index, &ctx.usdc_token);
+    }
+    assert_eq!(client.get_completed_stake(&ctx.creator), ctx.amount / 4);
+}
+
+#[test]
+fn test_cancellation_leaves_the_boards_alone() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    client.cancel_vault(&id, &ctx.usdc_token);
+    assert_eq!(
+        client.get_leaderboard(&LeaderboardKind::CompletedStake, &10),
+        Vec::new(&ctx.env)
+    );
+}
+
+fn addresses<const N: usize>(env: &Env) -> [Address; N] {
+    core::array::from_fn(|_| Address::generate(env))
+}
+
+#[test]
+fn test_ties_keep_the_earlier_entry_ahead() {
+    let env = Env::default();
+    let [a, b, c] = addresses(&env);
+    let mut board = Vec::new(&env);
+    assert!(place(&mut board, &a, 5, 3));
+    assert!(place(&mut board, &b, 5, 3));
+    assert!(place(&mut board, &c, 7, 3));
+    assert_eq!(
+        board,
+        vec![&env, (c.clone(), 7), (a.clone(), 5), (b.clone(), 5)]
+    );
+    // Re-reporting an unchanged score does not cost the address its rank among ties.
+    assert!(!place(&mut board, &a, 5, 3));
+    assert_eq!(board.get(1), Some((a, 5)));
+}
+
+#[test]
+fn test_improving_moves_the_existing_entry() {
+    let env = Env::default();
+    let [a, b, c] = addresses(&env);
+    let mut board = vec![&env, (a.clone(), 9), (b.clone(), 6), (c.clone(), 3)];
+    assert!(place(&mut board, &c, 8, 3));
+    assert_eq!(
+        board,
+        vec![&env, (a.clone(), 9), (c.clone(), 8), (b.clone(), 6)]
+    );
+    assert!(place(&mut board, &a, 2, 3));
+    assert_eq!(board, vec![&env, (c, 8), (b, 6), (a.clone(), 2)]);
+    assert!(place(&mut board, &a, 0, 3));
+    assert_eq!(board.len(), 2);
+}
+
+#[test]
+fn test_full_board_evicts_the_last_entry() {
+    let env = Env::default();
+    let [a, b, c, d] = addresses(&env);
+    let mut board = vec![&env, (a.clone(), 9), (b.clone(), 6), (c.clone(), 3)];
+    // Not better than the last entry: stays off the board.
+    assert!(!place(&mut board, &d, 3, 3));
+    assert!(place(&mut board, &d, 4, 3));
+    assert_eq!(board, vec![&env, (a, 9), (b, 6), (d.clone(), 4)]);
+    // An entry that falls below the cut leaves instead.
+    assert!(place(&mut board, &d, 1, 2));
+    assert_eq!(board.len(), 2);
+}
+
+#[test]
+fn test_board_is_capped() {
+    let env = Env::default();
+    let mut board = Vec::new(&env);
+    for score in 1..=MAX_LEADERBOARD as i128 + 5 {
+        place(&mut board, &Address::generate(&env), score, MAX_LEADERBOARD);
+    }
+    assert_eq!(board.len(), MAX_LEADERBOARD);
+    assert_eq!(board.get(0).unwrap().1, MAX_LEADERBOARD as i128 + 5);
+    assert_eq!(board.last().unwrap().1, 6);
+}
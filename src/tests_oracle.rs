@@ -0,0 +1,269 @@
+//! Quote-denominated stakes, against a mock SEP-40 oracle.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, testutils::Address as _,
+    testutils::Events as _, Address, Env, IntoVal, Symbol,
+};
+
+use crate::config::Config;
+use crate::events::PRICE_ORACLE_SET;
+use crate::oracle::{Asset, PriceData, QuoteTarget};
+use crate::privacy::Visibility;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+#[contracttype]
+enum Key {
+    Price,
+}
+
+/// Oracle with a single price, served for any asset. No price until `set_price`.
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&Key::Price, &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(env: Env, _asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&Key::Price)
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        DECIMALS
+    }
+}
+
+const DECIMALS: u32 = 7;
+/// One token unit per quote unit.
+const PAR: i128 = 10_000_000;
+/// Half of the default stake's worth, at par.
+const QUOTE_AMOUNT: i128 = 500_000;
+
+struct Setup {
+    ctx: VaultTestContext,
+    oracle: Address,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let ctx = VaultTestContext::new();
+        let oracle = ctx.env.register(MockOracle, ());
+        ctx.client().set_price_oracle(&usd(), &Some(oracle.clone()));
+        let setup = Setup { ctx, oracle };
+        setup.set_price(PAR);
+        setup
+    }
+
+    fn set_price(&self, price: i128) {
+        MockOracleClient::new(&self.ctx.env, &self.oracle)
+            .set_price(&price, &self.ctx.env.ledger().timestamp());
+    }
+
+    fn try_create(&self, quote_amount: i128) -> Result<u32, Error> {
+        let ctx = &self.ctx;
+        match ctx.client().try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                quote_asset: Some(usd()),
+                quote_amount,
+                ..VaultOptions::default()
+            },
+        ) {
+            Ok(Ok(id)) => Ok(id),
+            Err(Ok(error)) => Err(error),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
+
+fn usd() -> Symbol {
+    symbol_short!("USD")
+}
+
+#[test]
+fn test_stake_is_converted_at_the_oracle_price() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    setup.set_price(PAR * 2);
+    let id = setup.try_create(QUOTE_AMOUNT).unwrap();
+
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().amount,
+        QUOTE_AMOUNT / 2
+    );
+    ctx.assert_balances(&[
+        (&ctx.contract_id, QUOTE_AMOUNT / 2),
+        (&ctx.creator, ctx.amount - QUOTE_AMOUNT / 2),
+    ]);
+    assert_eq!(
+        ctx.client().get_quote_target(&id),
+        Some(QuoteTarget {
+            oracle: setup.oracle.clone(),
+            quote_asset: usd(),
+            quote_amount: QUOTE_AMOUNT,
+        })
+    );
+    ctx.mint(&ctx.creator, ctx.amount);
+    assert_eq!(
+        ctx.client().get_quote_target(&ctx.create_default_vault()),
+        None
+    );
+}
+
+#[test]
+fn test_stake_above_amount_is_rejected() {
+    let setup = Setup::new();
+    setup.set_price(PAR / 4);
+    assert_eq!(setup.try_create(QUOTE_AMOUNT), Err(Error::InvalidAmount));
+    assert_eq!(setup.try_create(0), Err(Error::InvalidAmount));
+}
+
+#[test]
+fn test_missing_oracle_or_price_is_not_initialized() {
+    let setup = Setup::new();
+    let client = setup.ctx.client();
+
+    setup.set_price(0);
+    assert_eq!(setup.try_create(QUOTE_AMOUNT), Err(Error::NotInitialized));
+
+    client.set_price_oracle(&usd(), &Some(Address::generate(&setup.ctx.env)));
+    assert_eq!(setup.try_create(QUOTE_AMOUNT), Err(Error::NotInitialized));
+
+    client.set_price_oracle(&usd(), &None);
+    assert_eq!(client.get_price_oracle(&usd()), None);
+    assert_eq!(setup.try_create(QUOTE_AMOUNT), Err(Error::NotInitialized));
+}
+
+#[test]
+fn test_stale_price_is_rejected() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    ctx.client().set_config(&Config {
+        max_price_age: 60,
+        ..Config::default()
+    });
+    ctx.advance_to(61);
+    assert_eq!(setup.try_create(QUOTE_AMOUNT), Err(Error::InvalidTimestamp));
+}
+
+#[test]
+fn test_stale_price_at_release_pays_out_the_whole_escrow() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    ctx.client().set_config(&Config {
+        max_price_age: 60,
+        ..Config::default()
+    });
+    ctx.advance_to_start();
+    setup.set_price(PAR);
+    let id = setup.try_create(QUOTE_AMOUNT).unwrap();
+    ctx.client().validate_milestone(&id);
+    // A fresh price would pay out half, but this one is past its age.
+    setup.set_price(PAR * 2);
+    ctx.advance_to(ctx.start_timestamp + 61);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[
+        (&ctx.success_dest, QUOTE_AMOUNT),
+        (&ctx.creator, ctx.amount - QUOTE_AMOUNT),
+        (&ctx.contract_id, 0),
+    ]);
+}
+
+#[test]
+fn test_price_rise_returns_the_surplus_to_the_creator() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let id = setup.try_create(QUOTE_AMOUNT).unwrap();
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    setup.set_price(PAR * 2);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[
+        (&ctx.success_dest, QUOTE_AMOUNT / 2),
+        (&ctx.creator, ctx.amount - QUOTE_AMOUNT / 2),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+}
+
+#[test]
+fn test_price_drop_pays_out_the_whole_escrow() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let id = setup.try_create(QUOTE_AMOUNT).unwrap();
+    ctx.advance_to_deadline();
+    setup.set_price(PAR / 2);
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, QUOTE_AMOUNT),
+        (&ctx.creator, ctx.amount - QUOTE_AMOUNT),
+        (&ctx.contract_id, 0),
+    ]);
+}
+
+#[test]
+fn test_set_price_oracle_emits_event() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let admin = ctx.client().get_admin().unwrap();
+    ctx.client().set_price_oracle(&usd(), &None);
+
+    let (_, topics, data) = ctx.env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&ctx.env, PRICE_ORACLE_SET), admin).into_val(&ctx.env)
+    );
+    let data: (Symbol, Option<Address>) = data.into_val(&ctx.env);
+    assert_eq!(data, (usd(), None));
+}
+
+#[test]
+fn test_private_vault_quote_omits_the_amount() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let id = ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            quote_asset: Some(usd()),
+            quote_amount: QUOTE_AMOUNT,
+            visibility: Visibility::Private,
+            ..VaultOptions::default()
+        },
+    );
+
+    assert_eq!(
+        ctx.client().get_quote_target(&id),
+        Some(QuoteTarget {
+            oracle: setup.oracle.clone(),
+            quote_asset: usd(),
+            quote_amount: 0,
+        })
+    );
+}
@@ -0,0 +1,144 @@
+//! Accountability partners and the two-step cancellation.
+
+use soroban_sdk::{testutils::Address as _, Address};
+
+use crate::partner::CANCEL_APPROVAL_WINDOW;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+struct Partnered {
+    ctx: VaultTestContext,
+    partner: Address,
+    id: u32,
+}
+
+impl Partnered {
+    fn new() -> Self {
+        let ctx = VaultTestContext::new();
+        let partner = Address::generate(&ctx.env);
+        let id = ctx.client().create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                accountability_partner: Some(partner.clone()),
+                ..VaultOptions::default()
+            },
+        );
+        Partnered { ctx, partner, id }
+    }
+
+    fn status(&self) -> VaultStatus {
+        self.ctx.client().get_vault_state(&self.id).unwrap().status
+    }
+}
+
+#[test]
+fn test_partner_approval_completes_the_cancellation() {
+    let vault = Partnered::new();
+    let ctx = &vault.ctx;
+    let client = ctx.client();
+
+    ctx.advance_to(200);
+    assert!(!client.cancel_vault(&vault.id, &ctx.usdc_token));
+    assert_eq!(vault.status(), VaultStatus::Active);
+    assert_eq!(
+        client.get_pending_cancellation(&vault.id),
+        Some(200 + CANCEL_APPROVAL_WINDOW)
+    );
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount), (&ctx.creator, 0)]);
+
+    assert!(client.approve_cancellation(&vault.id, &ctx.usdc_token));
+    assert_eq!(ctx.env.auths()[0].0, vault.partner);
+    assert_eq!(vault.status(), VaultStatus::Cancelled);
+    assert_eq!(client.get_pending_cancellation(&vault.id), None);
+    ctx.assert_balances(&[(&ctx.contract_id, 0), (&ctx.creator, ctx.amount)]);
+
+    let history = client.get_vault_history(&vault.id);
+    assert_eq!(history.last().unwrap().actor, vault.partner);
+}
+
+#[test]
+fn test_unapproved_cancellation_expires() {
+    let vault = Partnered::new();
+    let ctx = &vault.ctx;
+    let client = ctx.client();
+
+    client.cancel_vault(&vault.id, &ctx.usdc_token);
+    ctx.advance_to(CANCEL_APPROVAL_WINDOW);
+    assert_eq!(
+        client.try_approve_cancellation(&vault.id, &ctx.usdc_token),
+        Err(Ok(Error::CancellationExpired))
+    );
+    assert_eq!(vault.status(), VaultStatus::Active);
+
+    // The creator can ask again, which opens a fresh window.
+    client.cancel_vault(&vault.id, &ctx.usdc_token);
+    ctx.advance_to(2 * CANCEL_APPROVAL_WINDOW - 1);
+    client.approve_cancellation(&vault.id, &ctx.usdc_token);
+    assert_eq!(vault.status(), VaultStatus::Cancelled);
+}
+
+#[test]
+fn test_refusing_partner_leaves_the_vault_running() {
+    let vault = Partnered::new();
+    let ctx = &vault.ctx;
+    let client = ctx.client();
+
+    ctx.advance_to_start();
+    client.cancel_vault(&vault.id, &ctx.usdc_token);
+    client.validate_milestone(&vault.id);
+    client.release_funds(&vault.id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+
+    assert_eq!(
+        client.try_approve_cancellation(&vault.id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_approval_needs_a_pending_proposal() {
+    let vault = Partnered::new();
+    let ctx = &vault.ctx;
+    assert_eq!(
+        ctx.client()
+            .try_approve_cancellation(&vault.id, &ctx.usdc_token),
+        Err(Ok(Error::NoPendingCancellation))
+    );
+
+    ctx.mint(&ctx.creator, ctx.amount);
+    let plain = ctx.create_default_vault();
+    assert_eq!(
+        ctx.client()
+            .try_approve_cancellation(&plain, &ctx.usdc_token),
+        Err(Ok(Error::NoPendingCancellation))
+    );
+}
+
+#[test]
+fn test_partner_cannot_be_the_creator() {
+    let ctx = VaultTestContext::new();
+    let result = ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            accountability_partner: Some(ctx.creator.clone()),
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidPartner)));
+}
@@ -0,0 +1,218 @@
+//! Private vaults: redacted public reads, authed reads and zeroed event amounts.
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, BytesN, TryIntoVal};
+
+use crate::config::Config;
+use crate::installment::InstallmentSchedule;
+use crate::payout::PayoutBreakdown;
+use crate::privacy::Visibility;
+use crate::testutils::VaultTestContext;
+use crate::witness::Attestation;
+use crate::{Error, ProductivityVault, VaultOptions, VaultStatus};
+
+fn create_private_vault(ctx: &VaultTestContext) -> u32 {
+    create_private_vault_with(ctx, VaultOptions::default())
+}
+
+fn create_private_vault_with(ctx: &VaultTestContext, options: VaultOptions) -> u32 {
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            visibility: Visibility::Private,
+            ..options
+        },
+    )
+}
+
+fn assert_redacted(ctx: &VaultTestContext, vault: &ProductivityVault, status: VaultStatus) {
+    assert_eq!(vault.status, status);
+    assert_eq!(vault.start_timestamp, ctx.start_timestamp);
+    assert_eq!(vault.end_timestamp, ctx.end_timestamp);
+    assert_eq!(vault.visibility, Visibility::Private);
+    assert_eq!(vault.amount, 0);
+    assert_eq!(vault.creator, ctx.contract_id);
+    assert_eq!(vault.success_destination, ctx.contract_id);
+    assert_eq!(vault.failure_destination, ctx.contract_id);
+    assert_eq!(vault.verifier, None);
+    assert_eq!(vault.milestone_hash, BytesN::from_array(&ctx.env, &[0; 32]));
+}
+
+#[test]
+fn test_public_read_of_private_vault_is_redacted() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_private_vault(&ctx);
+
+    let (_, data) = ctx.vault_events().get(0).unwrap();
+    let created: ProductivityVault = data.try_into_val(&ctx.env).unwrap();
+    assert_redacted(&ctx, &created, VaultStatus::Active);
+    assert_redacted(
+        &ctx,
+        &client.get_vault_state(&id).unwrap(),
+        VaultStatus::Active,
+    );
+}
+
+#[test]
+fn test_parties_read_the_full_state() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_private_vault(&ctx);
+
+    for viewer in [&ctx.creator, &ctx.verifier] {
+        let vault = client.get_vault_state_authed(&id, viewer);
+        assert_eq!(ctx.env.auths()[0].0, *viewer);
+        assert_eq!(vault.amount, ctx.amount);
+        assert_eq!(vault.creator, ctx.creator);
+        assert_eq!(vault.success_destination, ctx.success_dest);
+        assert_eq!(vault.verifier, Some(ctx.verifier.clone()));
+    }
+    assert_eq!(
+        client.try_get_vault_state_authed(&id, &Address::generate(&ctx.env)),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_get_vault_state_authed(&(id + 1), &ctx.creator),
+        Err(Ok(Error::VaultNotFound))
+    );
+}
+
+#[test]
+fn test_public_vault_reads_are_unchanged() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+
+    let public = client.get_vault_state(&id).unwrap();
+    assert_eq!(public.visibility, Visibility::Public);
+    assert_eq!(public.amount, ctx.amount);
+    assert_eq!(public, client.get_vault_state_authed(&id, &ctx.creator));
+}
+
+#[test]
+fn test_private_settlement_events_omit_amounts() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = create_private_vault(&ctx);
+
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    let (_, data) = ctx.vault_events().get(0).unwrap();
+    let parts: PayoutBreakdown = data.try_into_val(&ctx.env).unwrap();
+    assert_eq!(
+        parts,
+        PayoutBreakdown {
+            gross: 0,
+            fee: 0,
+            penalty: 0,
+            verifier_reward: 0,
+            shares: vec![&ctx.env, (ctx.success_dest.clone(), 0)],
+        }
+    );
+    // Settlement itself is unaffected.
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_redacted(
+        &ctx,
+        &client.get_vault_state(&id).unwrap(),
+        VaultStatus::Completed,
+    );
+}
+
+#[test]
+fn test_private_cancellation_event_omits_amount() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_private_vault(&ctx);
+
+    client.cancel_vault(&id, &ctx.usdc_token);
+    let (_, data) = ctx.vault_events().get(0).unwrap();
+    let amount: i128 = data.try_into_val(&ctx.env).unwrap();
+    assert_eq!(amount, 0);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount)]);
+}
+
+#[test]
+fn test_private_vault_getters_hide_the_parties() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    client.set_config(&Config {
+        emergency_quorum_bps: 10_000,
+        emergency_approval_window: 300,
+        ..Config::default()
+    });
+    let witness = Address::generate(env);
+    let id = create_private_vault_with(
+        &ctx,
+        VaultOptions {
+            callback: Some(Address::generate(env)),
+            witnesses: Some(vec![env, witness.clone()]),
+            ..VaultOptions::default()
+        },
+    );
+    ctx.advance_to_start();
+    client.attest(&id, &witness);
+    client.transfer_creator(&id, &Address::generate(env));
+    client.validate_milestone(&id);
+    client.approve_emergency(&id, &ctx.verifier);
+
+    assert_eq!(client.get_witnesses(&id), vec![env]);
+    assert_eq!(
+        client.get_attestations(&id),
+        vec![
+            env,
+            Attestation {
+                witness: ctx.contract_id.clone(),
+                timestamp: ctx.start_timestamp,
+            }
+        ]
+    );
+    assert_eq!(client.get_callback(&id), None);
+    assert_eq!(client.get_pending_creator(&id), None);
+    assert_eq!(client.get_original_creator(&id), ctx.contract_id);
+    assert_eq!(client.get_emergency_approvals(&id), vec![env]);
+
+    client.release_funds(&id, &ctx.usdc_token);
+    let history = client.get_vault_history(&id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().actor, ctx.contract_id);
+    assert_eq!(history.get(0).unwrap().to, VaultStatus::Completed);
+}
+
+#[test]
+fn test_private_installment_schedule_omits_the_amount() {
+    let ctx = VaultTestContext::new();
+    let schedule = InstallmentSchedule {
+        count: 2,
+        amount: ctx.amount,
+        interval: 200,
+        grace_period: 50,
+    };
+    let id = create_private_vault_with(
+        &ctx,
+        VaultOptions {
+            installments: schedule.clone(),
+            ..VaultOptions::default()
+        },
+    );
+
+    let state = ctx.client().get_installments(&id).unwrap();
+    assert_eq!(
+        state.schedule,
+        InstallmentSchedule {
+            amount: 0,
+            ..schedule
+        }
+    );
+    assert_eq!(state.paid, 1);
+}
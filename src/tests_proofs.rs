@@ -0,0 +1,237 @@
+//! Proof log, submission and change-request preconditions.
+
+use soroban_sdk::testutils::storage::Persistent as _;
+use soroban_sdk::{BytesN, IntoVal, Symbol};
+
+use crate::config::Config;
+use crate::events::PROOF_APPROVED;
+use crate::proofs::{ProofEntry, ReasonCode, MAX_PROOF_SUBMISSIONS, PROOF_BUCKET_SIZE};
+use crate::testutils::VaultTestContext;
+use crate::{DataKey, Error};
+
+fn proof(ctx: &VaultTestContext, byte: u8) -> BytesN<32> {
+    BytesN::from_array(&ctx.env, &[byte; 32])
+}
+
+#[test]
+fn test_submissions_are_indexed_and_listed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    assert_eq!(client.submit_proof(&id, &proof(&ctx, 1)), 0);
+    assert_eq!(client.submit_proof(&id, &proof(&ctx, 2)), 1);
+    let proofs = client.get_proofs(&id, &0, &10);
+    assert_eq!(proofs.len(), 2);
+    assert_eq!(proofs.get(1).unwrap().hash, proof(&ctx, 2));
+    assert_eq!(client.get_proof_count(&id), 2);
+}
+
+#[test]
+fn test_log_keeps_timestamped_entries_across_buckets() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    let total = PROOF_BUCKET_SIZE + 3;
+    for i in 0..total {
+        ctx.advance_to(ctx.start_timestamp + i as u64);
+        client.submit_proof(&id, &proof(&ctx, i as u8));
+    }
+
+    assert_eq!(client.get_proof_count(&id), total);
+    let page = client.get_proofs(&id, &(PROOF_BUCKET_SIZE - 1), &2);
+    assert_eq!(
+        page.get(1),
+        Some(ProofEntry {
+            hash: proof(&ctx, PROOF_BUCKET_SIZE as u8),
+            timestamp: ctx.start_timestamp + PROOF_BUCKET_SIZE as u64,
+        })
+    );
+    assert_eq!(page.get(0).unwrap().hash, proof(&ctx, 9));
+    // Pages stop at the end of the log.
+    assert_eq!(client.get_proofs(&id, &(total - 1), &10).len(), 1);
+    assert_eq!(client.get_proofs(&id, &total, &10).len(), 0);
+}
+
+#[test]
+fn test_approve_proof_approves_the_given_entry() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    client.submit_proof(&id, &proof(&ctx, 1));
+    client.submit_proof(&id, &proof(&ctx, 2));
+
+    assert_eq!(
+        client.try_approve_proof(&id, &2),
+        Err(Ok(Error::NoProofSubmitted))
+    );
+    client.approve_proof(&id, &0);
+    let (topics, data) = ctx.vault_events().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, PROOF_APPROVED), id, ctx.verifier.clone()).into_val(env)
+    );
+    let hash: BytesN<32> = data.into_val(env);
+    assert_eq!(hash, proof(&ctx, 1));
+    assert_eq!(client.get_approved_proof(&id), Some(0));
+    assert!(client.get_vault_state(&id).unwrap().milestone_validated);
+}
+
+#[test]
+fn test_validate_milestone_records_latest_entry() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.mint(&ctx.creator, ctx.amount);
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    let bare = ctx.create_default_vault();
+    client.submit_proof(&id, &proof(&ctx, 1));
+    client.submit_proof(&id, &proof(&ctx, 2));
+
+    client.validate_milestone(&id);
+    client.validate_milestone(&bare);
+    assert_eq!(client.get_approved_proof(&id), Some(1));
+    assert_eq!(client.get_approved_proof(&bare), None);
+}
+
+#[test]
+fn test_timeout_release_requires_minimum_proofs() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    client.set_config(&Config {
+        timeout_min_proofs: 2,
+        ..Config::default()
+    });
+    ctx.mint(&ctx.creator, ctx.amount);
+    ctx.advance_to_start();
+    let idle = ctx.create_default_vault();
+    let busy = ctx.create_default_vault();
+    client.submit_proof(&idle, &proof(&ctx, 1));
+    client.submit_proof(&busy, &proof(&ctx, 1));
+    client.submit_proof(&busy, &proof(&ctx, 2));
+
+    ctx.advance_past_deadline();
+    assert_eq!(
+        client.try_release_funds(&idle, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    client.redirect_funds(&idle, &ctx.usdc_token);
+    client.release_funds(&busy, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, ctx.amount),
+        (&ctx.success_dest, ctx.amount),
+    ]);
+}
+
+#[test]
+fn test_log_is_persistent_and_removed_on_settlement() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    for byte in 0..=PROOF_BUCKET_SIZE as u8 {
+        client.submit_proof(&id, &proof(&ctx, byte));
+    }
+    let keys = [
+        DataKey::ProofCount(id),
+        DataKey::ProofBucket(id, 0),
+        DataKey::ProofBucket(id, 1),
+    ];
+    ctx.env.as_contract(&ctx.contract_id, || {
+        let storage = ctx.env.storage();
+        for key in &keys {
+            assert!(!storage.instance().has(key));
+            assert_eq!(storage.persistent().get_ttl(key), storage.max_ttl());
+        }
+    });
+
+    client.approve_proof(&id, &3);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.env.as_contract(&ctx.contract_id, || {
+        for key in &keys {
+            assert!(!ctx.env.storage().persistent().has(key));
+        }
+    });
+    assert_eq!(client.get_proof_count(&id), 0);
+    assert_eq!(client.get_approved_proof(&id), Some(3));
+}
+
+#[test]
+fn test_request_changes_without_proof_fails() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.try_request_changes(&id, &ReasonCode::Incomplete),
+        Err(Ok(Error::NoProofSubmitted))
+    );
+}
+
+#[test]
+fn test_submission_closes_at_deadline() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    client.submit_proof(&id, &proof(&ctx, 1));
+    ctx.advance_to_deadline();
+
+    assert_eq!(
+        client.try_submit_proof(&id, &proof(&ctx, 2)),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    assert_eq!(
+        client.try_request_changes(&id, &ReasonCode::Incomplete),
+        Err(Ok(Error::MilestoneExpired))
+    );
+}
+
+#[test]
+fn test_no_submissions_or_requests_after_validation() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    client.submit_proof(&id, &proof(&ctx, 1));
+    client.validate_milestone(&id);
+
+    assert_eq!(
+        client.try_submit_proof(&id, &proof(&ctx, 2)),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_request_changes(&id, &ReasonCode::Incomplete),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_submissions_are_capped() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+    for i in 0..MAX_PROOF_SUBMISSIONS {
+        client.submit_proof(&id, &proof(&ctx, i as u8));
+    }
+
+    assert_eq!(
+        client.try_submit_proof(&id, &proof(&ctx, 0xff)),
+        Err(Ok(Error::TooManyProofs))
+    );
+}
+
+#[test]
+fn test_submit_proof_unknown_vault() {
+    let ctx = VaultTestContext::new();
+    assert_eq!(
+        ctx.client().try_submit_proof(&7, &proof(&ctx, 1)),
+        Err(Ok(Error::VaultNotFound))
+    );
+}
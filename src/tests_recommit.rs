@@ -0,0 +1,161 @@
+//! Recommitting a cancelled vault's escrow into a new vault.
+
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    Address, IntoVal, Symbol,
+};
+
+use crate::config::Config;
+use crate::events::{VAULT_CANCELLED, VAULT_CREATED, VAULT_RECOMMITTED};
+use crate::recommit::VaultTerms;
+use crate::savings::VaultMode;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+/// Terms of the replacement: a later, longer window with the same parties.
+fn terms(ctx: &VaultTestContext) -> VaultTerms {
+    VaultTerms {
+        start_timestamp: 500,
+        end_timestamp: 2_000,
+        milestone_hash: ctx.milestone_hash(),
+        verifier: Some(ctx.verifier.clone()),
+        success_destination: ctx.success_dest.clone(),
+        failure_destination: ctx.failure_dest.clone(),
+    }
+}
+
+#[test]
+fn test_recommit_moves_the_escrow_into_a_new_vault() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    let successor_id =
+        client.recommit(&id, &ctx.usdc_token, &terms(&ctx), &VaultOptions::default());
+
+    let events = ctx.vault_events();
+    let names = [VAULT_CANCELLED, VAULT_RECOMMITTED, VAULT_CREATED];
+    for (i, (name, vault_id)) in names.iter().zip([id, id, successor_id]).enumerate() {
+        // `vault_created` carries the category as a fourth topic.
+        let (topics, _) = events.get(i as u32).unwrap();
+        assert_eq!(
+            topics.slice(0..3),
+            (Symbol::new(&ctx.env, name), vault_id, ctx.creator.clone()).into_val(&ctx.env)
+        );
+    }
+    let (_, data) = events.get(1).unwrap();
+    let linked: u32 = data.into_val(&ctx.env);
+    assert_eq!(linked, successor_id);
+
+    // No token call at all: the escrow never leaves the contract.
+    assert!(!ctx
+        .env
+        .events()
+        .all()
+        .iter()
+        .any(|(contract, _, _)| contract == ctx.usdc_token));
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount), (&ctx.creator, 0)]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+    let successor = client.get_vault_state(&successor_id).unwrap();
+    assert_eq!(successor.status, VaultStatus::Active);
+    assert_eq!(successor.amount, ctx.amount);
+    assert_eq!(successor.end_timestamp, 2_000);
+    assert_eq!(client.get_successor(&id), Some(successor_id));
+    assert_eq!(client.get_status_count(&VaultStatus::Active), 1);
+    assert_eq!(client.get_status_count(&VaultStatus::Cancelled), 1);
+
+    // The successor settles like any other vault.
+    ctx.advance_to(500);
+    client.validate_milestone(&successor_id);
+    client.release_funds(&successor_id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_recommit_withholds_the_savings_penalty() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    client.set_config(&Config {
+        early_withdrawal_bps: 1_000,
+        ..Config::default()
+    });
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.creator,
+        &ctx.creator,
+        &VaultOptions {
+            mode: VaultMode::SavingsLock,
+            ..VaultOptions::default()
+        },
+    );
+    let successor_id =
+        client.recommit(&id, &ctx.usdc_token, &terms(&ctx), &VaultOptions::default());
+
+    let penalty = ctx.amount / 10;
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), penalty);
+    assert_eq!(
+        client.get_vault_state(&successor_id).unwrap().amount,
+        ctx.amount - penalty
+    );
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount), (&ctx.creator, 0)]);
+}
+
+#[test]
+fn test_recommit_rejects_ineligible_vaults_and_bad_terms() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    let bad_terms = VaultTerms {
+        end_timestamp: 500,
+        ..terms(&ctx)
+    };
+    assert_eq!(
+        client.try_recommit(&id, &ctx.usdc_token, &bad_terms, &VaultOptions::default()),
+        Err(Ok(Error::InvalidTimestamps))
+    );
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Active
+    );
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount)]);
+
+    ctx.advance_to_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+    assert_eq!(
+        client.try_recommit(&id, &ctx.usdc_token, &terms(&ctx), &VaultOptions::default()),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_partner_vaults_cannot_skip_approval() {
+    let ctx = VaultTestContext::new();
+    let id = ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            accountability_partner: Some(Address::generate(&ctx.env)),
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(
+        ctx.client()
+            .try_recommit(&id, &ctx.usdc_token, &terms(&ctx), &VaultOptions::default()),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
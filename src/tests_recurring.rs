@@ -0,0 +1,342 @@
+//! Recurring vaults: per-period check-ins, missed-period redirects and completion.
+
+use soroban_sdk::{IntoVal, Symbol, TryIntoVal};
+
+use crate::events::{FUNDS_RELEASED, PERIOD_MISSED, PERIOD_VALIDATED, STREAK_UPDATED};
+use crate::recurring::{PeriodStatus, RecurringSchedule, MAX_PERIODS};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+const PERIOD: u64 = 100;
+const PERIODS: u32 = 4;
+
+fn schedule(release_immediately: bool) -> RecurringSchedule {
+    RecurringSchedule {
+        period_length: PERIOD,
+        period_count: PERIODS,
+        release_immediately,
+    }
+}
+
+fn try_create(ctx: &VaultTestContext, schedule: &RecurringSchedule) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_recurring_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            schedule,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext, release_immediately: bool) -> u32 {
+    try_create(ctx, &schedule(release_immediately)).unwrap()
+}
+
+/// First second of period `index`.
+fn period_start(ctx: &VaultTestContext, index: u32) -> u64 {
+    ctx.start_timestamp + PERIOD * index as u64
+}
+
+const SLICE: i128 = 250_000;
+
+#[test]
+fn test_deferred_slices_are_paid_when_last_period_settles() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+
+    for index in [0, 1] {
+        ctx.advance_to(period_start(&ctx, index));
+        assert_eq!(client.validate_period(&id, &index, &ctx.usdc_token), SLICE);
+    }
+    ctx.advance_to(period_start(&ctx, 3));
+    client.redirect_missed_period(&id, &2, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.success_dest, 0),
+        (&ctx.failure_dest, SLICE),
+        (&ctx.contract_id, 3 * SLICE),
+    ]);
+    assert_eq!(client.get_vault_state(&id).unwrap().amount, 3 * SLICE);
+
+    client.validate_period(&id, &3, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.success_dest, 3 * SLICE),
+        (&ctx.failure_dest, SLICE),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+}
+
+#[test]
+fn test_immediate_slices_are_paid_on_validation() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+
+    ctx.advance_to(period_start(&ctx, 0));
+    client.validate_period(&id, &0, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, SLICE), (&ctx.contract_id, 3 * SLICE)]);
+
+    ctx.advance_to(period_start(&ctx, 1) + PERIOD - 1);
+    client.validate_period(&id, &1, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.success_dest, 2 * SLICE),
+        (&ctx.contract_id, 2 * SLICE),
+    ]);
+    let state = client.get_recurring_state(&id).unwrap();
+    assert_eq!(state.settled_periods, 2);
+    assert_eq!(state.validated_periods, 2);
+}
+
+#[test]
+fn test_vault_with_every_period_missed_fails() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+
+    ctx.advance_to(period_start(&ctx, PERIODS));
+    for index in 0..PERIODS {
+        client.redirect_missed_period(&id, &index, &ctx.usdc_token);
+        assert_eq!(client.get_period_status(&id, &index), PeriodStatus::Missed);
+    }
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, ctx.amount),
+        (&ctx.success_dest, 0),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Failed
+    );
+}
+
+#[test]
+fn test_period_window_boundaries() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+
+    // Period 1 is [200, 300).
+    ctx.advance_to(period_start(&ctx, 1) - 1);
+    assert_eq!(
+        client.try_validate_period(&id, &1, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    ctx.advance_to(period_start(&ctx, 2) - 1);
+    assert_eq!(
+        client.try_redirect_missed_period(&id, &1, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+
+    ctx.advance_to(period_start(&ctx, 2));
+    assert_eq!(
+        client.try_validate_period(&id, &1, &ctx.usdc_token),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    client.redirect_missed_period(&id, &1, &ctx.usdc_token);
+    // Period 2 opened at the same instant period 1 closed.
+    client.validate_period(&id, &2, &ctx.usdc_token);
+}
+
+#[test]
+fn test_period_cannot_settle_twice() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+
+    ctx.advance_to(period_start(&ctx, 0));
+    client.validate_period(&id, &0, &ctx.usdc_token);
+    assert_eq!(
+        client.try_validate_period(&id, &0, &ctx.usdc_token),
+        Err(Ok(Error::PeriodAlreadySettled))
+    );
+    ctx.advance_to(period_start(&ctx, 1));
+    assert_eq!(
+        client.try_redirect_missed_period(&id, &0, &ctx.usdc_token),
+        Err(Ok(Error::PeriodAlreadySettled))
+    );
+    assert_eq!(
+        client.try_validate_period(&id, &PERIODS, &ctx.usdc_token),
+        Err(Ok(Error::InvalidPeriod))
+    );
+    ctx.assert_balances(&[(&ctx.success_dest, SLICE), (&ctx.failure_dest, 0)]);
+}
+
+#[test]
+fn test_single_milestone_entrypoints_reject_recurring_vaults() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, false);
+    ctx.advance_to(period_start(&ctx, 0));
+
+    assert_eq!(
+        client.try_validate_milestone(&id),
+        Err(Ok(Error::InvalidStatus))
+    );
+    ctx.advance_to(period_start(&ctx, PERIODS));
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_redirect_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_period_entrypoints_reject_single_milestone_vaults() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.try_validate_period(&id, &0, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(client.get_recurring_state(&id), None);
+}
+
+#[test]
+fn test_create_rejects_malformed_schedules() {
+    let ctx = VaultTestContext::new();
+    let mut bad = schedule(false);
+    bad.period_length = 0;
+    assert_eq!(try_create(&ctx, &bad), Err(Error::InvalidPeriod));
+
+    let mut bad = schedule(false);
+    bad.period_count = 0;
+    assert_eq!(try_create(&ctx, &bad), Err(Error::InvalidPeriod));
+
+    let mut bad = schedule(false);
+    bad.period_count = MAX_PERIODS + 1;
+    assert_eq!(try_create(&ctx, &bad), Err(Error::InvalidPeriod));
+
+    let mut bad = schedule(false);
+    bad.period_length = u64::MAX;
+    assert_eq!(try_create(&ctx, &bad), Err(Error::InvalidTimestamps));
+
+    // Four year-long periods exceed the overall duration cap.
+    let mut bad = schedule(false);
+    bad.period_length = 31_536_000;
+    assert_eq!(try_create(&ctx, &bad), Err(Error::DurationTooLong));
+}
+
+#[test]
+fn test_create_rejects_amount_smaller_than_period_count() {
+    let ctx = VaultTestContext::new();
+    let result = ctx.client().try_create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &(PERIODS as i128 - 1),
+        &ctx.start_timestamp,
+        &schedule(false),
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_last_slice_sweeps_dust() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = client.create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &10,
+        &ctx.start_timestamp,
+        &RecurringSchedule {
+            period_length: PERIOD,
+            period_count: 3,
+            release_immediately: true,
+        },
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+
+    let mut paid = 0;
+    for index in 0..3 {
+        ctx.advance_to(period_start(&ctx, index));
+        paid += client.validate_period(&id, &index, &ctx.usdc_token);
+    }
+    assert_eq!(paid, 10);
+    ctx.assert_balances(&[(&ctx.success_dest, 10), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_cancel_refunds_only_what_is_still_escrowed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx, true);
+
+    ctx.advance_to(period_start(&ctx, 0));
+    client.validate_period(&id, &0, &ctx.usdc_token);
+    client.cancel_vault(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.success_dest, SLICE),
+        (&ctx.creator, ctx.amount - SLICE),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(
+        client.try_validate_period(&id, &1, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_events_are_period_indexed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let id = create(&ctx, false);
+
+    ctx.advance_to(period_start(&ctx, PERIODS - 1));
+    for index in 0..PERIODS - 1 {
+        client.redirect_missed_period(&id, &index, &ctx.usdc_token);
+    }
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(env, PERIOD_MISSED),
+            id,
+            ctx.failure_dest.clone()
+        )
+            .into_val(env)
+    );
+    let (index, slice): (u32, i128) = data.try_into_val(env).unwrap();
+    assert_eq!((index, slice), (PERIODS - 2, SLICE));
+
+    client.validate_period(&id, &(PERIODS - 1), &ctx.usdc_token);
+    let events = ctx.vault_events();
+    assert_eq!(events.len(), 3);
+    let name = |i: u32| -> Symbol {
+        events
+            .get(i)
+            .unwrap()
+            .0
+            .get(0)
+            .unwrap()
+            .try_into_val(env)
+            .unwrap()
+    };
+    assert_eq!(name(0), Symbol::new(env, PERIOD_VALIDATED));
+    assert_eq!(name(1), Symbol::new(env, FUNDS_RELEASED));
+    assert_eq!(name(2), Symbol::new(env, STREAK_UPDATED));
+}
@@ -0,0 +1,153 @@
+//! Auto-renewing vaults: successors, carry-over without transfers, and ending the chain.
+
+use soroban_sdk::testutils::Events as _;
+use soroban_sdk::{IntoVal, Symbol, TryIntoVal};
+
+use crate::events::{VAULT_CREATED, VAULT_RENEWED};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+const PERIOD: u64 = 1_000;
+
+fn try_create(ctx: &VaultTestContext, renewal_period: u64) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &Some(ctx.verifier.clone()),
+            &ctx.success_dest,
+            &ctx.failure_dest,
+            &VaultOptions {
+                auto_renew: true,
+                renewal_period,
+                ..VaultOptions::default()
+            },
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext) -> u32 {
+    try_create(ctx, PERIOD).unwrap()
+}
+
+/// Validate and release `id` at its start.
+fn complete(ctx: &VaultTestContext, id: u32) {
+    let client = ctx.client();
+    ctx.advance_to(client.get_vault_state(&id).unwrap().start_timestamp);
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+}
+
+#[test]
+fn test_release_rolls_escrow_into_a_successor() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    let id = create(&ctx);
+
+    complete(&ctx, id);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, VAULT_RENEWED), id, ctx.creator.clone()).into_val(env)
+    );
+    let successor: u32 = data.try_into_val(env).unwrap();
+    let name: Symbol = events
+        .get(1)
+        .unwrap()
+        .0
+        .get(0)
+        .unwrap()
+        .try_into_val(env)
+        .unwrap();
+    assert_eq!(name, Symbol::new(env, VAULT_CREATED));
+
+    // Pure bookkeeping: no token call at all, and the escrow stays put.
+    assert!(!env
+        .events()
+        .all()
+        .iter()
+        .any(|(contract, _, _)| contract == ctx.usdc_token));
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount), (&ctx.success_dest, 0)]);
+
+    assert_eq!(client.get_successor(&id), Some(successor));
+    let old = client.get_vault_state(&id).unwrap();
+    let new = client.get_vault_state(&successor).unwrap();
+    assert_eq!(old.status, VaultStatus::Completed);
+    assert_eq!(new.status, VaultStatus::Active);
+    assert_eq!(new.amount, ctx.amount);
+    assert_eq!(new.start_timestamp, ctx.start_timestamp + PERIOD);
+    assert_eq!(new.end_timestamp, ctx.end_timestamp + PERIOD);
+    assert!(!new.milestone_validated && new.auto_renew);
+    assert_eq!(client.get_renewal_period(&successor), Some(PERIOD));
+    assert_eq!(client.get_streak(&ctx.creator).current, 1);
+}
+
+#[test]
+fn test_chain_continues_until_renewal_is_turned_off() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = create(&ctx);
+
+    complete(&ctx, first);
+    let second = client.get_successor(&first).unwrap();
+    complete(&ctx, second);
+    let third = client.get_successor(&second).unwrap();
+
+    assert!(client.set_auto_renew(&third, &false));
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    complete(&ctx, third);
+    assert_eq!(client.get_successor(&third), None);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(client.get_streak(&ctx.creator).current, 3);
+}
+
+#[test]
+fn test_failure_ends_the_chain_with_the_normal_payout() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    ctx.advance_to_deadline();
+    client.redirect_funds(&id, &ctx.usdc_token);
+    assert_eq!(client.get_successor(&id), None);
+    ctx.assert_balances(&[(&ctx.failure_dest, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_renewal_can_be_turned_back_on() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    client.set_auto_renew(&id, &false);
+    client.set_auto_renew(&id, &true);
+    complete(&ctx, id);
+    assert!(client.get_successor(&id).is_some());
+
+    // Without a renewal period there is nothing to enable.
+    ctx.mint(&ctx.creator, ctx.amount);
+    let plain = ctx.create_default_vault();
+    assert_eq!(
+        client.try_set_auto_renew(&plain, &true),
+        Err(Ok(Error::InvalidPeriod))
+    );
+    assert_eq!(
+        client.try_set_auto_renew(&id, &false),
+        Err(Ok(Error::VaultNotActive))
+    );
+}
+
+#[test]
+fn test_renewal_period_must_cover_the_vault() {
+    let ctx = VaultTestContext::new();
+    let duration = ctx.end_timestamp - ctx.start_timestamp;
+    assert_eq!(try_create(&ctx, duration - 1), Err(Error::InvalidPeriod));
+    assert!(try_create(&ctx, duration).is_ok());
+}
@@ -0,0 +1,224 @@
+//! Creator and verifier reputation fed by settlements and verifier responses.
+
+use soroban_sdk::{Address, BytesN};
+
+use crate::config::Config;
+use crate::proofs::ReasonCode;
+use crate::recurring::RecurringSchedule;
+use crate::reputation::{Reputation, ReputationWeights, MAX_SCORE};
+use crate::testutils::VaultTestContext;
+use crate::VaultOptions;
+
+/// One default stake unit.
+const UNIT: i128 = 1_000_000;
+
+fn vault_with(ctx: &VaultTestContext, amount: i128, verifier: Option<&Address>) -> u32 {
+    ctx.mint(&ctx.creator, amount);
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &verifier.cloned(),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    )
+}
+
+fn complete(ctx: &VaultTestContext, amount: i128) {
+    ctx.advance_to_start();
+    let id = vault_with(ctx, amount, Some(&ctx.verifier));
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+}
+
+fn fail(ctx: &VaultTestContext, amount: i128) {
+    ctx.advance_to_start();
+    let id = vault_with(ctx, amount, Some(&ctx.verifier));
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+}
+
+#[test]
+fn test_completion_points_are_stake_weighted_and_capped() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+
+    complete(&ctx, 5 * UNIT + UNIT / 2);
+    assert_eq!(client.get_reputation(&ctx.creator).creator_score, 50);
+
+    // 500 units count for the default cap of 100.
+    complete(&ctx, 500 * UNIT);
+    let reputation = client.get_reputation(&ctx.creator);
+    assert_eq!(reputation.creator_score, 50 + 1_000);
+    assert_eq!(reputation.completed, 2);
+}
+
+#[test]
+fn test_failure_takes_points_and_floors_at_zero() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    complete(&ctx, 3 * UNIT);
+    fail(&ctx, 2 * UNIT);
+    assert_eq!(client.get_reputation(&ctx.creator).creator_score, 10);
+
+    fail(&ctx, 50 * UNIT);
+    let reputation = client.get_reputation(&ctx.creator);
+    assert_eq!(reputation.creator_score, 0);
+    assert_eq!((reputation.completed, reputation.failed), (1, 2));
+}
+
+#[test]
+fn test_dust_and_sub_minimum_vaults_earn_nothing() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    complete(&ctx, UNIT - 1);
+    let reputation = client.get_reputation(&ctx.creator);
+    assert_eq!((reputation.creator_score, reputation.completed), (0, 1));
+
+    client.set_config(&Config {
+        min_amount: 10 * UNIT,
+        ..Config::default()
+    });
+    complete(&ctx, 9 * UNIT);
+    assert_eq!(client.get_reputation(&ctx.creator), reputation);
+    complete(&ctx, 10 * UNIT);
+    assert_eq!(client.get_reputation(&ctx.creator).creator_score, 100);
+}
+
+#[test]
+fn test_weights_come_from_config() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    client.set_config(&Config {
+        reputation: ReputationWeights {
+            stake_unit: 10,
+            max_units: u32::MAX,
+            completion: u32::MAX,
+            ..ReputationWeights::default()
+        },
+        ..Config::default()
+    });
+
+    complete(&ctx, 500 * UNIT);
+    assert_eq!(client.get_reputation(&ctx.creator).creator_score, MAX_SCORE);
+}
+
+#[test]
+fn test_verifier_is_credited_once_per_vault() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = vault_with(&ctx, UNIT, Some(&ctx.verifier));
+
+    client.submit_proof(&id, &BytesN::from_array(&ctx.env, &[2u8; 32]));
+    client.request_changes(&id, &ReasonCode::Incomplete);
+    client.submit_proof(&id, &BytesN::from_array(&ctx.env, &[3u8; 32]));
+    client.request_changes(&id, &ReasonCode::Incomplete);
+    client.validate_milestone(&id);
+
+    assert_eq!(
+        client.get_reputation(&ctx.verifier),
+        Reputation {
+            verifier_score: 5,
+            responses: 1,
+            ..Reputation::default()
+        }
+    );
+
+    client.release_funds(&id, &ctx.usdc_token);
+    assert_eq!(client.get_reputation(&ctx.verifier).timeouts, 0);
+}
+
+#[test]
+fn test_self_attested_completion_earns_nothing() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    let id = vault_with(&ctx, 10 * UNIT, None);
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    assert_eq!(client.get_reputation(&ctx.creator), Reputation::default());
+
+    // Failing a self-attested vault still costs points.
+    complete(&ctx, 3 * UNIT);
+    ctx.advance_to_start();
+    let failed = vault_with(&ctx, UNIT, None);
+    ctx.advance_past_deadline();
+    client.redirect_funds(&failed, &ctx.usdc_token);
+    let reputation = client.get_reputation(&ctx.creator);
+    assert_eq!((reputation.creator_score, reputation.failed), (20, 1));
+}
+
+#[test]
+fn test_recurring_completion_is_weighted_by_validated_slices() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let amount = 40 * UNIT;
+    ctx.mint(&ctx.creator, amount);
+    let id = client.create_recurring_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &amount,
+        &ctx.start_timestamp,
+        &RecurringSchedule {
+            period_length: 100,
+            period_count: 4,
+            release_immediately: false,
+        },
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+    );
+
+    ctx.advance_to(ctx.start_timestamp);
+    client.validate_period(&id, &0, &ctx.usdc_token);
+    ctx.advance_to(ctx.start_timestamp + 400);
+    for index in 1..4 {
+        client.redirect_missed_period(&id, &index, &ctx.usdc_token);
+    }
+    let reputation = client.get_reputation(&ctx.creator);
+    assert_eq!((reputation.creator_score, reputation.completed), (100, 1));
+}
+
+#[test]
+fn test_deadline_release_counts_as_verifier_timeout() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.advance_to_start();
+    for _ in 0..5 {
+        let id = vault_with(&ctx, UNIT, Some(&ctx.verifier));
+        client.validate_milestone(&id);
+    }
+    let silent = vault_with(&ctx, UNIT, Some(&ctx.verifier));
+
+    ctx.advance_past_deadline();
+    client.release_funds(&silent, &ctx.usdc_token);
+    let reputation = client.get_reputation(&ctx.verifier);
+    assert_eq!(reputation.verifier_score, 25 - 20);
+    assert_eq!(reputation.timeouts, 1);
+    // The creator still completed.
+    assert_eq!(client.get_reputation(&ctx.creator).creator_score, 10);
+
+    // Redirecting an unvalidated vault is the normal failure path, not a timeout.
+    ctx.advance_to_start();
+    let failed = vault_with(&ctx, UNIT, Some(&ctx.verifier));
+    ctx.advance_past_deadline();
+    client.redirect_funds(&failed, &ctx.usdc_token);
+    assert_eq!(client.get_reputation(&ctx.verifier).timeouts, 1);
+}
+
+#[test]
+fn test_cancellation_leaves_reputation_untouched() {
+    let ctx = VaultTestContext::new();
+    let id = vault_with(&ctx, 10 * UNIT, Some(&ctx.verifier));
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    assert_eq!(
+        ctx.client().get_reputation(&ctx.creator),
+        Reputation::default()
+    );
+}
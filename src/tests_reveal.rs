@@ -0,0 +1,144 @@
+//! Commit-reveal of milestone descriptions.
+
+use soroban_sdk::{Bytes, BytesN, IntoVal, Symbol};
+
+use crate::events::MILESTONE_REVEALED;
+use crate::hashing::HashAlg;
+use crate::reveal::commitment;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+const DESCRIPTION: &[u8] = b"Ship the beta to ten users";
+
+fn description(ctx: &VaultTestContext) -> Bytes {
+    Bytes::from_slice(&ctx.env, DESCRIPTION)
+}
+
+fn salt(ctx: &VaultTestContext) -> BytesN<32> {
+    BytesN::from_array(&ctx.env, &[7u8; 32])
+}
+
+/// Self-attested vault committing to `DESCRIPTION` under `salt`.
+fn create_committed_vault(ctx: &VaultTestContext, reveal_required: bool) -> u32 {
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &commitment(&ctx.env, HashAlg::Sha256, &description(ctx), &salt(ctx)),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            reveal_required,
+            ..VaultOptions::default()
+        },
+    )
+}
+
+#[test]
+fn test_reveal_publishes_the_description() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_committed_vault(&ctx, false);
+    assert_eq!(client.get_revealed_description_hash(&id), None);
+
+    assert!(client.reveal_milestone(&id, &description(&ctx), &salt(&ctx)));
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, MILESTONE_REVEALED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let revealed: Bytes = data.into_val(&ctx.env);
+    assert_eq!(revealed, description(&ctx));
+    assert_eq!(
+        client.get_revealed_description_hash(&id),
+        Some(ctx.env.crypto().sha256(&description(&ctx)).into())
+    );
+
+    assert_eq!(
+        client.try_reveal_milestone(&id, &description(&ctx), &salt(&ctx)),
+        Err(Ok(Error::AlreadyRevealed))
+    );
+}
+
+#[test]
+fn test_wrong_salt_or_description_is_rejected() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_committed_vault(&ctx, false);
+
+    let wrong_salt = BytesN::from_array(&ctx.env, &[8u8; 32]);
+    assert_eq!(
+        client.try_reveal_milestone(&id, &description(&ctx), &wrong_salt),
+        Err(Ok(Error::RevealMismatch))
+    );
+    let wrong_description = Bytes::from_slice(&ctx.env, b"Ship the beta to one user");
+    assert_eq!(
+        client.try_reveal_milestone(&id, &wrong_description, &salt(&ctx)),
+        Err(Ok(Error::RevealMismatch))
+    );
+    assert_eq!(client.get_revealed_description_hash(&id), None);
+    ctx.assert_no_events();
+}
+
+#[test]
+fn test_reveal_required_gates_self_attested_release() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_committed_vault(&ctx, true);
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::RevealRequired))
+    );
+    client.reveal_milestone(&id, &description(&ctx), &salt(&ctx));
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+}
+
+#[test]
+fn test_reveal_is_optional_by_default_and_allowed_after_settlement() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create_committed_vault(&ctx, false);
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+
+    assert!(client.reveal_milestone(&id, &description(&ctx), &salt(&ctx)));
+}
+
+#[test]
+fn test_reveal_required_does_not_gate_verified_vaults() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            reveal_required: true,
+            ..VaultOptions::default()
+        },
+    );
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+}
@@ -0,0 +1,191 @@
+//! Savings locks: the blocked early withdrawal, its optional penalty and the release.
+
+use soroban_sdk::{Address, IntoVal, Symbol};
+
+use crate::config::Config;
+use crate::events::{EARLY_WITHDRAWAL, VAULT_CANCELLED};
+use crate::savings::VaultMode;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+/// 5% of the stake.
+const PENALTY_BPS: u32 = 500;
+const PENALTY: i128 = 50_000;
+
+fn options() -> VaultOptions {
+    VaultOptions {
+        mode: VaultMode::SavingsLock,
+        ..VaultOptions::default()
+    }
+}
+
+fn try_create(
+    ctx: &VaultTestContext,
+    verifier: Option<Address>,
+    destination: &Address,
+    options: &VaultOptions,
+) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_vault(
+            &ctx.usdc_token,
+            &ctx.creator,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            &verifier,
+            destination,
+            &ctx.creator,
+            options,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn create(ctx: &VaultTestContext) -> u32 {
+    try_create(ctx, None, &ctx.creator, &options()).unwrap()
+}
+
+fn enable_early_withdrawal(ctx: &VaultTestContext) {
+    ctx.client().set_config(&Config {
+        early_withdrawal_bps: PENALTY_BPS,
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_anyone_releases_a_savings_lock_to_the_creator_after_the_deadline() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().mode,
+        VaultMode::SavingsLock
+    );
+
+    ctx.advance_to(ctx.end_timestamp - 1);
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    ctx.advance_to_deadline();
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+}
+
+#[test]
+fn test_proof_minimum_does_not_hold_back_a_savings_lock() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    client.set_config(&Config {
+        timeout_min_proofs: 3,
+        ..Config::default()
+    });
+    let id = create(&ctx);
+
+    ctx.advance_to_deadline();
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_early_withdrawal_is_blocked_by_default() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_cancel_vault(&id, &ctx.usdc_token),
+        Err(Ok(Error::SavingsLocked))
+    );
+    ctx.assert_balances(&[(&ctx.creator, 0), (&ctx.contract_id, ctx.amount)]);
+
+    // At the deadline the lock is over and cancelling costs nothing.
+    ctx.advance_to_deadline();
+    client.cancel_vault(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_early_withdrawal_pays_the_penalty_to_the_protocol() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let env = &ctx.env;
+    enable_early_withdrawal(&ctx);
+    let id = create(&ctx);
+
+    ctx.advance_to_start();
+    client.cancel_vault(&id, &ctx.usdc_token);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, EARLY_WITHDRAWAL), id, ctx.creator.clone()).into_val(env)
+    );
+    let penalty: i128 = data.into_val(env);
+    assert_eq!(penalty, PENALTY);
+    let (topics, data) = events.get(1).unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(env, VAULT_CANCELLED), id, ctx.creator.clone()).into_val(env)
+    );
+    let refund: i128 = data.into_val(env);
+    assert_eq!(refund, ctx.amount - PENALTY);
+
+    ctx.assert_balances(&[
+        (&ctx.creator, ctx.amount - PENALTY),
+        (&ctx.contract_id, PENALTY),
+    ]);
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), PENALTY);
+}
+
+#[test]
+fn test_savings_lock_has_nothing_to_validate_or_redirect() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = create(&ctx);
+
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_validate_milestone(&id),
+        Err(Ok(Error::InvalidStatus))
+    );
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_redirect_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_savings_lock_terms_are_fixed() {
+    let ctx = VaultTestContext::new();
+    assert_eq!(
+        try_create(&ctx, Some(ctx.verifier.clone()), &ctx.creator, &options()),
+        Err(Error::InvalidSavingsLock)
+    );
+    assert_eq!(
+        try_create(&ctx, None, &ctx.success_dest, &options()),
+        Err(Error::InvalidSavingsLock)
+    );
+    let graded = VaultOptions {
+        allow_graded: true,
+        ..options()
+    };
+    assert_eq!(
+        try_create(&ctx, None, &ctx.creator, &graded),
+        Err(Error::InvalidSavingsLock)
+    );
+
+    // The same terms are fine for a standard vault.
+    let id = try_create(&ctx, None, &ctx.success_dest, &VaultOptions::default()).unwrap();
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().mode,
+        VaultMode::Standard
+    );
+}
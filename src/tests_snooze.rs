@@ -0,0 +1,297 @@
+//! One-time snooze: the deadline push, its fee, and every case that blocks it.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, IntoVal, Symbol};
+
+use crate::config::Config;
+use crate::events::{DEADLINE_EXTENDED, VAULT_FORFEITED};
+use crate::group::GroupPayout;
+use crate::hashing::HashAlg;
+use crate::savings::VaultMode;
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions};
+
+const MAX_SNOOZE: u64 = 500;
+/// 2% of the stake.
+const FEE_BPS: u32 = 200;
+const FEE: i128 = 20_000;
+
+fn configure(ctx: &VaultTestContext) {
+    ctx.client().set_config(&Config {
+        max_snooze_seconds: MAX_SNOOZE,
+        snooze_fee_bps: FEE_BPS,
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_snooze_pushes_the_deadline_and_pays_the_fee() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let id = ctx.create_default_vault();
+
+    assert_eq!(
+        client.snooze(&id, &MAX_SNOOZE, &ctx.usdc_token),
+        ctx.end_timestamp + MAX_SNOOZE
+    );
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    let (topics, data) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, DEADLINE_EXTENDED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let payload: (u64, u64, i128) = data.into_val(&ctx.env);
+    assert_eq!(
+        payload,
+        (ctx.end_timestamp, ctx.end_timestamp + MAX_SNOOZE, FEE)
+    );
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, FEE),
+        (&ctx.contract_id, ctx.amount - FEE),
+    ]);
+    let vault = client.get_vault_state(&id).unwrap();
+    assert_eq!(vault.amount, ctx.amount - FEE);
+    assert_eq!(vault.end_timestamp, ctx.end_timestamp + MAX_SNOOZE);
+    assert!(client.is_snoozed(&id));
+
+    // The old deadline no longer closes validation; the snoozed vault settles what is left.
+    ctx.advance_to_deadline();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount - FEE), (&ctx.contract_id, 0)]);
+}
+
+#[test]
+fn test_snooze_is_allowed_once() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let id = ctx.create_default_vault();
+
+    client.snooze(&id, &100, &ctx.usdc_token);
+    assert_eq!(
+        client.try_snooze(&id, &100, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySnoozed))
+    );
+}
+
+#[test]
+fn test_snooze_length_is_bounded() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = ctx.create_default_vault();
+    // Disabled until configured.
+    assert_eq!(
+        client.try_snooze(&id, &1, &ctx.usdc_token),
+        Err(Ok(Error::InvalidSnooze))
+    );
+
+    configure(&ctx);
+    for extra in [0, MAX_SNOOZE + 1] {
+        assert_eq!(
+            client.try_snooze(&id, &extra, &ctx.usdc_token),
+            Err(Ok(Error::InvalidSnooze))
+        );
+    }
+    assert!(!client.is_snoozed(&id));
+}
+
+#[test]
+fn test_snooze_is_blocked_after_the_deadline_and_on_validated_vaults() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    ctx.mint(&ctx.creator, ctx.amount);
+    let validated = ctx.create_default_vault();
+    let late = ctx.create_default_vault();
+
+    ctx.advance_to_start();
+    client.validate_milestone(&validated);
+    assert_eq!(
+        client.try_snooze(&validated, &100, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_snooze(&late, &100, &ctx.usdc_token),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    client.redirect_funds(&late, &ctx.usdc_token);
+    assert_eq!(
+        client.try_snooze(&late, &100, &ctx.usdc_token),
+        Err(Ok(Error::VaultNotActive))
+    );
+}
+
+#[test]
+fn test_graded_vault_cannot_be_snoozed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            allow_graded: true,
+            ..VaultOptions::default()
+        },
+    );
+    ctx.advance_to_start();
+    client.validate_graded(&id, &0);
+    assert_eq!(
+        client.try_snooze(&id, &100, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_burn_vault_burns_the_snooze_fee() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            burn_on_failure: true,
+            ..VaultOptions::default()
+        },
+    );
+
+    client.snooze(&id, &MAX_SNOOZE, &ctx.usdc_token);
+    let (topics, data) = ctx.vault_events().get(1).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, VAULT_FORFEITED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let forfeited: (i128, bool) = data.into_val(&ctx.env);
+    assert_eq!(forfeited, (FEE, true));
+    ctx.assert_balances(&[(&ctx.failure_dest, 0), (&ctx.contract_id, ctx.amount - FEE)]);
+    assert_eq!(client.get_accrued_fees(&ctx.usdc_token), 0);
+}
+
+#[test]
+fn test_shared_deadlines_cannot_be_snoozed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let funder = Address::generate(&ctx.env);
+    ctx.mint(&funder, 2 * ctx.amount);
+    ctx.mint(&ctx.creator, 2 * ctx.amount);
+
+    let wager = client.create_wager(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &Address::generate(&ctx.env),
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &ctx.verifier,
+    );
+    let group = client.create_group_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &vec![&ctx.env, ctx.creator.clone()],
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &GroupPayout::Proportional,
+    );
+    client.join_vault(&group, &ctx.creator, &ctx.amount, &ctx.usdc_token);
+    let sponsored = client.create_sponsored_vault(
+        &ctx.usdc_token,
+        &funder,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &None,
+    );
+    let gift = client.create_gift(
+        &ctx.usdc_token,
+        &funder,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &None,
+    );
+    let bounty = client.create_bounty(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &false,
+        &HashAlg::Sha256,
+    );
+
+    for id in [wager, group, sponsored, gift, bounty] {
+        assert_eq!(
+            client.try_snooze(&id, &100, &ctx.usdc_token),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert!(!client.is_snoozed(&id));
+    }
+}
+
+#[test]
+fn test_savings_lock_cannot_be_snoozed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    configure(&ctx);
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.creator,
+        &ctx.creator,
+        &VaultOptions {
+            mode: VaultMode::SavingsLock,
+            ..VaultOptions::default()
+        },
+    );
+    assert_eq!(
+        client.try_snooze(&id, &MAX_SNOOZE, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert!(!client.is_snoozed(&id));
+}
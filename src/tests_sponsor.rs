@@ -0,0 +1,166 @@
+//! Sponsored vaults: funder-paid stakes, funder refunds and shared cancellation rights.
+
+extern crate std;
+
+use soroban_sdk::{testutils::Address as _, Address, IntoVal, Symbol};
+
+use crate::events::{VAULT_CREATED, VAULT_SPONSORED};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+struct Sponsored {
+    ctx: VaultTestContext,
+    funder: Address,
+}
+
+impl Sponsored {
+    /// A funder holding one stake; the creator's own balance stays untouched.
+    fn new() -> Self {
+        let ctx = VaultTestContext::new();
+        let funder = Address::generate(&ctx.env);
+        ctx.mint(&funder, ctx.amount);
+        Sponsored { ctx, funder }
+    }
+
+    fn try_create(&self, failure_destination: Option<&Address>) -> Result<u32, Error> {
+        let ctx = &self.ctx;
+        ctx.client()
+            .try_create_sponsored_vault(
+                &ctx.usdc_token,
+                &self.funder,
+                &ctx.creator,
+                &ctx.amount,
+                &ctx.start_timestamp,
+                &ctx.end_timestamp,
+                &ctx.milestone_hash(),
+                &Some(ctx.verifier.clone()),
+                &ctx.success_dest,
+                &failure_destination.cloned(),
+            )
+            .map(|id| id.unwrap())
+            .map_err(|e| e.unwrap())
+    }
+
+    fn create(&self) -> u32 {
+        self.try_create(None).unwrap()
+    }
+
+    fn authorizers(&self) -> std::vec::Vec<Address> {
+        self.ctx
+            .env
+            .auths()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect()
+    }
+}
+
+#[test]
+fn test_funder_pays_and_creator_completes() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    let client = ctx.client();
+    let id = sponsored.create();
+
+    let events = ctx.vault_events();
+    let name = |i: u32| -> Symbol { events.get(i).unwrap().0.get(0).unwrap().into_val(&ctx.env) };
+    assert_eq!(name(0), Symbol::new(&ctx.env, VAULT_CREATED));
+    assert_eq!(name(1), Symbol::new(&ctx.env, VAULT_SPONSORED));
+    assert_eq!(
+        events.get(1).unwrap().0,
+        (
+            Symbol::new(&ctx.env, VAULT_SPONSORED),
+            id,
+            sponsored.funder.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    ctx.assert_balances(&[
+        (&sponsored.funder, 0),
+        (&ctx.creator, ctx.amount),
+        (&ctx.contract_id, ctx.amount),
+    ]);
+    assert_eq!(client.get_funder(&id), Some(sponsored.funder.clone()));
+
+    ctx.advance_to_start();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+    assert_eq!(client.get_streak(&ctx.creator).current, 1);
+    assert_eq!(client.get_streak(&sponsored.funder).current, 0);
+}
+
+#[test]
+fn test_failure_returns_stake_to_funder_by_default() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    let id = sponsored.create();
+    assert_eq!(
+        ctx.client()
+            .get_vault_state(&id)
+            .unwrap()
+            .failure_destination,
+        sponsored.funder
+    );
+
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&sponsored.funder, ctx.amount), (&ctx.creator, ctx.amount)]);
+}
+
+#[test]
+fn test_failure_can_go_to_a_chosen_destination() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    let id = sponsored.try_create(Some(&ctx.failure_dest)).unwrap();
+
+    ctx.advance_past_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.failure_dest, ctx.amount), (&sponsored.funder, 0)]);
+}
+
+#[test]
+fn test_funder_alone_cancels_before_start() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    let id = sponsored.create();
+
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    assert_eq!(sponsored.authorizers(), std::vec![sponsored.funder.clone()]);
+    ctx.assert_balances(&[(&sponsored.funder, ctx.amount), (&ctx.creator, ctx.amount)]);
+    let history = ctx.client().get_vault_history(&id);
+    assert_eq!(history.last().unwrap().actor, sponsored.funder);
+}
+
+#[test]
+fn test_cancel_after_start_needs_funder_and_creator() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    let id = sponsored.create();
+
+    ctx.advance_to_start();
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    assert_eq!(
+        sponsored.authorizers(),
+        [sponsored.funder.clone(), ctx.creator.clone()]
+    );
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().status,
+        VaultStatus::Cancelled
+    );
+    ctx.assert_balances(&[(&sponsored.funder, ctx.amount)]);
+}
+
+#[test]
+fn test_create_requires_both_parties_and_checks_funder_balance() {
+    let sponsored = Sponsored::new();
+    let ctx = &sponsored.ctx;
+    sponsored.create();
+    assert_eq!(
+        sponsored.authorizers(),
+        [sponsored.funder.clone(), ctx.creator.clone()]
+    );
+
+    // The funder spent its only stake; the creator's balance does not count.
+    assert_eq!(sponsored.try_create(None), Err(Error::InsufficientBalance));
+}
@@ -0,0 +1,174 @@
+//! Creator streaks across consecutive vault settlements.
+
+use soroban_sdk::{testutils::Address as _, Address};
+
+use crate::config::Config;
+use crate::streak::StreakInfo;
+use crate::testutils::VaultTestContext;
+use crate::{VaultOptions, VaultStatus};
+
+/// Create a vault for the context's creator with window `[start, start + 900)`.
+fn vault_starting_at(ctx: &VaultTestContext, start: u64) -> u32 {
+    ctx.mint(&ctx.creator, ctx.amount);
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &start,
+        &(start + 900),
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    )
+}
+
+/// Complete a vault started at `start`, validating and releasing at that instant.
+fn complete_at(ctx: &VaultTestContext, start: u64) {
+    ctx.advance_to(start);
+    let id = vault_starting_at(ctx, start);
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+}
+
+fn fail_at(ctx: &VaultTestContext, start: u64) {
+    let id = vault_starting_at(ctx, start);
+    ctx.advance_to(start + 900);
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+}
+
+fn configure_window(ctx: &VaultTestContext, streak_window: u64) {
+    ctx.client().set_config(&Config {
+        streak_window,
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_completions_build_a_streak_and_failure_resets_it() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    assert_eq!(client.get_streak(&ctx.creator), StreakInfo::default());
+
+    complete_at(&ctx, 100);
+    complete_at(&ctx, 200);
+    complete_at(&ctx, 300);
+    assert_eq!(
+        client.get_streak(&ctx.creator),
+        StreakInfo {
+            current: 3,
+            longest: 3,
+            last_completed_at: Some(300),
+        }
+    );
+
+    fail_at(&ctx, 400);
+    let streak = client.get_streak(&ctx.creator);
+    assert_eq!((streak.current, streak.longest), (0, 3));
+    assert_eq!(streak.last_completed_at, Some(300));
+
+    complete_at(&ctx, 2_000);
+    let streak = client.get_streak(&ctx.creator);
+    assert_eq!((streak.current, streak.longest), (1, 3));
+}
+
+#[test]
+fn test_cancellation_leaves_streak_untouched() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    complete_at(&ctx, 100);
+
+    let id = vault_starting_at(&ctx, 200);
+    client.cancel_vault(&id, &ctx.usdc_token);
+    assert_eq!(client.get_streak(&ctx.creator).current, 1);
+
+    complete_at(&ctx, 300);
+    assert_eq!(client.get_streak(&ctx.creator).current, 2);
+}
+
+#[test]
+fn test_window_continues_streak_up_to_its_last_second() {
+    let ctx = VaultTestContext::new();
+    configure_window(&ctx, 500);
+    complete_at(&ctx, 100);
+
+    // Started exactly `streak_window` after the previous completion.
+    complete_at(&ctx, 600);
+    assert_eq!(ctx.client().get_streak(&ctx.creator).current, 2);
+}
+
+#[test]
+fn test_window_break_starts_a_new_streak() {
+    let ctx = VaultTestContext::new();
+    configure_window(&ctx, 500);
+    complete_at(&ctx, 100);
+    complete_at(&ctx, 600);
+
+    complete_at(&ctx, 1_101);
+    assert_eq!(
+        ctx.client().get_streak(&ctx.creator),
+        StreakInfo {
+            current: 1,
+            longest: 2,
+            last_completed_at: Some(1_101),
+        }
+    );
+}
+
+#[test]
+fn test_no_window_by_default() {
+    let ctx = VaultTestContext::new();
+    complete_at(&ctx, 100);
+    complete_at(&ctx, 20_000_000);
+    assert_eq!(ctx.client().get_streak(&ctx.creator).current, 2);
+}
+
+#[test]
+fn test_streaks_are_per_creator() {
+    let ctx = VaultTestContext::new();
+    complete_at(&ctx, 100);
+    assert_eq!(
+        ctx.client().get_streak(&Address::generate(&ctx.env)),
+        StreakInfo::default()
+    );
+}
+
+#[test]
+fn test_self_attested_and_sub_minimum_vaults_do_not_count() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    complete_at(&ctx, 100);
+
+    ctx.advance_to(200);
+    ctx.mint(&ctx.creator, ctx.amount);
+    let id = ctx.create_vault_no_verifier();
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    assert_eq!(
+        client.get_vault_state(&id).unwrap().status,
+        VaultStatus::Completed
+    );
+    assert_eq!(client.get_streak(&ctx.creator).current, 1);
+
+    // Vaults opened before the minimum was raised.
+    ctx.advance_to(300);
+    let small = vault_starting_at(&ctx, 300);
+    let failing = vault_starting_at(&ctx, 400);
+    client.set_config(&Config {
+        min_amount: ctx.amount + 1,
+        ..Config::default()
+    });
+    client.validate_milestone(&small);
+    client.release_funds(&small, &ctx.usdc_token);
+    ctx.advance_to(1_300);
+    client.redirect_funds(&failing, &ctx.usdc_token);
+    assert_eq!(
+        client.get_streak(&ctx.creator),
+        StreakInfo {
+            current: 1,
+            longest: 1,
+            last_completed_at: Some(100),
+        }
+    );
+}
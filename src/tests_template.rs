@@ -0,0 +1,140 @@
+//! Vault templates: saving, instantiating, listing and deleting.
+
+use soroban_sdk::{testutils::Address as _, Address, BytesN};
+
+use crate::template::{TemplateParams, MAX_TEMPLATES_PER_OWNER};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultOptions, VaultStatus};
+
+fn params(ctx: &VaultTestContext) -> TemplateParams {
+    TemplateParams {
+        usdc_token: ctx.usdc_token.clone(),
+        amount: ctx.amount,
+        milestone_hash: ctx.milestone_hash(),
+        verifier: Some(ctx.verifier.clone()),
+        success_destination: ctx.success_dest.clone(),
+        failure_destination: ctx.failure_dest.clone(),
+        options: VaultOptions::default(),
+    }
+}
+
+#[test]
+fn test_template_instantiates_repeatable_vaults() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.mint(&ctx.creator, ctx.amount);
+    let template_id = client.create_template(&ctx.creator, &params(&ctx));
+
+    let first = client.create_from_template(&template_id, &100, &1_000, &None);
+    let second = client.create_from_template(&template_id, &2_000, &3_000, &Some(400_000));
+
+    let vault = client.get_vault_state(&first).unwrap();
+    assert_eq!(vault.creator, ctx.creator);
+    assert_eq!(vault.amount, ctx.amount);
+    assert_eq!(vault.verifier, Some(ctx.verifier.clone()));
+    assert_eq!(vault.status, VaultStatus::Active);
+    let vault = client.get_vault_state(&second).unwrap();
+    assert_eq!(
+        (vault.start_timestamp, vault.end_timestamp, vault.amount),
+        (2_000, 3_000, 400_000)
+    );
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount + 400_000)]);
+}
+
+#[test]
+fn test_instantiation_applies_current_rules() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    client.add_charity(&ctx.failure_dest, &BytesN::from_array(&ctx.env, &[0u8; 32]));
+    let template_id = client.create_template(
+        &ctx.creator,
+        &TemplateParams {
+            options: VaultOptions {
+                require_registered_charity: true,
+                ..VaultOptions::default()
+            },
+            ..params(&ctx)
+        },
+    );
+
+    assert_eq!(
+        client.try_create_from_template(&template_id, &500, &100, &None),
+        Err(Ok(Error::InvalidTimestamps))
+    );
+    assert_eq!(
+        client.try_create_from_template(&template_id, &100, &1_000, &Some(0)),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    // The charity was registered when the template was saved, but not any more.
+    client.remove_charity(&ctx.failure_dest);
+    assert_eq!(
+        client.try_create_from_template(&template_id, &100, &1_000, &None),
+        Err(Ok(Error::UnregisteredDestination))
+    );
+}
+
+#[test]
+fn test_templates_are_listed_and_deleted_per_owner() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let first = client.create_template(&ctx.creator, &params(&ctx));
+    let second = client.create_template(&ctx.creator, &params(&ctx));
+    let other = Address::generate(&ctx.env);
+    client.create_template(&other, &params(&ctx));
+
+    let listed = client.list_templates(&ctx.creator);
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed.get(0).unwrap().0, first);
+    assert_eq!(listed.get(1).unwrap().1.owner, ctx.creator);
+
+    client.delete_template(&first);
+    assert_eq!(ctx.env.auths()[0].0, ctx.creator);
+    let listed = client.list_templates(&ctx.creator);
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed.get(0).unwrap().0, second);
+    assert_eq!(client.get_template(&first), None);
+    assert_eq!(
+        client.try_create_from_template(&first, &100, &1_000, &None),
+        Err(Ok(Error::TemplateNotFound))
+    );
+    assert_eq!(
+        client.try_delete_template(&first),
+        Err(Ok(Error::TemplateNotFound))
+    );
+    assert_eq!(client.list_templates(&other).len(), 1);
+}
+
+#[test]
+fn test_create_template_validates_what_it_can() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    assert_eq!(
+        client.try_create_template(
+            &ctx.creator,
+            &TemplateParams {
+                amount: 0,
+                ..params(&ctx)
+            }
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_create_template(
+            &ctx.creator,
+            &TemplateParams {
+                failure_destination: ctx.contract_id.clone(),
+                ..params(&ctx)
+            }
+        ),
+        Err(Ok(Error::InvalidDestination))
+    );
+
+    for _ in 0..MAX_TEMPLATES_PER_OWNER {
+        client.create_template(&ctx.creator, &params(&ctx));
+    }
+    assert_eq!(
+        client.try_create_template(&ctx.creator, &params(&ctx)),
+        Err(Ok(Error::TooManyTemplates))
+    );
+}
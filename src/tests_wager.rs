@@ -0,0 +1,271 @@
+//! Peer wagers: acceptance, reclaim and every cell of the settlement matrix.
+
+use soroban_sdk::{
+    testutils::Address as _, token::StellarAssetClient, Address, IntoVal, Symbol, TryIntoVal,
+};
+
+use crate::events::{VAULT_ACTIVATED, WAGER_ACCEPTED};
+use crate::testutils::VaultTestContext;
+use crate::{Error, VaultStatus};
+
+/// The challenger, funded with a matching stake.
+fn funded_challenger(ctx: &VaultTestContext) -> Address {
+    let challenger = Address::generate(&ctx.env);
+    ctx.mint(&challenger, ctx.amount);
+    challenger
+}
+
+fn try_create(
+    ctx: &VaultTestContext,
+    challenger: &Address,
+    verifier: &Address,
+) -> Result<u32, Error> {
+    ctx.client()
+        .try_create_wager(
+            &ctx.usdc_token,
+            &ctx.creator,
+            challenger,
+            &ctx.amount,
+            &ctx.start_timestamp,
+            &ctx.end_timestamp,
+            &ctx.milestone_hash(),
+            verifier,
+        )
+        .map(|id| id.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+fn accept(ctx: &VaultTestContext, challenger: &Address) -> u32 {
+    let id = try_create(ctx, challenger, &ctx.verifier).unwrap();
+    ctx.client().accept_wager(&id, &ctx.usdc_token);
+    id
+}
+
+/// An accepted wager, at its start.
+fn accepted_wager(ctx: &VaultTestContext, challenger: &Address) -> u32 {
+    let id = accept(ctx, challenger);
+    ctx.advance_to_start();
+    id
+}
+
+fn status(ctx: &VaultTestContext, id: u32) -> VaultStatus {
+    ctx.client().get_vault_state(&id).unwrap().status
+}
+
+#[test]
+fn test_accept_escrows_both_stakes() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = try_create(&ctx, &challenger, &ctx.verifier).unwrap();
+    assert_eq!(status(&ctx, id), VaultStatus::Pending);
+    ctx.assert_balances(&[(&ctx.contract_id, ctx.amount)]);
+
+    assert!(client.accept_wager(&id, &ctx.usdc_token));
+    assert_eq!(ctx.env.auths()[0].0, challenger);
+    let events = ctx.vault_events();
+    let (topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, WAGER_ACCEPTED),
+            id,
+            challenger.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let stake: i128 = data.try_into_val(&ctx.env).unwrap();
+    assert_eq!(stake, ctx.amount);
+    assert_eq!(
+        events.get(1).unwrap().0,
+        (
+            Symbol::new(&ctx.env, VAULT_ACTIVATED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    ctx.assert_balances(&[(&ctx.contract_id, 2 * ctx.amount), (&challenger, 0)]);
+    let vault = client.get_vault_state(&id).unwrap();
+    assert_eq!(
+        (vault.status, vault.amount),
+        (VaultStatus::Active, 2 * ctx.amount)
+    );
+    assert!(client.get_wager(&id).unwrap().accepted);
+    assert_eq!(
+        client.try_accept_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_validated_wager_pays_the_creator() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = accepted_wager(&ctx, &challenger);
+
+    client.validate_milestone(&id);
+    client.release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.creator, 2 * ctx.amount),
+        (&challenger, 0),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(status(&ctx, id), VaultStatus::Completed);
+}
+
+#[test]
+fn test_expired_wager_pays_the_challenger() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = accepted_wager(&ctx, &challenger);
+
+    ctx.advance_to_deadline();
+    // The deadline does not release a wager to the creator.
+    assert_eq!(
+        client.try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::NotAuthorized))
+    );
+    client.redirect_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, 0), (&challenger, 2 * ctx.amount)]);
+    assert_eq!(status(&ctx, id), VaultStatus::Failed);
+}
+
+#[test]
+fn test_rejected_wager_pays_the_challenger_now() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = accepted_wager(&ctx, &challenger);
+
+    assert!(client.reject_wager(&id, &ctx.usdc_token));
+    assert_eq!(ctx.env.auths()[0].0, ctx.verifier);
+    ctx.assert_balances(&[(&ctx.creator, 0), (&challenger, 2 * ctx.amount)]);
+    assert_eq!(status(&ctx, id), VaultStatus::Failed);
+    assert_eq!(client.get_streak(&ctx.creator).current, 0);
+}
+
+#[test]
+fn test_draw_returns_both_stakes() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = accepted_wager(&ctx, &challenger);
+
+    assert!(client.declare_draw(&id, &ctx.usdc_token));
+    ctx.assert_balances(&[
+        (&ctx.creator, ctx.amount),
+        (&challenger, ctx.amount),
+        (&ctx.contract_id, 0),
+    ]);
+    assert_eq!(status(&ctx, id), VaultStatus::Cancelled);
+}
+
+#[test]
+fn test_rulings_need_an_open_unvalidated_wager() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    ctx.mint(&ctx.creator, 2 * ctx.amount);
+    let id = accept(&ctx, &funded_challenger(&ctx));
+    let other = accepted_wager(&ctx, &funded_challenger(&ctx));
+
+    client.validate_milestone(&id);
+    assert_eq!(
+        client.try_reject_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    ctx.advance_to_deadline();
+    assert_eq!(
+        client.try_declare_draw(&other, &ctx.usdc_token),
+        Err(Ok(Error::MilestoneExpired))
+    );
+    assert_eq!(
+        client.try_reject_wager(&ctx.create_default_vault(), &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_accept_must_match_the_creator_s_token() {
+    let ctx = VaultTestContext::new();
+    let challenger = funded_challenger(&ctx);
+    let id = try_create(&ctx, &challenger, &ctx.verifier).unwrap();
+    let junk = ctx
+        .env
+        .register_stellar_asset_contract_v2(challenger.clone())
+        .address();
+    StellarAssetClient::new(&ctx.env, &junk).mint(&challenger, &ctx.amount);
+
+    assert_eq!(
+        ctx.client().try_accept_wager(&id, &junk),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(status(&ctx, id), VaultStatus::Pending);
+}
+
+#[test]
+fn test_unaccepted_wager_is_reclaimed_after_start() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let challenger = funded_challenger(&ctx);
+    let id = try_create(&ctx, &challenger, &ctx.verifier).unwrap();
+
+    assert_eq!(
+        client.try_reclaim_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    ctx.advance_to_start();
+    assert_eq!(
+        client.try_accept_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidTimestamp))
+    );
+    assert!(client.reclaim_wager(&id, &ctx.usdc_token));
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount), (&challenger, ctx.amount)]);
+    assert_eq!(status(&ctx, id), VaultStatus::Cancelled);
+    assert_eq!(
+        client.try_reclaim_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+#[test]
+fn test_accepted_wager_cannot_be_cancelled_or_reclaimed() {
+    let ctx = VaultTestContext::new();
+    let client = ctx.client();
+    let id = accepted_wager(&ctx, &funded_challenger(&ctx));
+
+    assert_eq!(
+        client.try_cancel_vault(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_reclaim_wager(&id, &ctx.usdc_token),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_create_rejects_overlapping_parties() {
+    let ctx = VaultTestContext::new();
+    let challenger = funded_challenger(&ctx);
+    assert_eq!(
+        try_create(&ctx, &ctx.creator, &ctx.verifier),
+        Err(Error::InvalidWagerParties)
+    );
+    assert_eq!(
+        try_create(&ctx, &challenger, &ctx.creator),
+        Err(Error::InvalidWagerParties)
+    );
+    assert_eq!(
+        try_create(&ctx, &challenger, &challenger),
+        Err(Error::InvalidWagerParties)
+    );
+    ctx.advance_to_start();
+    assert_eq!(
+        try_create(&ctx, &challenger, &ctx.verifier),
+        Err(Error::InvalidTimestamp)
+    );
+}
@@ -0,0 +1,156 @@
+//! Witness attestations.
+
+use soroban_sdk::{testutils::Address as _, vec, Address, IntoVal, Symbol, Vec};
+
+use crate::events::MILESTONE_ATTESTED;
+use crate::testutils::VaultTestContext;
+use crate::witness::{Attestation, MAX_WITNESSES};
+use crate::{Error, VaultOptions, VaultStatus};
+
+fn try_create(ctx: &VaultTestContext, witnesses: Vec<Address>) -> Result<u32, Error> {
+    match ctx.client().try_create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            witnesses: Some(witnesses),
+            ..VaultOptions::default()
+        },
+    ) {
+        Ok(Ok(id)) => Ok(id),
+        Err(Ok(error)) => Err(error),
+        other => panic!("unexpected result {other:?}"),
+    }
+}
+
+struct Setup {
+    ctx: VaultTestContext,
+    witnesses: [Address; 2],
+    id: u32,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let ctx = VaultTestContext::new();
+        let witnesses = core::array::from_fn(|_| Address::generate(&ctx.env));
+        let id = try_create(&ctx, Vec::from_array(&ctx.env, witnesses.clone())).unwrap();
+        Setup { ctx, witnesses, id }
+    }
+}
+
+#[test]
+fn test_witnesses_attest_once_each() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let [first, second] = &setup.witnesses;
+
+    ctx.advance_to(200);
+    assert!(client.attest(&setup.id, second));
+    let (topics, _) = ctx.vault_events().get(0).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, MILESTONE_ATTESTED),
+            setup.id,
+            second.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    ctx.advance_to(300);
+    client.attest(&setup.id, first);
+
+    assert_eq!(
+        client.get_attestations(&setup.id),
+        vec![
+            &ctx.env,
+            Attestation {
+                witness: second.clone(),
+                timestamp: 200,
+            },
+            Attestation {
+                witness: first.clone(),
+                timestamp: 300,
+            },
+        ]
+    );
+    assert_eq!(
+        client.try_attest(&setup.id, first),
+        Err(Ok(Error::AlreadyJoined))
+    );
+    assert_eq!(
+        client.get_witnesses(&setup.id),
+        Vec::from_array(&ctx.env, setup.witnesses.clone())
+    );
+}
+
+#[test]
+fn test_unlisted_addresses_cannot_attest() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    for outsider in [
+        Address::generate(&ctx.env),
+        ctx.creator.clone(),
+        ctx.verifier.clone(),
+    ] {
+        assert_eq!(
+            client.try_attest(&setup.id, &outsider),
+            Err(Ok(Error::NotAuthorized))
+        );
+    }
+
+    ctx.mint(&ctx.creator, ctx.amount);
+    let plain = ctx.create_default_vault();
+    assert_eq!(
+        client.try_attest(&plain, &setup.witnesses[0]),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(client.get_witnesses(&plain), Vec::new(&ctx.env));
+}
+
+#[test]
+fn test_attestations_do_not_affect_settlement() {
+    let setup = Setup::new();
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    for witness in &setup.witnesses {
+        client.attest(&setup.id, witness);
+    }
+    ctx.advance_to_deadline();
+    client.redirect_funds(&setup.id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[(&ctx.failure_dest, ctx.amount), (&ctx.success_dest, 0)]);
+    assert_eq!(
+        client.get_vault_state(&setup.id).unwrap().status,
+        VaultStatus::Failed
+    );
+    assert_eq!(
+        client.try_attest(&setup.id, &setup.witnesses[0]),
+        Err(Ok(Error::AlreadySettled))
+    );
+    assert_eq!(client.get_attestations(&setup.id).len(), 2);
+}
+
+#[test]
+fn test_invalid_witness_lists_are_rejected() {
+    let ctx = VaultTestContext::new();
+    let witness = Address::generate(&ctx.env);
+    let too_many: [Address; MAX_WITNESSES as usize + 1] =
+        core::array::from_fn(|_| Address::generate(&ctx.env));
+
+    for witnesses in [
+        Vec::new(&ctx.env),
+        vec![&ctx.env, witness.clone(), witness.clone()],
+        vec![&ctx.env, witness.clone(), ctx.creator.clone()],
+        Vec::from_array(&ctx.env, too_many),
+    ] {
+        assert_eq!(try_create(&ctx, witnesses), Err(Error::InvalidParticipants));
+    }
+}
@@ -0,0 +1,415 @@
+//! Routing escrow through a yield adapter, against a mock adapter.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::Address as _, token, Address, Env, IntoVal,
+    Symbol,
+};
+
+use crate::config::Config;
+use crate::events::YIELD_REDEEMED;
+use crate::installment::InstallmentSchedule;
+use crate::privacy::Visibility;
+use crate::testutils::VaultTestContext;
+use crate::yield_adapter::{ShortfallPolicy, YieldPosition};
+use crate::{Error, VaultOptions, VaultStatus};
+
+#[contracttype]
+enum Key {
+    Token,
+    Position(Address),
+    /// Amount every `withdraw` keeps back while still debiting it, like a lossy strategy.
+    Withheld,
+}
+
+/// Minimal adapter: keeps deposits as plain token balances and tracks positions by owner.
+/// `accrue` and `slash` move a position's value to simulate yield and losses.
+#[contract]
+struct MockAdapter;
+
+#[contractimpl]
+impl MockAdapter {
+    pub fn init(env: Env, token: Address) {
+        env.storage().instance().set(&Key::Token, &token);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let balance = Self::balance_of(env.clone(), from.clone());
+        set_position(&env, &from, balance + amount);
+    }
+
+    pub fn withdraw(env: Env, owner: Address, amount: i128) {
+        owner.require_auth();
+        let balance = Self::balance_of(env.clone(), owner.clone());
+        let amount = amount.min(balance);
+        set_position(&env, &owner, balance - amount);
+        let withheld: i128 = env.storage().instance().get(&Key::Withheld).unwrap_or(0);
+        let token: Address = env.storage().instance().get(&Key::Token).unwrap();
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &owner,
+            &(amount - withheld).max(0),
+        );
+    }
+
+    pub fn balance_of(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&Key::Position(owner))
+            .unwrap_or(0)
+    }
+
+    /// Credit yield; the test mints the matching tokens to the adapter.
+    pub fn accrue(env: Env, owner: Address, amount: i128) {
+        let balance = Self::balance_of(env.clone(), owner.clone());
+        set_position(&env, &owner, balance + amount);
+    }
+
+    pub fn slash(env: Env, owner: Address, amount: i128) {
+        let balance = Self::balance_of(env.clone(), owner.clone());
+        set_position(&env, &owner, balance - amount);
+    }
+
+    pub fn withhold(env: Env, amount: i128) {
+        env.storage().instance().set(&Key::Withheld, &amount);
+    }
+}
+
+fn set_position(env: &Env, owner: &Address, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&Key::Position(owner.clone()), &amount);
+}
+
+/// Half of the yield goes to the creator on completion.
+const CREATOR_YIELD_BPS: u32 = 5_000;
+const YIELD: i128 = 1_000;
+
+struct Setup {
+    ctx: VaultTestContext,
+    adapter: Address,
+}
+
+impl Setup {
+    fn new(yield_shortfall: ShortfallPolicy) -> Self {
+        let ctx = VaultTestContext::new();
+        ctx.client().set_config(&Config {
+            creator_yield_bps: CREATOR_YIELD_BPS,
+            yield_shortfall,
+            ..Config::default()
+        });
+        let adapter = ctx.env.register(MockAdapter, ());
+        MockAdapterClient::new(&ctx.env, &adapter).init(&ctx.usdc_token);
+        ctx.client()
+            .set_yield_adapter(&ctx.usdc_token, &Some(adapter.clone()));
+        Setup { ctx, adapter }
+    }
+
+    fn adapter(&self) -> MockAdapterClient<'_> {
+        MockAdapterClient::new(&self.ctx.env, &self.adapter)
+    }
+
+    fn earn(&self, amount: i128) {
+        self.ctx.mint(&self.adapter, amount);
+        self.adapter().accrue(&self.ctx.contract_id, &amount);
+    }
+
+    fn complete(&self, id: u32) {
+        let ctx = &self.ctx;
+        ctx.advance_to_start();
+        ctx.client().validate_milestone(&id);
+        ctx.client().release_funds(&id, &ctx.usdc_token);
+    }
+}
+
+#[test]
+fn test_stake_is_deposited_and_yield_shared_on_completion() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let id = ctx.create_default_vault();
+    ctx.assert_balances(&[(&ctx.contract_id, 0), (&setup.adapter, ctx.amount)]);
+    assert_eq!(setup.adapter().balance_of(&ctx.contract_id), ctx.amount);
+
+    setup.earn(YIELD);
+    setup.complete(id);
+    let (topics, data) = ctx.vault_events().get(1).unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&ctx.env, YIELD_REDEEMED),
+            id,
+            ctx.creator.clone()
+        )
+            .into_val(&ctx.env)
+    );
+    let redeemed: (i128, i128, i128) = data.into_val(&ctx.env);
+    assert_eq!(redeemed, (ctx.amount, ctx.amount + YIELD, YIELD / 2));
+
+    ctx.assert_balances(&[
+        (&ctx.success_dest, ctx.amount),
+        (&ctx.creator, YIELD / 2),
+        (&ctx.contract_id, YIELD / 2),
+        (&setup.adapter, 0),
+    ]);
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), YIELD / 2);
+    assert_eq!(
+        ctx.client().get_yield_position(&id),
+        Some(YieldPosition {
+            adapter: setup.adapter.clone(),
+            principal: ctx.amount,
+            redeemed: Some(ctx.amount + YIELD),
+            creator_yield: YIELD / 2,
+        })
+    );
+}
+
+#[test]
+fn test_failed_vault_yield_goes_to_the_protocol() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let id = ctx.create_default_vault();
+    setup.earn(YIELD);
+    ctx.advance_to_deadline();
+    ctx.client().redirect_funds(&id, &ctx.usdc_token);
+
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, ctx.amount),
+        (&ctx.creator, 0),
+        (&ctx.contract_id, YIELD),
+    ]);
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), YIELD);
+}
+
+#[test]
+fn test_renewal_keeps_the_position_deposited() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            auto_renew: true,
+            renewal_period: ctx.end_timestamp - ctx.start_timestamp,
+            ..VaultOptions::default()
+        },
+    );
+    setup.earn(YIELD);
+    setup.complete(id);
+
+    let successor = client.get_successor(&id).unwrap();
+    assert_eq!(client.get_yield_position(&id), None);
+    assert_eq!(
+        client.get_yield_position(&successor).unwrap().principal,
+        ctx.amount
+    );
+    ctx.assert_balances(&[(&setup.adapter, ctx.amount + YIELD), (&ctx.contract_id, 0)]);
+
+    // The successor redeems the position, yield included, when it finally pays out.
+    client.set_auto_renew(&successor, &false);
+    ctx.advance_to(ctx.end_timestamp);
+    client.validate_milestone(&successor);
+    client.release_funds(&successor, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.success_dest, ctx.amount),
+        (&ctx.creator, YIELD / 2),
+        (&setup.adapter, 0),
+    ]);
+}
+
+#[test]
+fn test_snooze_fee_is_withdrawn_from_the_adapter() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    client.set_config(&Config {
+        creator_yield_bps: CREATOR_YIELD_BPS,
+        max_snooze_seconds: 100,
+        snooze_fee_bps: 1_000,
+        ..Config::default()
+    });
+    let id = ctx.create_default_vault();
+    let fee = ctx.amount / 10;
+
+    client.snooze(&id, &100, &ctx.usdc_token);
+    ctx.assert_balances(&[
+        (&ctx.failure_dest, fee),
+        (&ctx.contract_id, 0),
+        (&setup.adapter, ctx.amount - fee),
+    ]);
+    assert_eq!(
+        client.get_yield_position(&id).unwrap().principal,
+        ctx.amount - fee
+    );
+
+    setup.complete(id);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount - fee), (&setup.adapter, 0)]);
+}
+
+#[test]
+fn test_pooled_yield_is_split_by_principal() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let first = ctx.create_default_vault();
+    ctx.mint(&ctx.creator, 3 * ctx.amount);
+    ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &(3 * ctx.amount),
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &None,
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions::default(),
+    );
+    setup.earn(4 * YIELD);
+
+    setup.complete(first);
+    assert_eq!(
+        ctx.client().get_yield_position(&first).unwrap().redeemed,
+        Some(ctx.amount + YIELD)
+    );
+    ctx.assert_balances(&[(&setup.adapter, 3 * (ctx.amount + YIELD))]);
+}
+
+#[test]
+fn test_shortfall_reverts_by_default() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let id = ctx.create_default_vault();
+    setup.adapter().slash(&ctx.contract_id, &YIELD);
+
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    assert_eq!(
+        ctx.client().try_release_funds(&id, &ctx.usdc_token),
+        Err(Ok(Error::YieldShortfall))
+    );
+    let vault = ctx.client().get_vault_state(&id).unwrap();
+    assert_eq!(
+        (vault.status, vault.amount),
+        (VaultStatus::Active, ctx.amount)
+    );
+    ctx.assert_balances(&[(&setup.adapter, ctx.amount), (&ctx.success_dest, 0)]);
+}
+
+#[test]
+fn test_shortfall_haircut_settles_what_came_back() {
+    let setup = Setup::new(ShortfallPolicy::Haircut);
+    let ctx = &setup.ctx;
+    let id = ctx.create_default_vault();
+    // The adapter reports the full balance but pays out less: the contract trusts only
+    // what actually arrives.
+    setup.adapter().withhold(&YIELD);
+
+    ctx.client().cancel_vault(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.creator, ctx.amount - YIELD), (&ctx.contract_id, 0)]);
+    assert_eq!(
+        ctx.client().get_vault_state(&id).unwrap().amount,
+        ctx.amount - YIELD
+    );
+    assert_eq!(ctx.client().get_accrued_fees(&ctx.usdc_token), 0);
+}
+
+#[test]
+fn test_registration_is_admin_only_and_not_retroactive() {
+    let ctx = VaultTestContext::new();
+    let adapter = Address::generate(&ctx.env);
+    let id = ctx.create_default_vault();
+    ctx.client()
+        .set_yield_adapter(&ctx.usdc_token, &Some(adapter.clone()));
+    assert_eq!(ctx.env.auths()[0].0, ctx.admin);
+    assert_eq!(
+        ctx.client().get_yield_adapter(&ctx.usdc_token),
+        Some(adapter)
+    );
+    assert_eq!(ctx.client().get_yield_position(&id), None);
+
+    ctx.advance_to_start();
+    ctx.client().validate_milestone(&id);
+    ctx.client().release_funds(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount)]);
+
+    ctx.client().set_yield_adapter(&ctx.usdc_token, &None);
+    assert_eq!(ctx.client().get_yield_adapter(&ctx.usdc_token), None);
+}
+
+#[test]
+fn test_installments_are_deposited_into_the_adapter() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let client = ctx.client();
+    let deposit = ctx.amount / 2;
+    let id = client.create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &deposit,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            installments: InstallmentSchedule {
+                count: 2,
+                amount: deposit,
+                interval: 100,
+                grace_period: 0,
+            },
+            ..VaultOptions::default()
+        },
+    );
+
+    client.pay_installment(&id, &ctx.usdc_token);
+    ctx.assert_balances(&[(&ctx.contract_id, 0), (&setup.adapter, ctx.amount)]);
+    assert_eq!(
+        client.get_yield_position(&id).unwrap().principal,
+        ctx.amount
+    );
+
+    setup.complete(id);
+    ctx.assert_balances(&[(&ctx.success_dest, ctx.amount), (&setup.adapter, 0)]);
+}
+
+#[test]
+fn test_private_vault_position_omits_the_amounts() {
+    let setup = Setup::new(ShortfallPolicy::Revert);
+    let ctx = &setup.ctx;
+    let id = ctx.client().create_vault(
+        &ctx.usdc_token,
+        &ctx.creator,
+        &ctx.amount,
+        &ctx.start_timestamp,
+        &ctx.end_timestamp,
+        &ctx.milestone_hash(),
+        &Some(ctx.verifier.clone()),
+        &ctx.success_dest,
+        &ctx.failure_dest,
+        &VaultOptions {
+            visibility: Visibility::Private,
+            ..VaultOptions::default()
+        },
+    );
+    setup.earn(YIELD);
+    setup.complete(id);
+
+    assert_eq!(
+        ctx.client().get_yield_position(&id),
+        Some(YieldPosition {
+            adapter: setup.adapter.clone(),
+            principal: 0,
+            redeemed: Some(0),
+            creator_yield: 0,
+        })
+    );
+}
@@ -0,0 +1,189 @@
+//! Test scaffolding for contracts and bots that integrate with `DisciplrVault`.
+//!
+//! Enabled with the `testutils` cargo feature (and always available to this crate's own
+//! tests). It never ships in the contract WASM: the module is compiled only under
+//! `cfg(any(test, feature = "testutils"))`.
+//!
+//! ```ignore
+//! let ctx = VaultTestContext::new();
+//! ctx.advance_to_start();
+//! let vault_id = ctx.create_default_vault();
+//! ctx.advance_past_deadline();
+//! ctx.client().redirect_funds(&vault_id, &ctx.usdc_token);
+//! ctx.assert_balances(&[(&ctx.failure_dest, ctx.amount), (&ctx.contract_id, 0)]);
+//! ```
+
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, Val, Vec,
+};
+
+use crate::{DisciplrVault, DisciplrVaultClient, VaultOptions};
+
+/// A ready-to-use environment: the vault contract registered with an admin, a Stellar Asset
+/// Contract standing in for USDC, a funded creator and the usual counterparties.
+///
+/// All auths are mocked, so every entrypoint can be called directly through `client()`.
+pub struct VaultTestContext {
+    pub env: Env,
+    /// Address of the registered `DisciplrVault` contract.
+    pub contract_id: Address,
+    /// Address of the Stellar Asset Contract used as USDC.
+    pub usdc_token: Address,
+    /// Admin of the USDC asset; used by `mint`.
+    pub usdc_admin: Address,
+    /// Admin the vault contract was deployed with.
+    pub admin: Address,
+    pub creator: Address,
+    pub verifier: Address,
+    pub success_dest: Address,
+    pub failure_dest: Address,
+    /// Stake used by the default vault; minted to `creator` by `new`.
+    pub amount: i128,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+}
+
+impl Default for VaultTestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VaultTestContext {
+    /// Fresh `Env` with mocked auths, the contract and token registered and `amount` (1 USDC)
+    /// minted to `creator`. Default window is `[100, 1_000)`.
+    pub fn new() -> Self {
+        Self::with_env(Env::default())
+    }
+
+    /// Same as `new`, on a caller-supplied `Env` (e.g. one built with a custom
+    /// `EnvTestConfig`).
+    pub fn with_env(env: Env) -> Self {
+        env.mock_all_auths();
+
+        let usdc_admin = Address::generate(&env);
+        let usdc_token = env
+            .register_stellar_asset_contract_v2(usdc_admin.clone())
+            .address();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(DisciplrVault, (admin.clone(),));
+
+        let ctx = VaultTestContext {
+            creator: Address::generate(&env),
+            verifier: Address::generate(&env),
+            success_dest: Address::generate(&env),
+            failure_dest: Address::generate(&env),
+            env,
+            contract_id,
+            usdc_token,
+            usdc_admin,
+            admin,
+            amount: 1_000_000, // 1 USDC (6 decimals)
+            start_timestamp: 100,
+            end_timestamp: 1_000,
+        };
+        ctx.mint(&ctx.creator, ctx.amount);
+        ctx
+    }
+
+    pub fn client(&self) -> DisciplrVaultClient<'_> {
+        DisciplrVaultClient::new(&self.env, &self.contract_id)
+    }
+
+    pub fn usdc_client(&self) -> TokenClient<'_> {
+        TokenClient::new(&self.env, &self.usdc_token)
+    }
+
+    /// Mint `amount` USDC to `to`.
+    pub fn mint(&self, to: &Address, amount: i128) {
+        StellarAssetClient::new(&self.env, &self.usdc_token).mint(to, &amount);
+    }
+
+    pub fn milestone_hash(&self) -> BytesN<32> {
+        BytesN::from_array(&self.env, &[1u8; 32])
+    }
+
+    /// Create a vault with the context's defaults and `verifier` as the designated verifier.
+    pub fn create_default_vault(&self) -> u32 {
+        self.client().create_vault(
+            &self.usdc_token,
+            &self.creator,
+            &self.amount,
+            &self.start_timestamp,
+            &self.end_timestamp,
+            &self.milestone_hash(),
+            &Some(self.verifier.clone()),
+            &self.success_dest,
+            &self.failure_dest,
+            &VaultOptions::default(),
+        )
+    }
+
+    /// Create vault with verifier = None (only creator can validate).
+    pub fn create_vault_no_verifier(&self) -> u32 {
+        self.client().create_vault(
+            &self.usdc_token,
+            &self.creator,
+            &self.amount,
+            &self.start_timestamp,
+            &self.end_timestamp,
+            &self.milestone_hash(),
+            &None,
+            &self.success_dest,
+            &self.failure_dest,
+            &VaultOptions::default(),
+        )
+    }
+
+    /// Set the ledger timestamp.
+    pub fn advance_to(&self, timestamp: u64) {
+        self.env.ledger().set_timestamp(timestamp);
+    }
+
+    /// Set the ledger timestamp to `start_timestamp`.
+    pub fn advance_to_start(&self) {
+        self.advance_to(self.start_timestamp);
+    }
+
+    /// Set the ledger timestamp to exactly `end_timestamp`: validation is closed and
+    /// deadline-based settlement is open.
+    pub fn advance_to_deadline(&self) {
+        self.advance_to(self.end_timestamp);
+    }
+
+    /// Set the ledger timestamp to one second past `end_timestamp`.
+    pub fn advance_past_deadline(&self) {
+        self.advance_to(self.end_timestamp + 1);
+    }
+
+    /// Assert the USDC balance of every `(address, expected)` pair.
+    pub fn assert_balances(&self, expected: &[(&Address, i128)]) {
+        let usdc = self.usdc_client();
+        for (address, amount) in expected {
+            assert_eq!(usdc.balance(address), *amount, "unexpected USDC balance");
+        }
+    }
+
+    /// `(topics, data)` of every event the vault contract published during the last
+    /// invocation, in order. Token events are filtered out.
+    pub fn vault_events(&self) -> Vec<(Vec<Val>, Val)> {
+        let mut events = Vec::new(&self.env);
+        for (contract, topics, data) in self.env.events().all().iter() {
+            if contract == self.contract_id {
+                events.push_back((topics, data));
+            }
+        }
+        events
+    }
+
+    /// Assert that the last invocation published no events at all (from any contract).
+    pub fn assert_no_events(&self) {
+        assert!(
+            self.env.events().all().is_empty(),
+            "expected no events from the last invocation"
+        );
+    }
+}
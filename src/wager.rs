@@ -0,0 +1,272 @@
+//! Peer wagers: a creator and a challenger stake the same amount on opposite sides of one
+//! milestone, judged by a shared verifier.
+//!
+//! `create_wager` escrows the creator's stake and opens the vault `Pending`, with the
+//! creator as success destination and the challenger as failure destination. The challenger
+//! matches it with `accept_wager` before the funding deadline (`start_timestamp` unless set
+//! earlier, see `funding`), which activates the vault; if the window closes first, anyone
+//! may call `reclaim_wager` to refund the creator and cancel it. Once active the pot settles
+//! as follows:
+//!
+//! | outcome                                                | status    | pot goes to      |
+//! |--------------------------------------------------------|-----------|------------------|
+//! | `validate_milestone`, then `release_funds`             | Completed | creator          |
+//! | `reject_wager`, or `redirect_funds` after the deadline | Failed    | challenger       |
+//! | `declare_draw`                                         | Cancelled | each their stake |
+//!
+//! Unlike other vaults, a wager is never released just because its deadline passed, its
+//! destinations are locked, and neither party can `cancel_vault` it.
+
+use soroban_sdk::{contractimpl, contracttype, token, vec, Address, BytesN, Env, Vec};
+
+use crate::privacy::Visibility;
+use crate::savings::VaultMode;
+use crate::{
+    callback, category, check_amount, check_balance, check_new_vault, check_token, events, funding,
+    load_vault, settle, store_new_vault, streak, transition, yield_adapter, DataKey, DisciplrVault,
+    DisciplrVaultArgs, DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wager {
+    pub challenger: Address,
+    /// What each side stakes.
+    pub stake: i128,
+    /// Whether the challenger has matched the stake.
+    pub accepted: bool,
+}
+
+fn load_wager(env: &Env, vault_id: u32) -> Option<Wager> {
+    env.storage().instance().get(&DataKey::Wager(vault_id))
+}
+
+pub(crate) fn is_wager(env: &Env, vault_id: u32) -> bool {
+    env.storage().instance().has(&DataKey::Wager(vault_id))
+}
+
+/// What each side of a wager stakes, or `None` for other vaults.
+pub(crate) fn stake(env: &Env, vault_id: u32) -> Option<i128> {
+    load_wager(env, vault_id).map(|wager| wager.stake)
+}
+
+/// Each side's own stake, for refunding an accepted wager. `None` for other vaults and for
+/// wagers nobody accepted, whose whole escrow is the creator's.
+pub(crate) fn stakes(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+) -> Option<Vec<(Address, i128)>> {
+    let wager = load_wager(env, vault_id).filter(|wager| wager.accepted)?;
+    Some(vec![
+        env,
+        (vault.creator.clone(), wager.stake),
+        (wager.challenger, wager.stake),
+    ])
+}
+
+/// Common checks for the verifier's rulings: an Active, not yet validated wager whose
+/// deadline has not passed, authorized by its verifier.
+fn load_for_ruling(env: &Env, vault_id: u32) -> Result<(ProductivityVault, Wager), Error> {
+    let vault = load_vault(env, vault_id)?;
+    let wager = load_wager(env, vault_id).ok_or(Error::InvalidStatus)?;
+    if vault.status != VaultStatus::Active {
+        return Err(Error::VaultNotActive);
+    }
+    vault.validator().require_auth();
+    if !vault.validation_open(env.ledger().timestamp()) {
+        return Err(Error::MilestoneExpired);
+    }
+    if vault.milestone_validated {
+        return Err(Error::InvalidStatus);
+    }
+    Ok((vault, wager))
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Offer `challenger` a wager of `amount` each on the milestone, judged by `verifier`,
+    /// and escrow the creator's stake.
+    ///
+    /// Fails with `InvalidWagerParties` when the challenger is the creator or the verifier is
+    /// either party, `InvalidTimestamp` when `start_timestamp` has already passed (there
+    /// would be no time to accept), and otherwise validates like `create_vault`.
+    pub fn create_wager(
+        env: Env,
+        usdc_token: Address,
+        creator: Address,
+        challenger: Address,
+        amount: i128,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        milestone_hash: BytesN<32>,
+        verifier: Address,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+
+        if challenger == creator || verifier == creator || verifier == challenger {
+            return Err(Error::InvalidWagerParties);
+        }
+        if env.ledger().timestamp() >= start_timestamp {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let vault = ProductivityVault {
+            creator: creator.clone(),
+            amount,
+            token: usdc_token.clone(),
+            start_timestamp,
+            end_timestamp,
+            milestone_hash,
+            verifier: Some(verifier),
+            success_destination: creator,
+            failure_destination: challenger.clone(),
+            status: VaultStatus::Pending,
+            milestone_validated: false,
+            referrer: None,
+            accountability_partner: None,
+            destinations_locked: true,
+            reveal_required: false,
+            category: category::DEFAULT_CATEGORY,
+            allow_graded: false,
+            visibility: Visibility::Public,
+            auto_renew: false,
+            mode: VaultMode::Standard,
+        };
+        check_new_vault(&env, &usdc_token, &vault)?;
+        let vault_id = store_new_vault(&env, &vault)?;
+        let wager = Wager {
+            challenger,
+            stake: amount,
+            accepted: false,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Wager(vault_id), &wager);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &vault.creator,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        events::vault_created(&env, vault_id, &vault);
+        Ok(vault_id)
+    }
+
+    /// Challenger matches the creator's stake before the funding deadline, activating the
+    /// wager. Returns `true`.
+    ///
+    /// Fails with `InvalidStatus` unless the vault is a Pending wager, `NotAuthorized` for a
+    /// token other than the creator's stake and `InvalidTimestamp` once the window has closed.
+    pub fn accept_wager(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let mut vault = load_vault(&env, vault_id)?;
+        let mut wager = load_wager(&env, vault_id).ok_or(Error::InvalidStatus)?;
+        wager.challenger.require_auth();
+        if vault.status != VaultStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        check_token(&vault, &usdc_token)?;
+        if funding::closed(&env, vault_id, &vault) {
+            return Err(Error::InvalidTimestamp);
+        }
+        let pot = vault
+            .amount
+            .checked_add(wager.stake)
+            .ok_or(Error::ArithmeticOverflow)?;
+        check_amount(pot)?;
+        check_balance(&env, &usdc_token, &wager.challenger, wager.stake)?;
+
+        vault.amount = pot;
+        category::record_stake(&env, &vault, wager.stake)?;
+        wager.accepted = true;
+        transition(
+            &env,
+            vault_id,
+            &mut vault,
+            VaultStatus::Active,
+            wager.challenger.clone(),
+        )?;
+        env.storage()
+            .instance()
+            .set(&DataKey::Wager(vault_id), &wager);
+
+        token::Client::new(&env, &usdc_token).transfer(
+            &wager.challenger,
+            &env.current_contract_address(),
+            &wager.stake,
+        );
+
+        events::wager_accepted(&env, vault_id, &wager.challenger, wager.stake);
+        events::vault_activated(&env, vault_id, &vault.creator, vault.amount);
+        Ok(true)
+    }
+
+    /// Refund the creator of a wager nobody accepted before its funding deadline, and cancel
+    /// it. Permissionless; `expire_unfunded` restricted to wagers.
+    ///
+    /// Fails with `InvalidTimestamp` while the window is open, `AlreadySettled` once the
+    /// wager was cancelled and `InvalidStatus` for accepted wagers and other vaults.
+    pub fn reclaim_wager(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        load_vault(&env, vault_id)?;
+        if !is_wager(&env, vault_id) {
+            return Err(Error::InvalidStatus);
+        }
+        funding::expire(&env, vault_id, &usdc_token)
+    }
+
+    /// Verifier rules against the creator before the deadline: the pot goes to the
+    /// challenger now. Fails with `InvalidStatus` for a validated wager or another vault,
+    /// `VaultNotActive` unless Active and `MilestoneExpired` at or past the deadline, where
+    /// `redirect_funds` pays the challenger anyway.
+    pub fn reject_wager(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let (mut vault, wager) = load_for_ruling(&env, vault_id)?;
+        let verifier = vault.validator().clone();
+        let parts = settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &vec![&env, (wager.challenger.clone(), 1)],
+            VaultStatus::Failed,
+            verifier.clone(),
+        )?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::wager_rejected(&env, vault_id, &verifier);
+        events::funds_redirected(&env, vault_id, &wager.challenger, &parts);
+        yield_adapter::publish(&env, vault_id, &vault);
+        streak::publish_update(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// Verifier declares neither side the winner: both parties get their stake back and the
+    /// wager is cancelled. Fails like `reject_wager`.
+    pub fn declare_draw(env: Env, vault_id: u32, usdc_token: Address) -> Result<bool, Error> {
+        let (mut vault, _) = load_for_ruling(&env, vault_id)?;
+        let verifier = vault.validator().clone();
+        let refunds = stakes(&env, vault_id, &vault).ok_or(Error::InvalidStatus)?;
+        settle(
+            &env,
+            vault_id,
+            &mut vault,
+            &usdc_token,
+            &refunds,
+            VaultStatus::Cancelled,
+            verifier.clone(),
+        )?;
+        let notification = callback::notify(&env, vault_id, &vault);
+
+        events::wager_drawn(&env, vault_id, &verifier);
+        events::vault_cancelled(&env, vault_id, &vault.creator, vault.amount);
+        yield_adapter::publish(&env, vault_id, &vault);
+        callback::publish(&env, vault_id, &vault, notification);
+        Ok(true)
+    }
+
+    /// Challenger and stake of a wager, or `None` for other vaults.
+    pub fn get_wager(env: Env, vault_id: u32) -> Option<Wager> {
+        load_wager(&env, vault_id)
+    }
+}
@@ -0,0 +1,136 @@
+//! Witnesses who publicly vouch for a vault's milestone.
+//!
+//! A vault created with `VaultOptions::witnesses` names up to `MAX_WITNESSES` friends, other
+//! than its creator, who may `attest` that the milestone was met. Each witness attests at
+//! most once, while the vault is Active, and the attestation is timestamped and announced
+//! with `milestone_attested`. Attestations are social proof only: they give witnesses no
+//! say over validation or settlement. `get_attestations` returns them in the order they
+//! were made. A renewed vault's successor keeps the witness list but starts without
+//! attestations.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::{
+    events, load_vault, privacy, require_unsettled, DataKeyExt, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault,
+};
+
+/// Longest witness list a vault can have.
+pub const MAX_WITNESSES: u32 = 10;
+
+/// A witness's attestation as returned by `get_attestations`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub witness: Address,
+    /// Ledger timestamp of the attestation.
+    pub timestamp: u64,
+}
+
+fn witnesses(env: &Env, vault_id: u32) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::Witnesses(vault_id))
+        .unwrap_or(Vec::new(env))
+}
+
+fn attestations(env: &Env, vault_id: u32) -> Vec<Attestation> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::Attestations(vault_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Record the witnesses of a new vault. An empty or overlong list, a duplicate or the
+/// creator as a witness fail with `InvalidParticipants`.
+pub(crate) fn register(
+    env: &Env,
+    vault_id: u32,
+    vault: &ProductivityVault,
+    witnesses: Option<Vec<Address>>,
+) -> Result<(), Error> {
+    let Some(witnesses) = witnesses else {
+        return Ok(());
+    };
+    if witnesses.is_empty() || witnesses.len() > MAX_WITNESSES {
+        return Err(Error::InvalidParticipants);
+    }
+    for (i, witness) in witnesses.iter().enumerate() {
+        if witness == vault.creator || witnesses.first_index_of(&witness) != Some(i as u32) {
+            return Err(Error::InvalidParticipants);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::Witnesses(vault_id), &witnesses);
+    Ok(())
+}
+
+/// Give a renewed vault's successor the same witnesses.
+pub(crate) fn inherit(env: &Env, from: u32, to: u32) {
+    let witnesses = witnesses(env, from);
+    if !witnesses.is_empty() {
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Witnesses(to), &witnesses);
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Witness vouches that the milestone of an Active vault was met. Has no effect on
+    /// settlement.
+    ///
+    /// Fails with `NotAuthorized` for an address not on the vault's witness list,
+    /// `AlreadyJoined` when the witness already attested, and `AlreadySettled` (or
+    /// `VaultNotActive` while Pending) outside the vault's active life.
+    pub fn attest(env: Env, vault_id: u32, witness: Address) -> Result<bool, Error> {
+        let vault = load_vault(&env, vault_id)?;
+        require_unsettled(&vault)?;
+        witness.require_auth();
+        if !witnesses(&env, vault_id).contains(&witness) {
+            return Err(Error::NotAuthorized);
+        }
+        let mut attestations = attestations(&env, vault_id);
+        if attestations.iter().any(|a| a.witness == witness) {
+            return Err(Error::AlreadyJoined);
+        }
+
+        attestations.push_back(Attestation {
+            witness: witness.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::Attestations(vault_id), &attestations);
+
+        events::milestone_attested(&env, vault_id, &witness);
+        Ok(true)
+    }
+
+    /// Witnesses a vault was created with; empty for vaults without any, and for private
+    /// vaults.
+    pub fn get_witnesses(env: Env, vault_id: u32) -> Vec<Address> {
+        if privacy::is_private(&env, vault_id) {
+            return Vec::new(&env);
+        }
+        witnesses(&env, vault_id)
+    }
+
+    /// Every attestation made on a vault, oldest first. Private vaults report this contract
+    /// as the witness of each.
+    pub fn get_attestations(env: Env, vault_id: u32) -> Vec<Attestation> {
+        let attestations = attestations(&env, vault_id);
+        if !privacy::is_private(&env, vault_id) {
+            return attestations;
+        }
+        let mut redacted = Vec::new(&env);
+        for attestation in attestations.iter() {
+            redacted.push_back(Attestation {
+                witness: env.current_contract_address(),
+                ..attestation
+            });
+        }
+        redacted
+    }
+}
@@ -0,0 +1,306 @@
+//! Putting escrow to work in an external yield adapter.
+//!
+//! The admin registers at most one `YieldAdapter` per token with `set_yield_adapter`. A vault
+//! opened through `create_vault` in a token that has an adapter deposits its stake there
+//! right after it is pulled from the creator, and the vault remembers that adapter even if
+//! the registration later changes. With no adapter registered nothing is deposited and the
+//! stake stays in this contract, exactly as before.
+//!
+//! All positions in one adapter are pooled under this contract's address. At settlement a
+//! vault withdraws its pro-rata share of the pool, `balance_of * principal / total
+//! principal`, and what actually arrived (measured as this contract's token balance change,
+//! not taken from the adapter's word) is split as follows:
+//!
+//! - Up to `principal` is the escrow, settled as usual.
+//! - Anything above it is yield. On completion `Config::creator_yield_bps` of it goes to the
+//!   creator; the rest, and all yield of failed or cancelled vaults, accrues to the protocol
+//!   next to the fees (see `fees::withdraw_fees`).
+//! - Less than `principal` is a shortfall, handled by `Config::yield_shortfall`: `Revert`
+//!   fails the settlement with `YieldShortfall` and leaves the vault untouched, `Haircut`
+//!   settles only what came back.
+
+use soroban_sdk::{contractclient, contractimpl, contracttype, token, Address, Env};
+
+use crate::{
+    config, events, fees, payout, privacy, DataKey, DisciplrVault, DisciplrVaultArgs,
+    DisciplrVaultClient, Error, ProductivityVault, VaultStatus,
+};
+
+/// Interface a yield adapter must expose. An adapter handles a single token.
+#[contractclient(name = "YieldAdapterClient")]
+pub trait YieldAdapter {
+    /// Credit `amount`, already transferred to the adapter, to `from`'s position.
+    fn deposit(env: Env, from: Address, amount: i128);
+    /// Send up to `amount` from `owner`'s position back to `owner`.
+    fn withdraw(env: Env, owner: Address, amount: i128);
+    /// Current value of `owner`'s position, yield included.
+    fn balance_of(env: Env, owner: Address) -> i128;
+}
+
+/// What to do when an adapter returns less than a vault deposited.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ShortfallPolicy {
+    /// Fail the settlement with `YieldShortfall`.
+    #[default]
+    Revert = 0,
+    /// Settle whatever the adapter returned.
+    Haircut = 1,
+}
+
+/// A vault's deposit in an adapter.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldPosition {
+    pub adapter: Address,
+    /// Stake deposited at creation.
+    pub principal: i128,
+    /// Amount the adapter returned at settlement; `None` while still deposited.
+    pub redeemed: Option<i128>,
+    /// Part of the yield paid to the creator.
+    pub creator_yield: i128,
+}
+
+/// Escrow and creator payout of a redeemed position, for `settle`.
+pub(crate) struct Redemption {
+    /// Amount to settle: the principal, or less after a haircut.
+    pub escrow: i128,
+    pub creator_yield: i128,
+}
+
+fn position(env: &Env, vault_id: u32) -> Option<YieldPosition> {
+    env.storage()
+        .instance()
+        .get(&DataKey::YieldPosition(vault_id))
+}
+
+fn pooled_principal(env: &Env, adapter: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::YieldPrincipal(adapter.clone()))
+        .unwrap_or(0)
+}
+
+fn set_pooled_principal(env: &Env, adapter: &Address, principal: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::YieldPrincipal(adapter.clone()), &principal);
+}
+
+/// Book a deposit of `amount` for a new vault if `usdc_token` has an adapter. Returns the
+/// adapter the caller must `deposit` into once the stake has been pulled.
+pub(crate) fn open_position(
+    env: &Env,
+    vault_id: u32,
+    usdc_token: &Address,
+    amount: i128,
+) -> Result<Option<Address>, Error> {
+    let Some(adapter) = env
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::YieldAdapter(usdc_token.clone()))
+    else {
+        return Ok(None);
+    };
+    let pooled = pooled_principal(env, &adapter)
+        .checked_add(amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    set_pooled_principal(env, &adapter, pooled);
+    env.storage().instance().set(
+        &DataKey::YieldPosition(vault_id),
+        &YieldPosition {
+            adapter: adapter.clone(),
+            principal: amount,
+            redeemed: None,
+            creator_yield: 0,
+        },
+    );
+    Ok(Some(adapter))
+}
+
+/// Move `amount` held by this contract into `adapter`.
+pub(crate) fn deposit(env: &Env, usdc_token: &Address, adapter: &Address, amount: i128) {
+    let contract = env.current_contract_address();
+    token::Client::new(env, usdc_token).transfer(&contract, adapter, &amount);
+    YieldAdapterClient::new(env, adapter).deposit(&contract, &amount);
+}
+
+/// Move an undrawn position from a renewed vault to its successor. No adapter call: the
+/// deposit simply stays where it is.
+pub(crate) fn carry_over(env: &Env, from: u32, to: u32) {
+    if let Some(position) = position(env, from) {
+        env.storage()
+            .instance()
+            .remove(&DataKey::YieldPosition(from));
+        env.storage()
+            .instance()
+            .set(&DataKey::YieldPosition(to), &position);
+    }
+}
+
+/// Book `amount` more principal for a vault that deposited, e.g. a later installment.
+/// Returns the adapter the caller must `deposit` into once the funds have been pulled.
+pub(crate) fn top_up(env: &Env, vault_id: u32, amount: i128) -> Result<Option<Address>, Error> {
+    let Some(mut position) = position(env, vault_id) else {
+        return Ok(None);
+    };
+    let pooled = pooled_principal(env, &position.adapter)
+        .checked_add(amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    set_pooled_principal(env, &position.adapter, pooled);
+    position.principal = position
+        .principal
+        .checked_add(amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::YieldPosition(vault_id), &position);
+    Ok(Some(position.adapter))
+}
+
+/// Take `amount` of a vault's principal back out of its adapter ahead of settlement, e.g. a
+/// fee paid from escrow. Does nothing for vaults that never deposited.
+pub(crate) fn withdraw(
+    env: &Env,
+    vault_id: u32,
+    usdc_token: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let Some(mut position) = position(env, vault_id) else {
+        return Ok(());
+    };
+    let contract = env.current_contract_address();
+    let usdc = token::Client::new(env, usdc_token);
+    let before = usdc.balance(&contract);
+    YieldAdapterClient::new(env, &position.adapter).withdraw(&contract, &amount);
+    if usdc.balance(&contract) - before < amount {
+        return Err(Error::YieldShortfall);
+    }
+    let pooled = pooled_principal(env, &position.adapter) - amount;
+    set_pooled_principal(env, &position.adapter, pooled);
+    position.principal -= amount;
+    env.storage()
+        .instance()
+        .set(&DataKey::YieldPosition(vault_id), &position);
+    Ok(())
+}
+
+/// Withdraw a vault's position ahead of a settlement into `status`, booking the protocol's
+/// yield. Returns `None` for vaults that never deposited. The caller transfers the creator's
+/// share once the vault has transitioned.
+pub(crate) fn redeem(
+    env: &Env,
+    vault_id: u32,
+    usdc_token: &Address,
+    status: VaultStatus,
+) -> Result<Option<Redemption>, Error> {
+    let Some(mut position) = position(env, vault_id) else {
+        return Ok(None);
+    };
+    let adapter = YieldAdapterClient::new(env, &position.adapter);
+    let contract = env.current_contract_address();
+    let pooled = pooled_principal(env, &position.adapter);
+    let share = payout::mul_div_floor(
+        adapter.balance_of(&contract).max(0),
+        position.principal,
+        pooled,
+    )?;
+
+    let usdc = token::Client::new(env, usdc_token);
+    let before = usdc.balance(&contract);
+    if share > 0 {
+        adapter.withdraw(&contract, &share);
+    }
+    let redeemed = usdc
+        .balance(&contract)
+        .checked_sub(before)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    let config = config::load(env);
+    let (escrow, earned) = if redeemed >= position.principal {
+        (position.principal, redeemed - position.principal)
+    } else if config.yield_shortfall == ShortfallPolicy::Haircut {
+        (redeemed, 0)
+    } else {
+        return Err(Error::YieldShortfall);
+    };
+    let creator_yield = match status {
+        VaultStatus::Completed => payout::bps_of(earned, config.creator_yield_bps)?,
+        _ => 0,
+    };
+    fees::accrue(env, usdc_token, earned - creator_yield)?;
+
+    set_pooled_principal(env, &position.adapter, pooled - position.principal);
+    position.redeemed = Some(redeemed);
+    position.creator_yield = creator_yield;
+    env.storage()
+        .instance()
+        .set(&DataKey::YieldPosition(vault_id), &position);
+    Ok(Some(Redemption {
+        escrow,
+        creator_yield,
+    }))
+}
+
+/// Publish `yield_redeemed` for a vault whose position was just redeemed.
+pub(crate) fn publish(env: &Env, vault_id: u32, vault: &ProductivityVault) {
+    if let Some(YieldPosition {
+        principal,
+        redeemed: Some(redeemed),
+        creator_yield,
+        ..
+    }) = position(env, vault_id)
+    {
+        events::yield_redeemed(
+            env,
+            vault_id,
+            &vault.creator,
+            privacy::amount(vault, principal),
+            privacy::amount(vault, redeemed),
+            privacy::amount(vault, creator_yield),
+        );
+    }
+}
+
+#[contractimpl]
+impl DisciplrVault {
+    /// Register `adapter` for `usdc_token`, or unregister with `None`. Admin only. Only
+    /// vaults created afterwards are affected; existing positions stay where they are.
+    pub fn set_yield_adapter(
+        env: Env,
+        usdc_token: Address,
+        adapter: Option<Address>,
+    ) -> Result<(), Error> {
+        let admin = config::require_admin(&env)?;
+        let key = DataKey::YieldAdapter(usdc_token.clone());
+        match &adapter {
+            Some(adapter) => env.storage().instance().set(&key, adapter),
+            None => env.storage().instance().remove(&key),
+        }
+
+        events::yield_adapter_set(&env, &admin, &usdc_token, &adapter);
+        Ok(())
+    }
+
+    /// Adapter registered for `usdc_token`, if any.
+    pub fn get_yield_adapter(env: Env, usdc_token: Address) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::YieldAdapter(usdc_token))
+    }
+
+    /// Adapter position of a vault, or `None` if its stake was never deposited. Amounts are
+    /// zero for private vaults.
+    pub fn get_yield_position(env: Env, vault_id: u32) -> Option<YieldPosition> {
+        let position = position(&env, vault_id)?;
+        if !privacy::is_private(&env, vault_id) {
+            return Some(position);
+        }
+        Some(YieldPosition {
+            principal: 0,
+            redeemed: position.redeemed.map(|_| 0),
+            creator_yield: 0,
+            ..position
+        })
+    }
+}